@@ -9,6 +9,7 @@ import { initAbcRender } from "./features/abc_render.js";
 import { initAgent } from "./features/agent.js";
 import { initFileManage } from "./features/file_manage.js";
 import { initPalette } from "./features/palette.js";
+import { initPdfPage } from "./features/pdf_page.js";
 
 const init = () => {
     initTodoToggle();
@@ -22,6 +23,7 @@ const init = () => {
     initAgent();
     initFileManage();
     initPalette();
+    initPdfPage();
 };
 
 if (document.readyState === "loading") {
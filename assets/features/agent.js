@@ -3,6 +3,9 @@ const getDocId = () => {
     return page ? page.dataset.docId : "";
 };
 
+// Blocks pushed by the server while a form was open, shown once it closes.
+let pendingBlocks = null;
+
 const collapseAll = () => {
     for (const form of document.querySelectorAll(".agent-insert-form")) {
         form.remove();
@@ -78,6 +81,7 @@ const createForm = (block, docId) => {
     textarea.addEventListener("keydown", (e) => {
         if (e.key === "Escape") {
             collapseAll();
+            showPendingBlocks();
         } else if (e.key === "Enter" && (e.metaKey || e.ctrlKey)) {
             doSubmit();
         }
@@ -100,8 +104,9 @@ const initBottomInput = (docId) => {
     const submit = bottom.querySelector(".agent-insert-submit");
     if (!textarea || !submit) return;
 
-    const afterLine = bottom.dataset.afterLine;
     const doSubmit = () => {
+        // Read on submit: live updates move the end of the document.
+        const afterLine = bottom.dataset.afterLine;
         submitDirective(docId, afterLine, textarea.value.trim(), (disabled) => {
             submit.disabled = disabled;
             textarea.disabled = disabled;
@@ -116,40 +121,126 @@ const initBottomInput = (docId) => {
     });
 };
 
-const acceptEdit = async (btn, docId) => {
+const reviewEdit = async (edit, docId, action, fields = {}) => {
+    const body = new URLSearchParams({
+        doc_id: docId,
+        edit_index: edit.dataset.editIndex,
+        ...fields,
+    });
+    const response = await fetch(`/api/d/${action}-magent-edit`, {
+        method: "POST",
+        headers: { "Content-Type": "application/x-www-form-urlencoded" },
+        body,
+    });
+    if (response.status === 409) {
+        throw new Error(await response.text());
+    }
+    if (!response.ok) {
+        throw new Error(`Failed to ${action} edit`);
+    }
+};
+
+const appliesEdits = () => document.querySelector(".agent-page[data-apply-edits]") !== null;
+
+const setReviewDisabled = (edit, disabled) => {
+    for (const control of edit.querySelectorAll(".magent-review-actions button")) {
+        control.disabled = disabled;
+    }
+};
+
+const decideEdit = async (btn, docId, action, status, label) => {
     const edit = btn.closest(".magent-edit");
-    const editIndex = edit.dataset.editIndex;
-    btn.disabled = true;
+    setReviewDisabled(edit, true);
 
     try {
-        const body = new URLSearchParams({
-            doc_id: docId,
-            edit_index: editIndex,
-        });
-        const response = await fetch("/api/d/accept-magent-edit", {
-            method: "POST",
-            headers: { "Content-Type": "application/x-www-form-urlencoded" },
-            body,
-        });
-        if (!response.ok) {
-            throw new Error("Failed to accept edit");
+        await reviewEdit(edit, docId, action);
+        if (action === "accept" && appliesEdits()) {
+            // The document text changed, so the blocks above are stale.
+            window.location.reload();
+            return;
         }
-        edit.dataset.status = "accepted";
-        btn.textContent = "Accepted";
+        edit.dataset.status = status;
+        btn.textContent = label;
     } catch (err) {
         console.error(err);
-        btn.disabled = false;
+        btn.title = err.message;
+        setReviewDisabled(edit, false);
     }
 };
 
-const initAcceptButtons = (docId) => {
+const openReviseForm = (btn, docId) => {
+    const edit = btn.closest(".magent-edit");
+    if (edit.querySelector(".magent-revise-form")) return;
+
+    const form = document.createElement("div");
+    form.className = "magent-revise-form";
+
+    const textarea = document.createElement("textarea");
+    textarea.className = "agent-insert-input";
+    textarea.placeholder = "What should magent change?";
+    textarea.rows = 2;
+
+    const submit = document.createElement("button");
+    submit.type = "button";
+    submit.className = "agent-insert-submit";
+    submit.textContent = "Send";
+
+    form.appendChild(textarea);
+    form.appendChild(submit);
+    btn.parentElement.after(form);
+    textarea.focus();
+
+    const doSubmit = async () => {
+        const comment = textarea.value.trim();
+        if (!comment) return;
+        setReviewDisabled(edit, true);
+        submit.disabled = true;
+        textarea.disabled = true;
+
+        try {
+            await reviewEdit(edit, docId, "revise", { comment });
+            window.location.reload();
+        } catch (err) {
+            console.error(err);
+            setReviewDisabled(edit, false);
+            submit.disabled = false;
+            textarea.disabled = false;
+        }
+    };
+
+    submit.addEventListener("click", doSubmit);
+    textarea.addEventListener("keydown", (e) => {
+        if (e.key === "Escape") {
+            form.remove();
+            showPendingBlocks();
+        } else if (e.key === "Enter" && (e.metaKey || e.ctrlKey)) {
+            doSubmit();
+        }
+    });
+};
+
+const initReviewButtons = (docId) => {
     document.querySelectorAll('.magent-edit[data-status="proposed"]').forEach((edit) => {
-        const btn = document.createElement("button");
-        btn.type = "button";
-        btn.className = "magent-accept-btn";
-        btn.textContent = "Accept";
-        edit.appendChild(btn);
-        btn.addEventListener("click", () => acceptEdit(btn, docId));
+        const actions = document.createElement("div");
+        actions.className = "magent-review-actions";
+
+        const button = (className, text, onClick) => {
+            const btn = document.createElement("button");
+            btn.type = "button";
+            btn.className = className;
+            btn.textContent = text;
+            btn.addEventListener("click", () => onClick(btn));
+            actions.appendChild(btn);
+        };
+        button("magent-accept-btn", "Accept", (btn) =>
+            decideEdit(btn, docId, "accept", "accepted", "Accepted"),
+        );
+        button("magent-reject-btn", "Reject", (btn) =>
+            decideEdit(btn, docId, "reject", "rejected", "Rejected"),
+        );
+        button("magent-revise-btn", "Request changes", (btn) => openReviseForm(btn, docId));
+
+        edit.appendChild(actions);
     });
 };
 
@@ -190,12 +281,54 @@ const initRemoveButtons = (docId) => {
     });
 };
 
+const initBlocks = (docId) => {
+    initInsertPoints(docId);
+    initReviewButtons(docId);
+    initRemoveButtons(docId);
+};
+
+// Replacing the blocks would drop whatever is being typed.
+const isTyping = (container) =>
+    container.querySelector(".agent-insert-form, .magent-revise-form") !== null;
+
+const showPendingBlocks = () => {
+    const container = document.querySelector(".agent-blocks");
+    if (!container || !pendingBlocks || isTyping(container)) return;
+
+    const { html, line_count: lineCount } = pendingBlocks;
+    pendingBlocks = null;
+    container.innerHTML = html;
+
+    const page = container.closest(".agent-page");
+    page.dataset.lineCount = lineCount;
+    const bottom = page.querySelector(".agent-bottom-input");
+    if (bottom) {
+        bottom.dataset.afterLine = lineCount;
+    }
+    initBlocks(page.dataset.docId);
+};
+
+const subscribeToChanges = (docId) => {
+    if (typeof EventSource === "undefined") return;
+
+    const encoded = docId.split("/").map(encodeURIComponent).join("/");
+    const source = new EventSource(`/api/agent-events/${encoded}`);
+    source.addEventListener("blocks", (e) => {
+        try {
+            pendingBlocks = JSON.parse(e.data);
+        } catch (err) {
+            console.error(err);
+            return;
+        }
+        showPendingBlocks();
+    });
+};
+
 export const initAgent = () => {
     const docId = getDocId();
     if (!docId) return;
 
-    initInsertPoints(docId);
+    initBlocks(docId);
     initBottomInput(docId);
-    initAcceptButtons(docId);
-    initRemoveButtons(docId);
+    subscribeToChanges(docId);
 };
@@ -1,7 +1,10 @@
-const postMove = async (sourcePath, targetDir) => {
+const MOVE_NOTICE_KEY = "mindex-manage-notice";
+
+const postMove = async (sourcePath, targetDir, newName = "") => {
     const body = new URLSearchParams({
         source_path: sourcePath,
         target_dir: targetDir,
+        new_name: newName,
     });
     const response = await fetch("/api/d/move-file", {
         method: "POST",
@@ -12,6 +15,23 @@ const postMove = async (sourcePath, targetDir) => {
         const text = await response.text();
         throw new Error(text || "Failed to move file");
     }
+    return response.json();
+};
+
+const describeMove = (result) => {
+    const updated = result.updated || [];
+    const failed = result.failed || [];
+    let message = `Moved to ${result.moved_to}.`;
+    if (updated.length > 0) {
+        const links = updated.reduce((sum, doc) => sum + doc.changes.length, 0);
+        const files = updated.map((doc) => doc.doc_id).join(", ");
+        message += ` Updated ${links} link(s) in ${files}.`;
+    }
+    if (failed.length > 0) {
+        const files = failed.map((doc) => doc.doc_id).join(", ");
+        message += ` Could not update links in ${files}.`;
+    }
+    return message;
 };
 
 const postDelete = async (filePath) => {
@@ -41,6 +61,12 @@ export const initFileManage = () => {
         }
     };
 
+    const movedNotice = sessionStorage.getItem(MOVE_NOTICE_KEY);
+    if (movedNotice) {
+        sessionStorage.removeItem(MOVE_NOTICE_KEY);
+        setNotice(movedNotice);
+    }
+
     let dragState = null;
     let highlightedTarget = null;
     let autoScroll = null;
@@ -88,14 +114,15 @@ export const initFileManage = () => {
         return target;
     };
 
-    const applyMove = async (sourcePath, targetDir) => {
+    const applyMove = async (sourcePath, targetDir, newName = "") => {
         if (busy) {
             return;
         }
         busy = true;
         setNotice("Moving\u2026");
         try {
-            await postMove(sourcePath, targetDir);
+            const result = await postMove(sourcePath, targetDir, newName);
+            sessionStorage.setItem(MOVE_NOTICE_KEY, describeMove(result));
             window.location.reload();
         } catch (err) {
             console.error(err);
@@ -286,6 +313,26 @@ export const initFileManage = () => {
         });
     });
 
+    // Rename buttons
+    document.querySelectorAll(".manage-rename-btn").forEach((btn) => {
+        btn.addEventListener("click", async () => {
+            if (busy) {
+                return;
+            }
+            const filePath = btn.dataset.filePath;
+            if (!filePath) {
+                return;
+            }
+            const fileName = filePath.split("/").pop() || filePath;
+            const newName = prompt(`Rename "${fileName}" to:`, fileName);
+            if (!newName || newName.trim() === fileName) {
+                return;
+            }
+            const targetDir = page.dataset.currentDir || "";
+            await applyMove(filePath, targetDir, newName.trim());
+        });
+    });
+
     // Delete buttons
     document.querySelectorAll(".manage-delete-btn").forEach((btn) => {
         btn.addEventListener("click", async () => {
@@ -74,9 +74,18 @@ export const filter = (query, items, limit = 50) => {
         return limit == null ? items.slice() : items.slice(0, limit);
     }
     const text = (item) => (typeof item === "string" ? item : item.path);
+    // Items may carry a display title (e.g. from front matter) that also matches.
+    const itemScore = (item) => {
+        const pathScore = score(query, text(item));
+        const titleScore = item.title ? score(query, item.title) : null;
+        if (titleScore === null) {
+            return pathScore;
+        }
+        return pathScore === null ? titleScore : Math.max(pathScore, titleScore);
+    };
     const scored = [];
     for (const item of items) {
-        const s = score(query, text(item));
+        const s = itemScore(item);
         if (s !== null) {
             scored.push({ item, score: s });
         }
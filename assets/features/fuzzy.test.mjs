@@ -83,6 +83,21 @@ test("filter works on objects with a path field", () => {
     assert.equal(matches[0].kind, "document");
 });
 
+test("filter also matches an item's title", () => {
+    // Given
+    const items = [
+        { path: "notes/2026-10-01.md", kind: "document", title: "Garden Plan" },
+        { path: "scan.pdf", kind: "pdf" },
+    ];
+
+    // When
+    const matches = filter("garden", items, null);
+
+    // Then
+    assert.equal(matches.length, 1);
+    assert.equal(matches[0].path, "notes/2026-10-01.md");
+});
+
 test("ties break toward the shorter path", () => {
     // Given two boundary-starting basename matches for "x"
     const candidates = ["x.md", "xenon-config.md"];
@@ -1,10 +1,12 @@
 import { filter } from "./fuzzy.js";
 
 // fzf-style command palette. Opened with Ctrl/Cmd-K from any page; a first
-// keystroke chooses a mode (`f` = fuzzy file open). Results are keyboard- and
-// click-navigable. The overlay is built once and reused.
+// keystroke chooses a mode (`f` = fuzzy file open, `/` = live content search
+// via `/api/search`). Results are keyboard- and click-navigable. The overlay
+// is built once and reused.
 
 const MAX_RESULTS = 50;
+const SEARCH_DEBOUNCE_MS = 150;
 
 export const initPalette = () => {
     if (document.querySelector(".palette-overlay")) {
@@ -56,9 +58,11 @@ export const initPalette = () => {
     document.body.append(launcher);
 
     let mode = null; // null = mode menu, "file" = fuzzy file open, "search" = content search
-    let entries = []; // currently rendered file entries
+    let entries = []; // currently rendered entries: { path, kind, docId?, route?, hash?, snippet? }
     let activeIndex = -1;
-    let fileCache = null; // [{ path, kind }], fetched once per page load
+    let fileCache = null; // [{ path, kind, title }], fetched once per page load
+    let searchTimer = null;
+    let searchSeq = 0; // drops responses to queries that were typed over
 
     const isTextEntry = (el) =>
         !!el &&
@@ -66,13 +70,16 @@ export const initPalette = () => {
             el.tagName === "TEXTAREA" ||
             el.isContentEditable);
 
+    const encodePath = (path) => path.split("/").map(encodeURIComponent).join("/");
+
     const navigate = (entry) => {
-        const encoded = entry.path
-            .split("/")
-            .map(encodeURIComponent)
-            .join("/");
+        if (entry.docId) {
+            // Search hits link straight to the heading or PDF page.
+            window.location.href = `${entry.route}${encodePath(entry.docId)}${entry.hash}`;
+            return;
+        }
         // `/d/<path>` resolves to the correct view for any file kind.
-        window.location.href = `/d/${encoded}`;
+        window.location.href = `/d/${encodePath(entry.path)}`;
     };
 
     const setActive = (index) => {
@@ -99,9 +106,9 @@ export const initPalette = () => {
         results.replaceChildren(item);
     };
 
-    const renderResults = () => {
+    const renderResults = (emptyText) => {
         if (entries.length === 0) {
-            showMessage("No matching files.");
+            showMessage(emptyText);
             activeIndex = -1;
             return;
         }
@@ -112,13 +119,21 @@ export const initPalette = () => {
 
             const path = document.createElement("span");
             path.className = "palette-result-path";
-            path.textContent = entry.path;
+            path.textContent = entry.title || entry.path;
 
             const kind = document.createElement("span");
             kind.className = "palette-result-kind";
             kind.textContent = entry.kind;
 
             item.append(path, kind);
+            const detail = entry.snippet || (entry.title ? entry.path : "");
+            if (detail) {
+                const snippet = document.createElement("span");
+                snippet.className = "palette-result-snippet";
+                snippet.textContent = detail;
+                item.classList.add("has-snippet");
+                item.append(snippet);
+            }
             item.addEventListener("mousedown", (event) => {
                 event.preventDefault();
                 navigate(entry);
@@ -135,7 +150,7 @@ export const initPalette = () => {
             return;
         }
         entries = filter(input.value, fileCache, MAX_RESULTS);
-        renderResults();
+        renderResults("No matching files.");
     };
 
     const loadFiles = async () => {
@@ -177,13 +192,52 @@ export const initPalette = () => {
         }
     };
 
-    const renderSearchHint = () => {
+    const runSearch = async (query) => {
+        const seq = ++searchSeq;
+        try {
+            const response = await fetch(`/api/search?q=${encodeURIComponent(query)}&limit=${MAX_RESULTS}`, {
+                headers: { Accept: "application/json" },
+            });
+            if (!response.ok) {
+                throw new Error(`status ${response.status}`);
+            }
+            const hits = await response.json();
+            if (seq !== searchSeq || mode !== "search") {
+                return;
+            }
+            entries = hits.slice(0, MAX_RESULTS).map((hit) => {
+                // Hit URLs are the viewer route, the raw doc id and, for
+                // snippets, a `#fragment`; only the doc id needs encoding.
+                const url = hit.snippets.length > 0 ? hit.snippets[0].url : hit.url;
+                return {
+                    path: hit.title,
+                    kind: hit.kind,
+                    docId: hit.doc_id,
+                    route: hit.url.slice(0, hit.url.length - hit.doc_id.length),
+                    hash: url.slice(hit.url.length),
+                    snippet: hit.snippets.length > 0 ? hit.snippets[0].text : hit.doc_id,
+                };
+            });
+            renderResults("No matches. Press Enter for the full search page.");
+        } catch (err) {
+            if (seq === searchSeq) {
+                console.error("palette: search failed", err);
+                showMessage("Search failed. Press Enter for the full search page.");
+            }
+        }
+    };
+
+    const updateSearch = () => {
+        clearTimeout(searchTimer);
+        entries = [];
+        activeIndex = -1;
         const query = input.value.trim();
-        showMessage(
-            query === ""
-                ? "Type a query, then press Enter to search file contents."
-                : `Press Enter to search contents for “${query}”.`,
-        );
+        if (query === "") {
+            searchSeq++;
+            showMessage("Type to search file contents.");
+            return;
+        }
+        searchTimer = setTimeout(() => runSearch(query), SEARCH_DEBOUNCE_MS);
     };
 
     const enterSearchMode = () => {
@@ -192,7 +246,7 @@ export const initPalette = () => {
         input.placeholder = "Search contents…";
         menu.hidden = true;
         results.hidden = false;
-        renderSearchHint();
+        updateSearch();
         input.focus();
     };
 
@@ -226,6 +280,7 @@ export const initPalette = () => {
 
     const resetToMenu = () => {
         mode = null;
+        clearTimeout(searchTimer);
         entries = [];
         activeIndex = -1;
         input.value = "";
@@ -263,13 +318,6 @@ export const initPalette = () => {
             }
             return;
         }
-        if (mode === "search") {
-            if (event.key === "Enter") {
-                event.preventDefault();
-                submitSearch();
-            }
-            return;
-        }
         if (event.key === "ArrowDown") {
             event.preventDefault();
             setActive(activeIndex + 1);
@@ -280,6 +328,8 @@ export const initPalette = () => {
             event.preventDefault();
             if (activeIndex >= 0 && entries[activeIndex]) {
                 navigate(entries[activeIndex]);
+            } else if (mode === "search") {
+                submitSearch();
             }
         }
     });
@@ -288,7 +338,7 @@ export const initPalette = () => {
         if (mode === "file") {
             updateResults();
         } else if (mode === "search") {
-            renderSearchHint();
+            updateSearch();
         }
     });
 
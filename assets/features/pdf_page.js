@@ -0,0 +1,21 @@
+const PAGE_HASH = /^#page=(\d+)$/;
+
+// Search results link to `/pdf/...#page=N`; pass the page on to the embedded viewer.
+export const initPdfPage = () => {
+    const frame = document.querySelector(".pdf-viewer-frame");
+    if (!frame) {
+        return;
+    }
+
+    const applyPage = () => {
+        const match = PAGE_HASH.exec(window.location.hash);
+        if (!match) {
+            return;
+        }
+        const src = frame.getAttribute("src").split("#")[0];
+        frame.setAttribute("src", `${src}#page=${match[1]}`);
+    };
+
+    applyPage();
+    window.addEventListener("hashchange", applyPage);
+};
@@ -6,6 +6,26 @@ const getDocId = () => {
     return decodeURIComponent(path.slice(3));
 };
 
+const localDate = () => {
+    const now = new Date();
+    const month = String(now.getMonth() + 1).padStart(2, "0");
+    const day = String(now.getDate()).padStart(2, "0");
+    return `${now.getFullYear()}-${month}-${day}`;
+};
+
+// Due dates are compared against the reader's local date, so overdue
+// highlighting follows their time zone rather than the server's.
+const markOverdue = (input) => {
+    const item = input.closest("li");
+    if (!item) {
+        return;
+    }
+    const today = localDate();
+    item.querySelectorAll(".task-due[data-due]").forEach((badge) => {
+        badge.classList.toggle("is-overdue", !input.checked && badge.dataset.due < today);
+    });
+};
+
 const toggleTask = async (input, docId) => {
     const taskIndex = Number.parseInt(input.dataset.taskIndex, 10);
     if (!Number.isFinite(taskIndex)) {
@@ -32,17 +52,21 @@ const toggleTask = async (input, docId) => {
         console.error(err);
     } finally {
         input.disabled = false;
+        markOverdue(input);
     }
 };
 
 export const initTodoToggle = () => {
-    const docId = getDocId();
-    if (!docId) {
-        return;
-    }
+    const pageDocId = getDocId();
     document
         .querySelectorAll('input.todo-checkbox[data-task-index]')
         .forEach((input) => {
+            // The task dashboard lists tasks from many documents.
+            markOverdue(input);
+            const docId = input.dataset.docId || pageDocId;
+            if (!docId) {
+                return;
+            }
             input.addEventListener("change", () => toggleTask(input, docId));
         });
 };
@@ -1,3 +1,20 @@
+mod agent;
+mod channels;
+mod command;
+mod email;
+mod http;
+mod ntfy;
+mod openai;
+mod webhook;
+
+pub use agent::ConfiguredAgent;
+pub use channels::ChannelSender;
+pub use command::CommandBackend;
+pub use email::EmailSender;
+pub use ntfy::NtfySender;
+pub use openai::OpenAiBackend;
+pub use webhook::WebhookSender;
+
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
@@ -44,6 +61,7 @@ impl WebPushSender {
 }
 
 impl PushSender for WebPushSender {
+    type Target = Subscription;
     type Error = web_push::WebPushError;
     type Fut<'a>
         = Pin<Box<dyn Future<Output = Result<(), Self::Error>> + Send + 'a>>
@@ -70,4 +88,11 @@ impl PushSender for WebPushSender {
             Ok(())
         })
     }
+
+    fn is_expired(error: &Self::Error) -> bool {
+        matches!(
+            error,
+            web_push::WebPushError::EndpointNotValid | web_push::WebPushError::EndpointNotFound
+        )
+    }
 }
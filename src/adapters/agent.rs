@@ -0,0 +1,51 @@
+use std::pin::Pin;
+
+use super::command::{CommandBackend, CommandError};
+use super::openai::{OpenAiBackend, OpenAiError};
+use crate::ports::agent::AgentBackend;
+use crate::types::agent::AgentRequest;
+
+/// The agent backend selected in the server settings.
+#[derive(Clone)]
+pub enum ConfiguredAgent {
+    OpenAi(OpenAiBackend),
+    Command(CommandBackend),
+}
+
+#[derive(Debug)]
+pub enum ConfiguredAgentError {
+    OpenAi(OpenAiError),
+    Command(CommandError),
+}
+
+impl std::fmt::Display for ConfiguredAgentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfiguredAgentError::OpenAi(err) => write!(f, "{err}"),
+            ConfiguredAgentError::Command(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl AgentBackend for ConfiguredAgent {
+    type Error = ConfiguredAgentError;
+    type Fut<'a>
+        = Pin<Box<dyn Future<Output = Result<String, Self::Error>> + Send + 'a>>
+    where
+        Self: 'a;
+
+    fn respond<'a>(&'a self, request: &'a AgentRequest) -> Self::Fut<'a> {
+        Box::pin(async move {
+            match self {
+                ConfiguredAgent::OpenAi(backend) => backend
+                    .respond(request)
+                    .await
+                    .map_err(ConfiguredAgentError::OpenAi),
+                ConfiguredAgent::Command(backend) => backend
+                    .respond(request)
+                    .await
+                    .map_err(ConfiguredAgentError::Command),
+            }
+        })
+    }
+}
@@ -0,0 +1,103 @@
+use std::pin::Pin;
+
+use super::email::EmailError;
+use super::http::HttpSendError;
+use super::{EmailSender, NtfySender, WebPushSender, WebhookSender};
+use crate::ports::push::PushSender;
+use crate::types::directives::{Channel, DeliveryTarget};
+
+/// Sends each delivery target through the adapter for its channel. Channels
+/// without an adapter fail with [`ChannelError::Unavailable`], and report
+/// `false` from [`PushSender::has_channel`] so the scheduler skips them.
+#[derive(Clone, Default)]
+pub struct ChannelSender {
+    pub webpush: Option<WebPushSender>,
+    pub webhook: Option<WebhookSender>,
+    pub ntfy: Option<NtfySender>,
+    pub email: Option<EmailSender>,
+}
+
+impl ChannelSender {
+    pub fn is_empty(&self) -> bool {
+        self.webpush.is_none()
+            && self.webhook.is_none()
+            && self.ntfy.is_none()
+            && self.email.is_none()
+    }
+}
+
+#[derive(Debug)]
+pub enum ChannelError {
+    WebPush(web_push::WebPushError),
+    Http(HttpSendError),
+    Email(EmailError),
+    Unavailable(Channel),
+}
+
+impl std::fmt::Display for ChannelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChannelError::WebPush(err) => write!(f, "{err}"),
+            ChannelError::Http(err) => write!(f, "{err}"),
+            ChannelError::Email(err) => write!(f, "{err}"),
+            ChannelError::Unavailable(channel) => {
+                write!(f, "the {} channel is not configured", channel.as_str())
+            }
+        }
+    }
+}
+
+impl PushSender for ChannelSender {
+    type Target = DeliveryTarget;
+    type Error = ChannelError;
+    type Fut<'a>
+        = Pin<Box<dyn Future<Output = Result<(), Self::Error>> + Send + 'a>>
+    where
+        Self: 'a;
+
+    fn send<'a>(&'a self, target: &'a DeliveryTarget, message: &'a str) -> Self::Fut<'a> {
+        Box::pin(async move {
+            let unavailable = ChannelError::Unavailable(target.channel());
+            match target {
+                DeliveryTarget::WebPush(subscription) => match &self.webpush {
+                    Some(sender) => sender
+                        .send(subscription, message)
+                        .await
+                        .map_err(ChannelError::WebPush),
+                    None => Err(unavailable),
+                },
+                DeliveryTarget::Webhook(url) => match &self.webhook {
+                    Some(sender) => sender.send(url, message).await.map_err(ChannelError::Http),
+                    None => Err(unavailable),
+                },
+                DeliveryTarget::Ntfy(url) => match &self.ntfy {
+                    Some(sender) => sender.send(url, message).await.map_err(ChannelError::Http),
+                    None => Err(unavailable),
+                },
+                DeliveryTarget::Email(address) => match &self.email {
+                    Some(sender) => sender
+                        .send(address, message)
+                        .await
+                        .map_err(ChannelError::Email),
+                    None => Err(unavailable),
+                },
+            }
+        })
+    }
+
+    fn is_expired(error: &Self::Error) -> bool {
+        match error {
+            ChannelError::WebPush(err) => WebPushSender::is_expired(err),
+            _ => false,
+        }
+    }
+
+    fn has_channel(&self, channel: Channel) -> bool {
+        match channel {
+            Channel::Webpush => self.webpush.is_some(),
+            Channel::Webhook => self.webhook.is_some(),
+            Channel::Ntfy => self.ntfy.is_some(),
+            Channel::Email => self.email.is_some(),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::pin::Pin;
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::io::AsyncWriteExt as _;
+use tokio::process::Command;
+
+use crate::ports::agent::AgentBackend;
+use crate::types::agent::AgentRequest;
+
+/// Answers directives by running a shell command with the prompt on stdin.
+/// Its stdout becomes the response. The command also gets `MINDEX_DOC_ID`
+/// and `MINDEX_DIRECTIVE` in its environment, and is killed when it runs
+/// longer than the timeout.
+#[derive(Clone)]
+pub struct CommandBackend {
+    command: String,
+    timeout: Duration,
+}
+
+#[derive(Debug)]
+pub enum CommandError {
+    Io(std::io::Error),
+    Status(String, String),
+    Output(std::string::FromUtf8Error),
+    Empty,
+    Timeout(Duration),
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::Io(err) => write!(f, "agent command failed to run: {err}"),
+            CommandError::Status(status, stderr) if stderr.is_empty() => {
+                write!(f, "agent command {status}")
+            }
+            CommandError::Status(status, stderr) => {
+                write!(f, "agent command {status}: {stderr}")
+            }
+            CommandError::Output(err) => write!(f, "agent command output is not UTF-8: {err}"),
+            CommandError::Empty => write!(f, "agent command printed nothing"),
+            CommandError::Timeout(timeout) => {
+                write!(f, "agent command timed out after {}s", timeout.as_secs())
+            }
+        }
+    }
+}
+
+impl CommandBackend {
+    pub fn new(command: String, timeout: Duration) -> Self {
+        Self { command, timeout }
+    }
+}
+
+impl AgentBackend for CommandBackend {
+    type Error = CommandError;
+    type Fut<'a>
+        = Pin<Box<dyn Future<Output = Result<String, Self::Error>> + Send + 'a>>
+    where
+        Self: 'a;
+
+    fn respond<'a>(&'a self, request: &'a AgentRequest) -> Self::Fut<'a> {
+        Box::pin(async move {
+            tokio::time::timeout(self.timeout, run(&self.command, request))
+                .await
+                .map_err(|_| CommandError::Timeout(self.timeout))?
+        })
+    }
+}
+
+/// Runs the command to completion. Dropping the future, as the timeout does,
+/// kills the command.
+async fn run(command: &str, request: &AgentRequest) -> Result<String, CommandError> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("MINDEX_DOC_ID", &request.doc_id)
+        .env("MINDEX_DIRECTIVE", &request.directive)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(CommandError::Io)?;
+
+    // Write while waiting so a command that prints before reading all of
+    // stdin cannot block on a full pipe.
+    let stdin = child.stdin.take();
+    let prompt = request.prompt();
+    let write = async move {
+        if let Some(mut stdin) = stdin {
+            // Commands that ignore stdin close it early; that is fine.
+            let _ = stdin.write_all(prompt.as_bytes()).await;
+        }
+    };
+    let ((), output) = tokio::join!(write, child.wait_with_output());
+    let output = output.map_err(CommandError::Io)?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(CommandError::Status(
+            output.status.to_string(),
+            stderr.trim().chars().take(200).collect(),
+        ));
+    }
+    let stdout = String::from_utf8(output.stdout).map_err(CommandError::Output)?;
+    if stdout.trim().is_empty() {
+        return Err(CommandError::Empty);
+    }
+    Ok(stdout)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn request() -> AgentRequest {
+        AgentRequest {
+            doc_id: "plan.md".to_string(),
+            directive: "shout".to_string(),
+            line: 0,
+            document: "@magent shout\n".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn command_backend__should_pipe_the_prompt_through_the_command() {
+        // Given
+        let backend = CommandBackend::new(
+            "printf '%s %s: ' \"$MINDEX_DOC_ID\" \"$MINDEX_DIRECTIVE\"; tail -n 1 | tr a-z A-Z"
+                .to_string(),
+            TIMEOUT,
+        );
+
+        // When
+        let result = backend.respond(&request()).await;
+
+        // Then
+        assert_eq!(
+            result.expect("response"),
+            "plan.md shout: DIRECTIVE ON LINE 1: SHOUT\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn command_backend__should_fail_on_error_status_or_empty_output() {
+        // Given
+        let failing = CommandBackend::new("echo broken >&2; exit 3".to_string(), TIMEOUT);
+        let silent = CommandBackend::new("true".to_string(), TIMEOUT);
+
+        // When
+        let failed = failing.respond(&request()).await;
+        let empty = silent.respond(&request()).await;
+
+        // Then
+        assert_eq!(
+            failed.expect_err("status").to_string(),
+            "agent command exit status: 3: broken"
+        );
+        assert_eq!(
+            empty.expect_err("empty").to_string(),
+            "agent command printed nothing"
+        );
+    }
+
+    #[tokio::test]
+    async fn command_backend__should_kill_commands_that_run_past_the_timeout() {
+        // Given
+        let backend = CommandBackend::new("sleep 10".to_string(), Duration::from_millis(100));
+
+        // When
+        let started = std::time::Instant::now();
+        let result = backend.respond(&request()).await;
+
+        // Then
+        assert!(matches!(result, Err(CommandError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,69 @@
+use isahc::AsyncReadResponseExt;
+use isahc::config::Configurable;
+use std::time::Duration;
+
+/// How long an HTTP notification may take before it counts as failed.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum HttpSendError {
+    Client(isahc::Error),
+    Request(isahc::http::Error),
+    Status(u16, String),
+}
+
+impl std::fmt::Display for HttpSendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpSendError::Client(err) => write!(f, "http request failed: {err}"),
+            HttpSendError::Request(err) => write!(f, "invalid http request: {err}"),
+            HttpSendError::Status(status, body) if body.is_empty() => {
+                write!(f, "http status {status}")
+            }
+            HttpSendError::Status(status, body) => write!(f, "http status {status}: {body}"),
+        }
+    }
+}
+
+pub(super) fn client() -> Result<isahc::HttpClient, isahc::Error> {
+    client_with_timeout(TIMEOUT)
+}
+
+pub(super) fn client_with_timeout(timeout: Duration) -> Result<isahc::HttpClient, isahc::Error> {
+    isahc::HttpClient::builder().timeout(timeout).build()
+}
+
+/// POSTs `body` to `url` and fails unless the response status is 2xx.
+pub(super) async fn post(
+    client: &isahc::HttpClient,
+    url: &str,
+    content_type: &str,
+    body: Vec<u8>,
+) -> Result<(), HttpSendError> {
+    let request = isahc::Request::post(url)
+        .header("Content-Type", content_type)
+        .body(body)
+        .map_err(HttpSendError::Request)?;
+    send(client, request).await.map(|_| ())
+}
+
+/// Sends `request` and returns the response body, failing unless the
+/// response status is 2xx.
+pub(super) async fn send(
+    client: &isahc::HttpClient,
+    request: isahc::Request<Vec<u8>>,
+) -> Result<String, HttpSendError> {
+    let mut response = client
+        .send_async(request)
+        .await
+        .map_err(HttpSendError::Client)?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if status.is_success() {
+        return Ok(body);
+    }
+    Err(HttpSendError::Status(
+        status.as_u16(),
+        body.trim().chars().take(200).collect(),
+    ))
+}
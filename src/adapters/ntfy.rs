@@ -0,0 +1,71 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use super::http::{self, HttpSendError};
+use crate::ports::push::PushSender;
+
+/// Publishes notifications to an ntfy topic URL, with the message as the
+/// plain text body.
+#[derive(Clone)]
+pub struct NtfySender {
+    client: Arc<isahc::HttpClient>,
+}
+
+impl NtfySender {
+    pub fn new() -> Result<Self, isahc::Error> {
+        Ok(Self {
+            client: Arc::new(http::client()?),
+        })
+    }
+}
+
+impl PushSender for NtfySender {
+    type Target = str;
+    type Error = HttpSendError;
+    type Fut<'a>
+        = Pin<Box<dyn Future<Output = Result<(), Self::Error>> + Send + 'a>>
+    where
+        Self: 'a;
+
+    fn send<'a>(&'a self, url: &'a str, message: &'a str) -> Self::Fut<'a> {
+        Box::pin(async move {
+            http::post(
+                &self.client,
+                url,
+                "text/plain; charset=utf-8",
+                message.as_bytes().to_vec(),
+            )
+            .await
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::test_support::spawn_recording_server;
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn ntfy_sender__should_publish_the_message_to_the_topic() {
+        // Given
+        let (url, requests) = spawn_recording_server(StatusCode::OK).await;
+        let sender = NtfySender::new().expect("client");
+        let topic = format!("{url}/mindex-marten");
+
+        // When
+        let result = sender.send(&topic, "Due 2026-11-01: pay rent").await;
+
+        // Then
+        assert!(result.is_ok());
+        let requests = requests.lock().expect("requests lock");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/mindex-marten");
+        assert_eq!(
+            requests[0].content_type.as_deref(),
+            Some("text/plain; charset=utf-8")
+        );
+        assert_eq!(requests[0].body, "Due 2026-11-01: pay rent");
+    }
+}
@@ -0,0 +1,196 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::http::{self, HttpSendError};
+use crate::ports::agent::AgentBackend;
+use crate::types::agent::AgentRequest;
+
+const SYSTEM_PROMPT: &str = "You answer `@magent` directives written in a markdown \
+knowledge base. You get the document and the directive to answer. Reply with the \
+markdown to show below the directive and nothing else.";
+
+/// Answers directives through an OpenAI-compatible chat completions API,
+/// e.g. `https://api.openai.com/v1` or a local server exposing the same API.
+#[derive(Clone)]
+pub struct OpenAiBackend {
+    client: Arc<isahc::HttpClient>,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatReply,
+}
+
+#[derive(Deserialize)]
+struct ChatReply {
+    content: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum OpenAiError {
+    Http(HttpSendError),
+    Response(String),
+}
+
+impl std::fmt::Display for OpenAiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpenAiError::Http(err) => write!(f, "{err}"),
+            OpenAiError::Response(err) => write!(f, "unexpected chat completion response: {err}"),
+        }
+    }
+}
+
+impl OpenAiBackend {
+    /// `base_url` is the API root that `/chat/completions` is appended to.
+    /// Requests taking longer than `timeout` fail.
+    pub fn new(
+        base_url: &str,
+        model: String,
+        api_key: Option<String>,
+        timeout: Duration,
+    ) -> Result<Self, isahc::Error> {
+        Ok(Self {
+            client: Arc::new(http::client_with_timeout(timeout)?),
+            url: format!("{}/chat/completions", base_url.trim_end_matches('/')),
+            model,
+            api_key,
+        })
+    }
+}
+
+impl AgentBackend for OpenAiBackend {
+    type Error = OpenAiError;
+    type Fut<'a>
+        = Pin<Box<dyn Future<Output = Result<String, Self::Error>> + Send + 'a>>
+    where
+        Self: 'a;
+
+    fn respond<'a>(&'a self, request: &'a AgentRequest) -> Self::Fut<'a> {
+        Box::pin(async move {
+            let prompt = request.prompt();
+            let body = serde_json::to_vec(&ChatRequest {
+                model: &self.model,
+                messages: [
+                    ChatMessage {
+                        role: "system",
+                        content: SYSTEM_PROMPT,
+                    },
+                    ChatMessage {
+                        role: "user",
+                        content: &prompt,
+                    },
+                ],
+            })
+            .expect("chat request serializes");
+            let mut builder =
+                isahc::Request::post(&self.url).header("Content-Type", "application/json");
+            if let Some(api_key) = &self.api_key {
+                builder = builder.header("Authorization", format!("Bearer {api_key}"));
+            }
+            let http_request = builder
+                .body(body)
+                .map_err(|err| OpenAiError::Http(HttpSendError::Request(err)))?;
+
+            let raw = http::send(&self.client, http_request)
+                .await
+                .map_err(OpenAiError::Http)?;
+            let response: ChatResponse =
+                serde_json::from_str(&raw).map_err(|err| OpenAiError::Response(err.to_string()))?;
+            response
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|choice| choice.message.content)
+                .filter(|content| !content.trim().is_empty())
+                .ok_or_else(|| OpenAiError::Response("no message content".to_string()))
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::test_support::spawn_responding_server;
+    use axum::http::StatusCode;
+
+    fn request() -> AgentRequest {
+        AgentRequest {
+            doc_id: "notes/plan.md".to_string(),
+            directive: "summarize".to_string(),
+            line: 2,
+            document: "# Plan\n\n@magent summarize\n".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn openai_backend__should_post_a_chat_completion_and_return_the_reply() {
+        // Given
+        let reply = serde_json::json!({
+            "choices": [{"message": {"role": "assistant", "content": "A short plan."}}]
+        });
+        let (url, requests) = spawn_responding_server(StatusCode::OK, reply.to_string()).await;
+        let backend = OpenAiBackend::new(
+            &format!("{url}/v1/"),
+            "local-model".to_string(),
+            Some("secret".to_string()),
+            Duration::from_secs(10),
+        )
+        .expect("client");
+
+        // When
+        let result = backend.respond(&request()).await;
+
+        // Then
+        assert_eq!(result.expect("reply"), "A short plan.");
+        let requests = requests.lock().expect("requests lock");
+        assert_eq!(requests[0].path, "/v1/chat/completions");
+        assert_eq!(requests[0].authorization.as_deref(), Some("Bearer secret"));
+        let payload: serde_json::Value =
+            serde_json::from_str(&requests[0].body).expect("parse payload");
+        assert_eq!(payload["model"], "local-model");
+        assert_eq!(payload["messages"][0]["role"], "system");
+        assert_eq!(payload["messages"][1]["content"], request().prompt());
+    }
+
+    #[tokio::test]
+    async fn openai_backend__should_fail_without_message_content() {
+        // Given
+        let (url, _) = spawn_responding_server(StatusCode::OK, r#"{"choices":[]}"#.into()).await;
+        let backend = OpenAiBackend::new(&url, "m".to_string(), None, Duration::from_secs(10))
+            .expect("client");
+
+        // When
+        let result = backend.respond(&request()).await;
+
+        // Then
+        assert_eq!(
+            result.expect_err("no content").to_string(),
+            "unexpected chat completion response: no message content"
+        );
+    }
+}
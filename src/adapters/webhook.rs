@@ -0,0 +1,90 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use super::http::{self, HttpSendError};
+use crate::ports::push::PushSender;
+
+/// Posts notifications as JSON (`{"message": "..."}`) to a webhook URL.
+#[derive(Clone)]
+pub struct WebhookSender {
+    client: Arc<isahc::HttpClient>,
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    message: &'a str,
+}
+
+impl WebhookSender {
+    pub fn new() -> Result<Self, isahc::Error> {
+        Ok(Self {
+            client: Arc::new(http::client()?),
+        })
+    }
+}
+
+impl PushSender for WebhookSender {
+    type Target = str;
+    type Error = HttpSendError;
+    type Fut<'a>
+        = Pin<Box<dyn Future<Output = Result<(), Self::Error>> + Send + 'a>>
+    where
+        Self: 'a;
+
+    fn send<'a>(&'a self, url: &'a str, message: &'a str) -> Self::Fut<'a> {
+        Box::pin(async move {
+            let body = serde_json::to_vec(&WebhookPayload { message })
+                .expect("webhook payload serializes");
+            http::post(&self.client, url, "application/json", body).await
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::test_support::spawn_recording_server;
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn webhook_sender__should_post_the_message_as_json() {
+        // Given
+        let (url, requests) = spawn_recording_server(StatusCode::NO_CONTENT).await;
+        let sender = WebhookSender::new().expect("client");
+
+        // When
+        let result = sender
+            .send(&format!("{url}/hooks/mindex"), "Stand-up \"now\"")
+            .await;
+
+        // Then
+        assert!(result.is_ok());
+        let requests = requests.lock().expect("requests lock");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/hooks/mindex");
+        assert_eq!(
+            requests[0].content_type.as_deref(),
+            Some("application/json")
+        );
+        let payload: serde_json::Value =
+            serde_json::from_str(&requests[0].body).expect("parse payload");
+        assert_eq!(payload, serde_json::json!({"message": "Stand-up \"now\""}));
+    }
+
+    #[tokio::test]
+    async fn webhook_sender__should_fail_on_error_status() {
+        // Given
+        let (url, _) = spawn_recording_server(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let sender = WebhookSender::new().expect("client");
+
+        // When
+        let result = sender.send(&url, "Hello").await;
+
+        // Then
+        let err = result.expect_err("status error");
+        assert_eq!(err.to_string(), "http status 500");
+    }
+}
@@ -0,0 +1,230 @@
+use crate::adapters::{CommandBackend, ConfiguredAgent, OpenAiBackend};
+use crate::config;
+use crate::documents::{insert_magent_response, unanswered_directives};
+use crate::fs::atomic_write;
+use crate::ports::agent::AgentBackend;
+use crate::types::agent::AgentRequest;
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// The agent backend from the server settings: a local command, or an
+/// OpenAI-compatible API. Returns `None` when neither is configured.
+pub(crate) fn build_runner(config: &config::AppConfig) -> Option<AgentRunner<ConfiguredAgent>> {
+    let timeout = config.agent_timeout.unsigned_abs();
+    let backend = match (&config.agent_command, &config.agent_url) {
+        (None, None) => return None,
+        (Some(_), Some(_)) => {
+            eprintln!("agent runner disabled: set only one of --agent-command and --agent-url");
+            return None;
+        }
+        (Some(command), None) => {
+            ConfiguredAgent::Command(CommandBackend::new(command.clone(), timeout))
+        }
+        (None, Some(url)) => {
+            let Some(model) = &config.agent_model else {
+                eprintln!("agent runner disabled: --agent-url needs --agent-model");
+                return None;
+            };
+            match OpenAiBackend::new(url, model.clone(), config.agent_api_key.clone(), timeout) {
+                Ok(backend) => ConfiguredAgent::OpenAi(backend),
+                Err(err) => {
+                    eprintln!("agent runner disabled: {err}");
+                    return None;
+                }
+            }
+        }
+    };
+    Some(AgentRunner::new(backend))
+}
+
+/// Answers unanswered `@magent` directives in place of an external magent
+/// process.
+#[derive(Clone)]
+pub(crate) struct AgentRunner<B> {
+    backend: B,
+    /// Documents being answered, and whether another pass was requested
+    /// meanwhile.
+    runs: Arc<Mutex<HashMap<String, bool>>>,
+}
+
+impl<B: AgentBackend> AgentRunner<B> {
+    pub(crate) fn new(backend: B) -> Self {
+        Self {
+            backend,
+            runs: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Answers the document's pending directives in the background and calls
+    /// `on_written` after each response is saved. While the document is being
+    /// answered, further calls only queue one more pass, so a directive is
+    /// never sent to the backend twice at once.
+    pub(crate) fn spawn(
+        &self,
+        doc_id: &str,
+        path: &Path,
+        on_written: impl Fn() + Send + Sync + 'static,
+    ) {
+        {
+            let mut runs = self.runs.lock().expect("agent runs lock");
+            if let Some(again) = runs.get_mut(doc_id) {
+                *again = true;
+                return;
+            }
+            runs.insert(doc_id.to_string(), false);
+        }
+
+        let runner = self.clone();
+        let doc_id = doc_id.to_string();
+        let path = path.to_path_buf();
+        tokio::spawn(async move {
+            loop {
+                runner.answer_pending(&doc_id, &path, &on_written).await;
+                let mut runs = runner.runs.lock().expect("agent runs lock");
+                match runs.get_mut(&doc_id) {
+                    Some(again) if *again => *again = false,
+                    _ => {
+                        runs.remove(&doc_id);
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Answers every pending directive of one document in order and returns
+    /// how many responses were written. Backend failures are logged and the
+    /// directive stays pending until the document is saved again.
+    pub(crate) async fn answer_pending(
+        &self,
+        doc_id: &str,
+        path: &Path,
+        on_written: &(impl Fn() + Send + Sync),
+    ) -> usize {
+        // Directives that failed stay pending ahead of the rest, so skip them.
+        let mut failed = 0;
+        let mut written = 0;
+        while let Some(document) = read_document(doc_id, path) {
+            let Some(directive) = unanswered_directives(&document).into_iter().nth(failed) else {
+                break;
+            };
+            let request = AgentRequest {
+                doc_id: doc_id.to_string(),
+                directive: directive.prompt.clone(),
+                line: directive.line,
+                document,
+            };
+            let response = match self.backend.respond(&request).await {
+                Ok(response) => response,
+                Err(err) => {
+                    eprintln!(
+                        "agent failed to answer line {} of {doc_id}: {err}",
+                        directive.line + 1
+                    );
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            // Insert into the document as it is now, not as it was sent.
+            let Some(current) = read_document(doc_id, path) else {
+                break;
+            };
+            let Some(updated) = insert_magent_response(&current, &directive, &response) else {
+                continue;
+            };
+            if let Err(err) = atomic_write(path, &updated) {
+                eprintln!("failed to save agent response to {doc_id}: {err}");
+                break;
+            }
+            written += 1;
+            on_written();
+        }
+        written
+    }
+}
+
+fn read_document(doc_id: &str, path: &Path) -> Option<String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Some(contents),
+        Err(err) => {
+            eprintln!("failed to load {doc_id} for the agent: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_temp_root;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Clone, Default)]
+    struct TestAgent {
+        requests: Arc<Mutex<Vec<AgentRequest>>>,
+    }
+
+    impl AgentBackend for TestAgent {
+        type Error = String;
+        type Fut<'a>
+            = Pin<Box<dyn Future<Output = Result<String, Self::Error>> + Send + 'a>>
+        where
+            Self: 'a;
+
+        fn respond<'a>(&'a self, request: &'a AgentRequest) -> Self::Fut<'a> {
+            Box::pin(async move {
+                self.requests
+                    .lock()
+                    .expect("requests lock")
+                    .push(request.clone());
+                if request.directive == "fail" {
+                    return Err("backend down".to_string());
+                }
+                Ok(format!("Answer to {}.", request.directive))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn answer_pending__should_write_a_response_below_each_pending_directive() {
+        // Given
+        let root = create_temp_root("agent-runner");
+        let path = root.join("chat.md");
+        std::fs::write(&path, "@magent one\n\n@magent fail\n\n@magent two\nText.\n")
+            .expect("write");
+        let agent = TestAgent::default();
+        let runner = AgentRunner::new(agent.clone());
+        let saves = AtomicUsize::new(0);
+        let on_written = || {
+            saves.fetch_add(1, Ordering::SeqCst);
+        };
+
+        // When
+        let written = runner.answer_pending("chat.md", &path, &on_written).await;
+        let again = runner.answer_pending("chat.md", &path, &on_written).await;
+
+        // Then
+        assert_eq!(written, 2);
+        assert_eq!(again, 0);
+        assert_eq!(saves.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("read"),
+            "@magent one\n\n<magent-response>\nAnswer to one.\n</magent-response>\n\n\
+             @magent fail\n\n\
+             @magent two\n\n<magent-response>\nAnswer to two.\n</magent-response>\n\nText.\n"
+        );
+        let requests = agent.requests.lock().expect("requests lock");
+        let directives: Vec<&str> = requests.iter().map(|r| r.directive.as_str()).collect();
+        assert_eq!(directives, vec!["one", "fail", "two", "fail"]);
+        // Each request sees the answers written before it.
+        assert!(requests[2].document.contains("Answer to one."));
+        assert_eq!(requests[2].line, 8);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+}
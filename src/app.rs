@@ -1,3 +1,4 @@
+use crate::agent as agent_service;
 use crate::assets;
 use crate::auth as auth_service;
 use crate::config;
@@ -15,7 +16,11 @@ use axum::routing::post;
 mod auth;
 mod documents;
 mod git;
+mod links;
 mod push;
+mod search;
+mod tags;
+mod tasks;
 mod text_files;
 mod uploads;
 
@@ -38,25 +43,45 @@ pub fn app(config: config::AppConfig) -> Router {
     };
     let registries = std::sync::Arc::new(std::sync::Mutex::new(registries));
     let push_handles = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let search_index =
+        std::sync::Arc::new(std::sync::Mutex::new(search::load_search_index(&config)));
+    let agent = agent_service::build_runner(&config);
+    let delivery_log = push_service::history::DeliveryLog::new(&config.root);
+    let push_scheduler = push_service::build_scheduler(&config, &delivery_log);
     let state = state::AppState {
         config,
         auth,
         registries: std::sync::Arc::clone(&registries),
         push_handles: std::sync::Arc::clone(&push_handles),
+        delivery_log,
+        push_scheduler,
+        search_index,
         git_dir,
+        agent,
     };
     let registries_snapshot = {
         let registries = registries.lock().expect("registries lock");
         std::sync::Arc::new(registries.clone())
     };
-    push_service::maybe_start_scheduler(&state.config, registries_snapshot, push_handles);
+    push::refresh_after_pruning(&state);
+    push_service::maybe_start_scheduler(
+        state.push_scheduler.as_ref(),
+        registries_snapshot,
+        push_handles,
+    );
     Router::new()
         .route("/", get(documents::directory_browse_root))
         .route("/d/{*path}", get(documents::resolve_path))
         .route("/login", get(auth::login_form).post(auth::login_submit))
         .route("/logout", post(auth::logout))
-        .route("/search", get(documents::document_search))
+        .route("/search", get(search::document_search))
         .route("/api/files", get(documents::document_file_list))
+        .route("/api/search", get(search::search_api))
+        .route("/api/graph", get(links::graph_api))
+        .route("/links/broken", get(links::broken_links_view))
+        .route("/tags", get(tags::tag_list_view))
+        .route("/tags/{*tag}", get(tags::tag_view))
+        .route("/tasks", get(tasks::task_board_view))
         .route(
             "/new",
             get(documents::document_new).post(documents::document_create),
@@ -67,6 +92,10 @@ pub fn app(config: config::AppConfig) -> Router {
         )
         .route("/reorder/{*path}", get(documents::document_reorder))
         .route("/agent/{*path}", get(documents::document_agent_view))
+        .route(
+            "/api/agent-events/{*path}",
+            get(documents::document_agent_events),
+        )
         .route("/view/{*path}", get(text_files::text_view))
         .route(
             "/edit-text/{*path}",
@@ -82,6 +111,14 @@ pub fn app(config: config::AppConfig) -> Router {
             "/api/d/accept-magent-edit",
             post(documents::document_accept_magent_edit),
         )
+        .route(
+            "/api/d/reject-magent-edit",
+            post(documents::document_reject_magent_edit),
+        )
+        .route(
+            "/api/d/revise-magent-edit",
+            post(documents::document_revise_magent_edit),
+        )
         .route(
             "/api/d/insert-magent-directive",
             post(documents::document_insert_magent_directive),
@@ -104,6 +141,8 @@ pub fn app(config: config::AppConfig) -> Router {
         .route("/manage", get(documents::file_manage_view_root))
         .route("/manage/{*path}", get(documents::file_manage_view_path))
         .route("/push/subscribe", get(push::push_subscribe))
+        .route("/push/history", get(push::push_history))
+        .route("/api/push/history", get(push::push_history_api))
         .route("/api/push/public-key", get(push::push_public_key))
         .route("/api/push/test", post(push::push_test))
         .route("/upload", get(uploads::upload_form))
@@ -155,6 +194,7 @@ pub fn app(config: config::AppConfig) -> Router {
         )
         .route("/static/features/palette.js", get(assets::palette_script))
         .route("/static/features/fuzzy.js", get(assets::fuzzy_script))
+        .route("/static/features/pdf_page.js", get(assets::pdf_page_script))
         .route("/static/manifest.json", get(assets::manifest))
         .route("/sw.js", get(assets::service_worker))
         .route("/static/icons/icon-192.png", get(assets::icon_192))
@@ -196,13 +236,18 @@ pub(crate) mod tests {
     use tower::ServiceExt;
 
     use askama::Template as _;
+    use futures_util::StreamExt as _;
     use std::path::{Path, PathBuf};
     use std::process::Command;
 
     #[tokio::test]
     async fn app__should_return_ok_on_health_endpoint() {
         // Given
-        let app = app(config::AppConfig::default());
+        let root = create_temp_root("health");
+        let app = app(config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        });
 
         // When
         let response = app
@@ -222,6 +267,8 @@ pub(crate) mod tests {
             .await
             .expect("read body");
         assert_eq!(body.as_ref(), b"ok");
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
     #[tokio::test]
@@ -315,7 +362,11 @@ pub(crate) mod tests {
         std::fs::write(root.join("alpha.md"), "# Alpha").expect("write");
         std::fs::write(root.join("scan.pdf"), "pdf").expect("write");
         std::fs::create_dir_all(root.join("notes")).expect("mkdir");
-        std::fs::write(root.join("notes/todo.md"), "# Todo").expect("write");
+        std::fs::write(
+            root.join("notes/todo.md"),
+            "---\ntitle: Chores\n---\n# Todo",
+        )
+        .expect("write");
         let app_config = config::AppConfig {
             root: root.clone(),
             ..Default::default()
@@ -341,9 +392,9 @@ pub(crate) mod tests {
         assert_eq!(
             payload,
             serde_json::json!([
-                { "path": "alpha.md", "kind": "document" },
-                { "path": "notes/todo.md", "kind": "document" },
-                { "path": "scan.pdf", "kind": "pdf" },
+                { "path": "alpha.md", "kind": "document", "title": null },
+                { "path": "notes/todo.md", "kind": "document", "title": "Chores" },
+                { "path": "scan.pdf", "kind": "pdf", "title": null },
             ])
         );
 
@@ -602,6 +653,154 @@ message = "Check the daily log."
         std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
+    #[tokio::test]
+    async fn push_history__should_list_recorded_deliveries_newest_first() {
+        // Given
+        let root = create_temp_root("push-history-routes");
+        let log = crate::push::history::DeliveryLog::new(&root);
+        let at = time::OffsetDateTime::parse(
+            "2026-10-19T09:00:00Z",
+            &time::format_description::well_known::Rfc3339,
+        )
+        .expect("parse");
+        let delivered = crate::push::history::DeliveryRecord {
+            notification_id: "note.md#1".to_string(),
+            doc_id: "note.md".to_string(),
+            scheduled_for: at,
+            attempted_at: at,
+            recipient: "marten".to_string(),
+            endpoint: Some("https://push.example/123".to_string()),
+            outcome: crate::push::history::DeliveryOutcome::Delivered,
+            error: None,
+            message: "Water the plants".to_string(),
+        };
+        log.append(&delivered);
+        log.append(&crate::push::history::DeliveryRecord {
+            outcome: crate::push::history::DeliveryOutcome::Failed,
+            error: Some("endpoint gone".to_string()),
+            attempted_at: at + time::Duration::minutes(1),
+            ..delivered.clone()
+        });
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+        let app = app(app_config);
+
+        // When
+        let api_response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/push/history")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+        let page_response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/push/history")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(api_response.status(), StatusCode::OK);
+        let body = to_bytes(api_response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let records: Vec<crate::push::history::DeliveryRecord> =
+            json_from_slice(&body).expect("parse json");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].error.as_deref(), Some("endpoint gone"));
+        assert_eq!(records[1], delivered);
+
+        assert_eq!(page_response.status(), StatusCode::OK);
+        let body = to_bytes(page_response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let html = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(html.contains("Water the plants"));
+        assert!(html.contains("endpoint gone"));
+        assert!(html.find("failed").expect("failed") < html.find("delivered").expect("delivered"));
+        assert!(!html.contains("Older deliveries"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn push_history__should_page_with_limit_and_before() {
+        // Given
+        let root = create_temp_root("push-history-paging");
+        let log = crate::push::history::DeliveryLog::new(&root);
+        let at = time::OffsetDateTime::parse(
+            "2026-10-19T09:00:00Z",
+            &time::format_description::well_known::Rfc3339,
+        )
+        .expect("parse");
+        for minutes in 0..3 {
+            log.append(&crate::push::history::DeliveryRecord {
+                notification_id: format!("note.md#{minutes}"),
+                doc_id: "note.md".to_string(),
+                scheduled_for: at,
+                attempted_at: at + time::Duration::minutes(minutes),
+                recipient: "marten".to_string(),
+                endpoint: None,
+                outcome: crate::push::history::DeliveryOutcome::Delivered,
+                error: None,
+                message: format!("Message {minutes}"),
+            });
+        }
+        let app = app(config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        });
+
+        // When
+        let api_response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/push/history?limit=1&before=2026-10-19T09:02:00Z")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+        let page_response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/push/history?limit=2")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        let body = to_bytes(api_response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let records: Vec<crate::push::history::DeliveryRecord> =
+            json_from_slice(&body).expect("parse json");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].message, "Message 1");
+
+        let body = to_bytes(page_response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let html = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(html.contains("Message 2"));
+        assert!(!html.contains("Message 0"));
+        assert!(html.contains("/push/history?before=2026-10-19T09:01:00Z"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
     #[tokio::test]
     async fn document_save__should_refresh_push_registries() {
         // Given
@@ -617,7 +816,11 @@ message = "Check the daily log."
                 directives::DirectiveRegistries::default(),
             )),
             push_handles: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            delivery_log: crate::push::history::DeliveryLog::new(&root),
+            push_scheduler: None,
+            search_index: std::sync::Arc::new(std::sync::Mutex::new(Default::default())),
             git_dir: None,
+            agent: None,
         };
 
         let form = documents::EditForm {
@@ -651,6 +854,45 @@ password_hash = "hash"
         std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
+    #[tokio::test]
+    async fn directory_browse__should_title_documents_from_the_search_index() {
+        // Given
+        let root = create_temp_root("browse-titles");
+        std::fs::create_dir_all(root.join("notes")).expect("mkdir");
+        std::fs::write(
+            root.join("notes/plan.md"),
+            "---\ntitle: Garden Plan\n---\n# Plan",
+        )
+        .expect("write");
+        std::fs::write(root.join("notes/todo.md"), "# Todo").expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+
+        // When
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .uri("/d/notes")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let html = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(html.contains(r#"<span class="dir-card-name" title="plan.md">Garden Plan</span>"#));
+        assert!(html.contains("todo.md"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
     #[test]
     fn render_directory_browse__should_include_links() {
         // Given
@@ -664,6 +906,7 @@ password_hash = "hash"
             directories: vec!["notes".to_string()],
             files: vec![templates::DirectoryFileEntry {
                 name: "b.md".to_string(),
+                title: None,
                 kind: templates::FileKind::Document,
                 url: "/d/b.md".to_string(),
             }],
@@ -696,6 +939,7 @@ password_hash = "hash"
             directories: vec!["work".to_string()],
             files: vec![templates::DirectoryFileEntry {
                 name: "todo.md".to_string(),
+                title: None,
                 kind: templates::FileKind::Document,
                 url: "/d/notes/todo.md".to_string(),
             }],
@@ -740,6 +984,7 @@ password_hash = "hash"
                 url: "/".to_string(),
             }],
             content: body,
+            backlinks: Vec::new(),
             has_mermaid: false,
             has_abc: false,
             has_code: false,
@@ -1023,6 +1268,50 @@ Hi there!
         std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
+    #[tokio::test]
+    async fn agent_view__should_number_edits_across_responses() {
+        // Given
+        let root = create_temp_root("agent-edit-index");
+        let response = "\
+<magent-response>
+<magent-edit status=\"proposed\">
+<magent-search>one</magent-search>
+<magent-replace>1</magent-replace>
+</magent-edit>
+</magent-response>
+";
+        let markdown = format!("one\n\n{response}\n{}", response.replace("one", "two"));
+        std::fs::write(root.join("chat.md"), markdown).expect("write chat.md");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+
+        // When
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .uri("/agent/chat.md")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let body = std::str::from_utf8(&body).expect("utf8");
+        assert!(body.contains("data-edit-index=\"0\""));
+        assert!(body.contains("data-edit-index=\"1\""));
+        assert!(body.contains("<del>one</del><ins>1</ins>"));
+        assert!(body.contains("data-found=\"once\""));
+        assert!(body.contains("data-found=\"missing\""));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
     #[tokio::test]
     async fn agent_view__should_return_not_found_for_missing_doc() {
         // Given
@@ -1049,6 +1338,89 @@ Hi there!
         std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
+    #[tokio::test]
+    async fn agent_events__should_push_blocks_when_the_document_changes() {
+        // Given
+        let root = create_temp_root("agent-events");
+        let path = root.join("chat.md");
+        std::fs::write(
+            &path,
+            "@magent hello\n\n<magent-response>\n</magent-response>\n",
+        )
+        .expect("write chat.md");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .uri("/api/agent-events/chat.md")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["content-type"].to_str().unwrap(),
+            "text/event-stream"
+        );
+
+        // When
+        std::fs::write(
+            &path,
+            "@magent hello\n\n<magent-response>\nHi there!\n</magent-response>\n",
+        )
+        .expect("update chat.md");
+        let mut events = response.into_body().into_data_stream();
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), events.next())
+            .await
+            .expect("event in time")
+            .expect("stream open")
+            .expect("read event");
+
+        // Then
+        let event = std::str::from_utf8(&event).expect("utf8");
+        let data = event
+            .strip_prefix("event: blocks\ndata: ")
+            .expect("blocks event")
+            .trim_end();
+        let payload: serde_json::Value = serde_json::from_str(data).expect("parse payload");
+        assert_eq!(payload["line_count"], 5);
+        let html = payload["html"].as_str().expect("html");
+        assert!(html.contains("agent-block-directive"));
+        assert!(html.contains("Hi there!"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn agent_events__should_return_not_found_for_missing_doc() {
+        // Given
+        let root = create_temp_root("agent-events-missing");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+
+        // When
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .uri("/api/agent-events/nope.md")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
     #[tokio::test]
     async fn view_document__should_load_highlight_js_for_code_blocks() {
         // Given
@@ -1677,91 +2049,725 @@ text line 2
         std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
-    // -- move_file --
+    // -- search --
 
     #[tokio::test]
-    async fn move_file__should_move_file_and_return_no_content() {
+    async fn document_view__should_resolve_wiki_links_to_documents_created_after_startup() {
         // Given
-        let root = create_temp_root("api-move-ok");
-        std::fs::create_dir_all(root.join("src")).expect("mkdir src");
-        std::fs::create_dir_all(root.join("dest")).expect("mkdir dest");
-        std::fs::write(root.join("src/note.md"), "# Note").expect("write");
+        let root = create_temp_root("wiki-links-view");
+        std::fs::write(root.join("index.md"), "[[Recipes]] and [[Travel]]").expect("write");
         let app_config = config::AppConfig {
             root: root.clone(),
             ..Default::default()
         };
-
-        // When
-        let body = "source_path=src%2Fnote.md&target_dir=dest";
-        let response = app(app_config)
+        let router = app(app_config);
+        let response = router
+            .clone()
             .oneshot(
                 Request::builder()
                     .method("POST")
-                    .uri("/api/d/move-file")
+                    .uri("/new")
                     .header("content-type", "application/x-www-form-urlencoded")
-                    .body(Body::from(body))
+                    .body(Body::from("doc_id=food%2Frecipes.md"))
                     .unwrap(),
             )
             .await
             .expect("request failed");
-
-        // Then
-        assert_eq!(response.status(), StatusCode::NO_CONTENT);
-        assert!(!root.join("src/note.md").exists());
-        assert_eq!(
-            std::fs::read_to_string(root.join("dest/note.md")).expect("read"),
-            "# Note"
-        );
-
-        std::fs::remove_dir_all(&root).expect("cleanup");
-    }
-
-    #[tokio::test]
-    async fn move_file__should_return_bad_request_for_invalid_path() {
-        // Given
-        let root = create_temp_root("api-move-bad");
-        let app_config = config::AppConfig {
-            root: root.clone(),
-            ..Default::default()
-        };
+        assert!(response.status().is_redirection());
 
         // When
-        let body = "source_path=..%2Fescape.md&target_dir=";
-        let response = app(app_config)
+        let response = router
             .oneshot(
                 Request::builder()
-                    .method("POST")
-                    .uri("/api/d/move-file")
-                    .header("content-type", "application/x-www-form-urlencoded")
-                    .body(Body::from(body))
+                    .uri("/d/index.md")
+                    .body(Body::empty())
                     .unwrap(),
             )
             .await
             .expect("request failed");
 
         // Then
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let html = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(html.contains(r#"<a href="/d/food/recipes.md">Recipes</a>"#));
+        assert!(html.contains(r#"class="wiki-link-missing" href="/new?path=Travel.md""#));
 
         std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
     #[tokio::test]
-    async fn move_file__should_return_not_found_for_missing_source() {
+    async fn document_view__should_show_backlinks_from_saved_documents() {
         // Given
-        let root = create_temp_root("api-move-404");
-        std::fs::create_dir_all(root.join("dest")).expect("mkdir");
+        let root = create_temp_root("backlinks-view");
+        std::fs::write(root.join("target.md"), "# Target").expect("write");
+        std::fs::write(root.join("source.md"), "Nothing yet").expect("write");
         let app_config = config::AppConfig {
             root: root.clone(),
             ..Default::default()
         };
-
-        // When
-        let body = "source_path=missing.md&target_dir=dest";
-        let response = app(app_config)
+        let router = app(app_config);
+        let response = router
+            .clone()
             .oneshot(
                 Request::builder()
                     .method("POST")
-                    .uri("/api/d/move-file")
+                    .uri("/edit/source.md")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(Body::from("contents=See+%5B%5BTarget%5D%5D+soon"))
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/d/target.md")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let html = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(html.contains("Linked from"));
+        assert!(html.contains(r#"<a href="/d/source.md">source</a>"#));
+        assert!(html.contains("See [[Target]] soon"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn document_view__should_show_front_matter_title_and_properties() {
+        // Given
+        let root = create_temp_root("front-matter-view");
+        std::fs::write(
+            root.join("2026-10-01.md"),
+            "---\ntitle: Garden Plan\ntags: [garden]\n---\nBody\n",
+        )
+        .expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+
+        // When
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .uri("/d/2026-10-01.md")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let html = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(html.contains(r#"<span class="breadcrumb-current">Garden Plan</span>"#));
+        assert!(html.contains(r#"<details class="front-matter">"#));
+        assert!(!html.contains("title: Garden Plan"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn graph_api__should_drop_edges_of_deleted_documents() {
+        // Given
+        let root = create_temp_root("graph-api");
+        std::fs::write(root.join("a.md"), "# A\n[B](b.md)").expect("write");
+        std::fs::write(root.join("b.md"), "[[a]]").expect("write");
+        std::fs::write(root.join("c.md"), "[[a]]").expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+        let router = app(app_config);
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/d/delete-file")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(Body::from("file_path=c.md"))
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/api/graph")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let payload: JsonValue = json_from_slice(&body).expect("parse json");
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "nodes": [
+                    { "id": "a.md", "title": "A" },
+                    { "id": "b.md", "title": "b" },
+                ],
+                "edges": [
+                    { "source": "a.md", "target": "b.md" },
+                    { "source": "b.md", "target": "a.md" },
+                ],
+            })
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn broken_links_view__should_list_missing_targets_with_lines() {
+        // Given
+        let root = create_temp_root("broken-links-view");
+        std::fs::write(
+            root.join("a.md"),
+            "# A\n\n[B](b.md#intro) [gone](gone.md)\n",
+        )
+        .expect("write");
+        std::fs::write(root.join("b.md"), "# Intro\n").expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+
+        // When
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .uri("/links/broken")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let body = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(
+            body.contains(r#"<a href="/d/a.md">a.md</a><span class="broken-link-line">:3</span>"#)
+        );
+        assert!(body.contains("<code>gone.md</code>"));
+        assert!(!body.contains("b.md#intro"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn task_board__should_filter_tasks_and_toggle_them_in_their_document() {
+        // Given
+        let root = create_temp_root("task-board");
+        std::fs::create_dir_all(root.join("work")).expect("mkdir");
+        std::fs::write(
+            root.join("work/plan.md"),
+            "<magent-response>\n- [ ] suggested @ana\n</magent-response>\n- [ ] draft @ana\n- [ ] file @ben\n",
+        )
+        .expect("write");
+        std::fs::write(root.join("home.md"), "- [ ] sweep @ana\n").expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+        let app = app(app_config);
+
+        // When
+        let board = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/tasks?folder=work&assignee=%40ana&status=open&tag=")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+        let toggle = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/d/toggle-task")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(Body::from(
+                        "doc_id=work%2Fplan.md&task_index=0&checked=true",
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(board.status(), StatusCode::OK);
+        let body = to_bytes(board.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let body = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(body.contains(r#"data-doc-id="work/plan.md" data-task-index="0""#));
+        assert!(body.contains("draft @ana"));
+        assert!(!body.contains("suggested @ana"));
+        assert!(!body.contains("file @ben"));
+        assert!(!body.contains("sweep @ana"));
+        assert_eq!(toggle.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            std::fs::read_to_string(root.join("work/plan.md")).expect("read"),
+            "<magent-response>\n- [ ] suggested @ana\n</magent-response>\n- [x] draft @ana\n- [ ] file @ben\n"
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn tag_pages__should_list_counts_and_tagged_lines() {
+        // Given
+        let root = create_temp_root("tag-pages");
+        std::fs::write(root.join("a.md"), "# A\nPlant #garden/veg today\n").expect("write");
+        std::fs::write(root.join("b.md"), "---\ntags: [garden]\n---\nBody\n").expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+        let app = app(app_config);
+
+        // When
+        let list = app
+            .clone()
+            .oneshot(Request::builder().uri("/tags").body(Body::empty()).unwrap())
+            .await
+            .expect("request failed");
+        let tag = app
+            .oneshot(
+                Request::builder()
+                    .uri("/tags/garden")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(list.status(), StatusCode::OK);
+        let body = to_bytes(list.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let body = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(body.contains(
+            r##"<a class="tag" href="/tags/garden/veg">#garden/veg</a>
+            <span class="tag-count">1</span>"##
+        ));
+        assert_eq!(tag.status(), StatusCode::OK);
+        let body = to_bytes(tag.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let body = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(body.contains(r#"<a href="/d/a.md">A</a>"#));
+        assert!(body.contains(r#"<span class="tag-line-number">2</span>"#));
+        assert!(body.contains(r#"<a href="/d/b.md">b</a>"#));
+        assert!(body.contains(r#"<span class="tag-source">front matter</span>"#));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn search__should_find_content_saved_after_startup() {
+        // Given
+        let root = create_temp_root("api-search-save");
+        std::fs::write(root.join("note.md"), "Nothing yet").expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+        let router = app(app_config);
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/edit/note.md")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(Body::from("contents=Freshly+written+zeppelin"))
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/search?q=zeppelin")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let html = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(html.contains("/d/note.md"));
+        assert!(html.contains("Freshly written <mark>zeppelin</mark>"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn search__should_link_saved_text_files_to_viewer() {
+        // Given
+        let root = create_temp_root("api-search-text");
+        std::fs::write(root.join("settings.toml"), "theme = \"dark\"\n").expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+        let router = app(app_config);
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/edit-text/settings.toml")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(Body::from("contents=editor+%3D+%22helix%22"))
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/search?q=helix")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let html = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(html.contains(r#"href="/view/settings.toml""#));
+        assert!(html.contains("<mark>helix</mark>"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn search_api__should_return_hits_with_snippet_offsets() {
+        // Given
+        let root = create_temp_root("api-search-json");
+        std::fs::write(root.join("garden.md"), "# Garden\n\nPlant the tulips\n").expect("write");
+        std::fs::write(root.join("other.md"), "Nothing here").expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+
+        // When
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .uri("/api/search?q=tulip")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let payload: JsonValue = json_from_slice(&body).expect("parse json");
+        let hits = payload.as_array().expect("array");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0]["doc_id"], "garden.md");
+        assert_eq!(hits[0]["kind"], "document");
+        assert_eq!(hits[0]["title"], "Garden");
+        assert_eq!(hits[0]["url"], "/d/garden.md");
+        assert!(hits[0]["score"].as_f64().expect("score") > 0.0);
+        assert_eq!(
+            hits[0]["snippets"],
+            serde_json::json!([{
+                "text": "Plant the tulips",
+                "highlights": [{ "start": 10, "end": 16 }],
+                "line": 3,
+                "start": 10,
+                "end": 26,
+                "anchor": "garden",
+                "page": null,
+                "url": "/d/garden.md#garden",
+            }])
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn search_api__should_return_the_best_hits_up_to_the_limit() {
+        // Given
+        let root = create_temp_root("api-search-limit");
+        std::fs::write(
+            root.join("tulips.md"),
+            "# Tulips
+
+tulips",
+        )
+        .expect("write");
+        std::fs::write(root.join("garden.md"), "a few tulips").expect("write");
+        std::fs::write(root.join("other.md"), "one tulip").expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+
+        // When
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .uri("/api/search?q=tulip&limit=1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let payload: JsonValue = json_from_slice(&body).expect("parse json");
+        let hits = payload.as_array().expect("array");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0]["doc_id"], "tulips.md");
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn search_api__should_be_auth_gated() {
+        // Given
+        let root = create_temp_root("api-search-auth");
+        let app_config = auth_app_config(root.clone(), b"search-api-secret");
+
+        // When
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .uri("/api/search?q=anything")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let payload: JsonValue = json_from_slice(&body).expect("parse json");
+        assert_eq!(payload["error"], "unauthorized");
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn search__should_follow_moved_documents() {
+        // Given
+        let root = create_temp_root("api-search-move");
+        std::fs::create_dir_all(root.join("dest")).expect("mkdir dest");
+        std::fs::write(root.join("note.md"), "Mentions a zeppelin").expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+        let router = app(app_config);
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/d/move-file")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(Body::from("source_path=note.md&target_dir=dest"))
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/search?q=zeppelin")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let html = String::from_utf8(body.to_vec()).expect("utf8");
+        assert!(html.contains("/d/dest/note.md"));
+        assert!(!html.contains("/d/note.md"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    // -- move_file --
+
+    #[tokio::test]
+    async fn move_file__should_move_file_and_report_rewritten_links() {
+        // Given
+        let root = create_temp_root("api-move-ok");
+        std::fs::create_dir_all(root.join("src")).expect("mkdir src");
+        std::fs::create_dir_all(root.join("dest")).expect("mkdir dest");
+        std::fs::write(root.join("src/note.md"), "# Note").expect("write");
+        std::fs::write(root.join("index.md"), "# Index\n[Note](src/note.md#note)\n")
+            .expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+
+        // When
+        let body = "source_path=src%2Fnote.md&target_dir=dest";
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/d/move-file")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let payload: JsonValue = json_from_slice(&body).expect("parse json");
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "moved_to": "dest/note.md",
+                "updated": [{
+                    "doc_id": "index.md",
+                    "changes": [{ "line": 2, "from": "src/note.md#note", "to": "dest/note.md#note" }],
+                }],
+                "failed": [],
+            })
+        );
+        assert!(!root.join("src/note.md").exists());
+        assert_eq!(
+            std::fs::read_to_string(root.join("dest/note.md")).expect("read"),
+            "# Note"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("index.md")).expect("read"),
+            "# Index\n[Note](dest/note.md#note)\n"
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn move_file__should_return_bad_request_for_invalid_path() {
+        // Given
+        let root = create_temp_root("api-move-bad");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+
+        // When
+        let body = "source_path=..%2Fescape.md&target_dir=";
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/d/move-file")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        // Then
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn move_file__should_return_not_found_for_missing_source() {
+        // Given
+        let root = create_temp_root("api-move-404");
+        std::fs::create_dir_all(root.join("dest")).expect("mkdir");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+
+        // When
+        let body = "source_path=missing.md&target_dir=dest";
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/d/move-file")
                     .header("content-type", "application/x-www-form-urlencoded")
                     .body(Body::from(body))
                     .unwrap(),
@@ -2070,6 +3076,46 @@ password_hash = "{password_hash}"
         std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
+    #[tokio::test]
+    async fn insert_magent_directive__should_be_answered_by_the_agent_command() {
+        let root = create_temp_root("insert-directive-agent");
+        std::fs::write(root.join("note.md"), "# Title\n").expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            agent_command: Some("printf 'Echo: %s' \"$MINDEX_DIRECTIVE\"".to_string()),
+            ..Default::default()
+        };
+
+        let body = "doc_id=note.md&after_line=0&directive=hello";
+        let response = app(app_config)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/d/insert-magent-directive")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .expect("request failed");
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let mut updated = String::new();
+        for _ in 0..200 {
+            updated = std::fs::read_to_string(root.join("note.md")).expect("read");
+            if updated.contains("</magent-response>") {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(25)).await;
+        }
+        assert_eq!(
+            updated,
+            "# Title\n\n@magent hello\n\n<magent-response>\nEcho: hello\n</magent-response>\n"
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
     // -----------------------------------------------------------------------
     // accept-magent-edit API
     // -----------------------------------------------------------------------
@@ -2116,4 +3162,121 @@ Fixed the URL:
 
         std::fs::remove_dir_all(&root).expect("cleanup");
     }
+
+    #[tokio::test]
+    async fn review_magent_edit__should_reject_or_request_changes() {
+        let root = create_temp_root("review-edit-api");
+        let doc = "\
+<magent-response>
+<magent-edit status=\"proposed\">
+<magent-search>a</magent-search>
+<magent-replace>b</magent-replace>
+</magent-edit>
+<magent-edit status=\"proposed\">
+<magent-search>c</magent-search>
+<magent-replace>d</magent-replace>
+</magent-edit>
+</magent-response>
+";
+        std::fs::write(root.join("note.md"), doc).expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+        let post = |uri: &'static str, body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/x-www-form-urlencoded")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let rejected = app(app_config.clone())
+            .oneshot(post(
+                "/api/d/reject-magent-edit",
+                "doc_id=note.md&edit_index=0",
+            ))
+            .await
+            .expect("request failed");
+        let no_comment = app(app_config.clone())
+            .oneshot(post(
+                "/api/d/revise-magent-edit",
+                "doc_id=note.md&edit_index=1&comment=+",
+            ))
+            .await
+            .expect("request failed");
+        let revised = app(app_config.clone())
+            .oneshot(post(
+                "/api/d/revise-magent-edit",
+                "doc_id=note.md&edit_index=1&comment=Use+uppercase",
+            ))
+            .await
+            .expect("request failed");
+        let reject_again = app(app_config)
+            .oneshot(post(
+                "/api/d/reject-magent-edit",
+                "doc_id=note.md&edit_index=0",
+            ))
+            .await
+            .expect("request failed");
+
+        assert_eq!(rejected.status(), StatusCode::NO_CONTENT);
+        assert_eq!(no_comment.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(revised.status(), StatusCode::NO_CONTENT);
+        assert_eq!(reject_again.status(), StatusCode::NOT_FOUND);
+        let updated = std::fs::read_to_string(root.join("note.md")).expect("read");
+        assert!(updated.contains("status=\"rejected\""));
+        assert!(updated.contains("status=\"revise\""));
+        assert!(updated.contains("<magent-comment>Use uppercase</magent-comment>\n</magent-edit>"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn accept_magent_edit__should_apply_the_edit_when_enabled() {
+        let root = create_temp_root("apply-edit-api");
+        let edit = "\
+<magent-response>
+<magent-edit status=\"proposed\">
+<magent-search>old-url</magent-search>
+<magent-replace>new-url</magent-replace>
+</magent-edit>
+</magent-response>
+";
+        std::fs::write(root.join("note.md"), format!("See old-url.\n\n{edit}")).expect("write");
+        std::fs::write(root.join("twice.md"), format!("old-url old-url\n\n{edit}")).expect("write");
+        let app_config = config::AppConfig {
+            root: root.clone(),
+            apply_magent_edits: true,
+            ..Default::default()
+        };
+        let accept = |body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/d/accept-magent-edit")
+                .header("content-type", "application/x-www-form-urlencoded")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let applied = app(app_config.clone())
+            .oneshot(accept("doc_id=note.md&edit_index=0"))
+            .await
+            .expect("request failed");
+        let conflict = app(app_config)
+            .oneshot(accept("doc_id=twice.md&edit_index=0"))
+            .await
+            .expect("request failed");
+
+        assert_eq!(applied.status(), StatusCode::NO_CONTENT);
+        let updated = std::fs::read_to_string(root.join("note.md")).expect("read");
+        assert!(updated.starts_with("See new-url.\n"));
+        assert!(updated.contains("status=\"applied\""));
+        assert_eq!(conflict.status(), StatusCode::CONFLICT);
+        let unchanged = std::fs::read_to_string(root.join("twice.md")).expect("read");
+        assert!(unchanged.contains("status=\"proposed\""));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
 }
@@ -1,10 +1,11 @@
 use crate::documents::{
-    BlockKind, DocError, FileKind, MagentRegion, ReorderError, accept_magent_edit,
-    add_task_item_in_list, collect_browsable_files, collect_mentions, create_document, delete_file,
-    find_magent_regions, insert_directive, line_count, lines_for_display, list_directory,
-    load_document, move_file, normalize_newlines, remove_magent_interaction, render_document_html,
-    render_magent_blocks, render_markdown_snippet, reorder_range, resolve_doc_path,
-    scan_block_ranges, search_documents, toggle_task_item,
+    ApplyEditError, BlockKind, DirectoryFile, DocError, FileKind, MagentRegion, ReorderError,
+    accept_magent_edit, add_task_item_in_list, apply_magent_edit, backlinks,
+    collect_browsable_files, collect_mentions, create_document, delete_file, find_magent_regions,
+    insert_directive, line_count, lines_for_display, list_directory, load_document, move_file,
+    normalize_newlines, reject_magent_edit, remove_magent_interaction, render_document_html,
+    render_magent_blocks, render_markdown_snippet, reorder_range, request_magent_edit_changes,
+    resolve_doc_path, scan_block_ranges, toggle_task_item,
 };
 use crate::fs::atomic_write;
 use crate::git;
@@ -12,19 +13,26 @@ use crate::push as push_service;
 use crate::state;
 use crate::templates;
 
+use askama::Template as _;
 use axum::Json;
 use axum::extract::Form;
 use axum::extract::Path as AxumPath;
 use axum::extract::Query;
 use axum::extract::State;
 use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::{IntoResponse, Redirect, Response};
+use futures_util::Stream;
 use serde::Deserialize;
 use serde::Serialize;
 
+use std::convert::Infallible;
 use std::io::ErrorKind;
+use std::path::Path;
+use std::time::Duration;
 
 use super::push::refresh_push_state;
+use super::search::refresh_search_document;
 
 pub(crate) async fn directory_browse_root(
     State(state): State<state::AppState>,
@@ -61,6 +69,8 @@ pub(crate) async fn resolve_path(
 pub(crate) struct FileListEntry {
     pub(crate) path: String,
     pub(crate) kind: &'static str,
+    /// Front matter title of a markdown document.
+    pub(crate) title: Option<String>,
 }
 
 pub(crate) async fn document_file_list(
@@ -70,9 +80,13 @@ pub(crate) async fn document_file_list(
         eprintln!("failed to list browsable files: {err}");
         (StatusCode::INTERNAL_SERVER_ERROR, "internal error")
     })?;
+    let index = state.search_index.lock().expect("search index lock");
     let entries = files
         .into_iter()
         .map(|file| FileListEntry {
+            title: index
+                .document(&file.path)
+                .and_then(|document| document.front_matter.title.clone()),
             path: file.path,
             kind: file.kind.as_str(),
         })
@@ -117,8 +131,30 @@ fn directory_browse(
         .unwrap_or(&current_dir)
         .to_string();
 
-    let files = listing
-        .files
+    let files = directory_file_entries(&state, &path_prefix, listing.files);
+
+    Ok(templates::DirectoryBrowseTemplate {
+        app_name: state.config.app_name,
+        current_dir,
+        current_dir_name,
+        path_prefix,
+        parent_url,
+        breadcrumbs,
+        directories: listing.directories,
+        files,
+        git_enabled,
+    })
+}
+
+/// Links for the files of a listed folder, titled from the search index so
+/// listing a folder does not re-read its documents.
+fn directory_file_entries(
+    state: &state::AppState,
+    path_prefix: &str,
+    files: Vec<DirectoryFile>,
+) -> Vec<templates::DirectoryFileEntry> {
+    let index = state.search_index.lock().expect("search index lock");
+    files
         .into_iter()
         .map(|f| {
             let full_path = format!("{path_prefix}{}", f.name);
@@ -129,24 +165,15 @@ fn directory_browse(
                 FileKind::Text => format!("/view/{full_path}"),
             };
             templates::DirectoryFileEntry {
+                title: index
+                    .document(&full_path)
+                    .and_then(|document| document.front_matter.title.clone()),
                 name: f.name,
                 kind: f.kind,
                 url,
             }
         })
-        .collect();
-
-    Ok(templates::DirectoryBrowseTemplate {
-        app_name: state.config.app_name,
-        current_dir,
-        current_dir_name,
-        path_prefix,
-        parent_url,
-        breadcrumbs,
-        directories: listing.directories,
-        files,
-        git_enabled,
-    })
+        .collect()
 }
 
 fn build_breadcrumbs(current_dir: &str) -> Vec<templates::BreadcrumbSegment> {
@@ -200,14 +227,17 @@ fn build_breadcrumbs_for_doc(parent_dir: &str) -> Vec<templates::BreadcrumbSegme
 #[derive(Debug, Deserialize)]
 pub(crate) struct NewDocumentQuery {
     pub(crate) dir: Option<String>,
+    /// Full suggested path, e.g. from an unresolved wiki link.
+    pub(crate) path: Option<String>,
 }
 
 pub(crate) async fn document_new(
     State(state): State<state::AppState>,
     Query(query): Query<NewDocumentQuery>,
 ) -> templates::NewDocumentTemplate {
-    let doc_id = match query.dir {
-        Some(ref dir) if !dir.is_empty() => format!("{dir}/"),
+    let doc_id = match (query.path, query.dir) {
+        (Some(path), _) if !path.is_empty() => path,
+        (_, Some(dir)) if !dir.is_empty() => format!("{dir}/"),
         _ => String::new(),
     };
     templates::NewDocumentTemplate {
@@ -244,7 +274,12 @@ pub(crate) async fn document_create(
 
     let empty = String::new();
     match create_document(&state.config.root, &doc_id, &empty) {
-        Ok(()) => Ok(Redirect::to(&format!("/edit/{doc_id}"))),
+        Ok(()) => {
+            if let Err(err) = refresh_search_document(&state, &doc_id) {
+                eprintln!("failed to update search index after create: {err}");
+            }
+            Ok(Redirect::to(&format!("/edit/{doc_id}")))
+        }
         Err(DocError::BadPath) | Err(DocError::Conflict) => Err((
             StatusCode::BAD_REQUEST,
             templates::NewDocumentTemplate {
@@ -287,35 +322,6 @@ pub(crate) async fn document_create(
     }
 }
 
-pub(crate) async fn document_search(
-    State(state): State<state::AppState>,
-    Query(query): Query<SearchQuery>,
-) -> Result<templates::SearchTemplate, (StatusCode, &'static str)> {
-    let git_enabled = state.git_dir.is_some();
-    let query = query.q.unwrap_or_default();
-    let trimmed = query.trim();
-    let results = if trimmed.is_empty() {
-        Vec::new()
-    } else {
-        search_documents(&state.config.root, trimmed).map_err(|err| {
-            eprintln!("failed to search documents: {err}");
-            (StatusCode::INTERNAL_SERVER_ERROR, "internal error")
-        })?
-    };
-
-    Ok(templates::SearchTemplate {
-        app_name: state.config.app_name,
-        query: trimmed.to_string(),
-        results,
-        git_enabled,
-    })
-}
-
-#[derive(Debug, Deserialize)]
-pub(crate) struct SearchQuery {
-    pub(crate) q: Option<String>,
-}
-
 fn document_view(
     state: state::AppState,
     doc_id: String,
@@ -353,9 +359,21 @@ fn document_view(
         && viewing_ref.is_none()
         && git::git_file_in_head(&state.config.root, &doc_id).unwrap_or(false);
 
-    let rendered = render_document_html(&contents, &doc_id);
+    let (wiki_links, linked_from) = {
+        let index = state.search_index.lock().expect("search index lock");
+        let linked_from = if viewing_ref.is_none() {
+            backlinks(&index, &doc_id)
+        } else {
+            Vec::new()
+        };
+        (index.wiki_link_resolver(), linked_from)
+    };
+    let rendered = render_document_html(&contents, &doc_id, &wiki_links);
 
-    let doc_name = doc_id.rsplit('/').next().unwrap_or(&doc_id).to_string();
+    let doc_name = rendered
+        .title
+        .clone()
+        .unwrap_or_else(|| doc_id.rsplit('/').next().unwrap_or(&doc_id).to_string());
 
     let parent_dir = match doc_id.rfind('/') {
         Some(pos) => &doc_id[..pos],
@@ -369,6 +387,7 @@ fn document_view(
         doc_name,
         breadcrumbs,
         content: rendered.html,
+        backlinks: linked_from,
         has_mermaid: rendered.has_mermaid,
         has_abc: rendered.has_abc,
         has_code: rendered.has_code,
@@ -469,22 +488,118 @@ pub(crate) async fn document_agent_view(
         }
     })?;
 
-    let display_lines = lines_for_display(&contents);
-    let total_lines = display_lines.len();
-    let scan_blocks = scan_block_ranges(&contents);
-    let magent_regions = find_magent_regions(&contents);
-    let blocks = build_agent_blocks(&display_lines, &scan_blocks, &magent_regions, &doc_id);
+    let (blocks, line_count) = agent_blocks(&contents, &doc_id);
 
     Ok(templates::AgentViewTemplate {
         app_name: state.config.app_name,
         doc_id,
         blocks,
-        line_count: total_lines,
+        line_count,
+        apply_edits: state.config.apply_magent_edits,
         git_enabled,
     })
 }
 
+/// How often an open agent view's document is checked for changes.
+const AGENT_EVENTS_POLL: Duration = Duration::from_millis(500);
+
+#[derive(Serialize)]
+struct AgentBlocksEvent {
+    html: String,
+    line_count: usize,
+}
+
+/// Streams the agent view's re-rendered blocks whenever the document changes
+/// on disk, so a magent response shows up while it is being written. The
+/// stream ends when the document is removed.
+pub(crate) async fn document_agent_events(
+    State(state): State<state::AppState>,
+    AxumPath(doc_id): AxumPath<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, &'static str)> {
+    let path = resolve_doc_path(&state.config.root, &doc_id).map_err(|err| match err {
+        DocError::NotFound | DocError::BadPath | DocError::Conflict => {
+            (StatusCode::NOT_FOUND, "document not found")
+        }
+        DocError::Io(err) => {
+            eprintln!("failed to resolve document {doc_id}: {err}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "internal error")
+        }
+    })?;
+    let contents = std::fs::read_to_string(&path).map_err(|err| match err.kind() {
+        ErrorKind::NotFound | ErrorKind::IsADirectory => {
+            (StatusCode::NOT_FOUND, "document not found")
+        }
+        _ => {
+            eprintln!("failed to load document {doc_id}: {err}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "internal error")
+        }
+    })?;
+
+    let events = futures_util::stream::unfold(
+        (doc_id, path, contents),
+        |(doc_id, path, mut contents)| async move {
+            loop {
+                tokio::time::sleep(AGENT_EVENTS_POLL).await;
+                let current = match std::fs::read_to_string(&path) {
+                    Ok(current) => current,
+                    Err(err) => {
+                        if err.kind() != ErrorKind::NotFound {
+                            eprintln!("failed to reload document {doc_id}: {err}");
+                        }
+                        return None;
+                    }
+                };
+                if current == contents {
+                    continue;
+                }
+                contents = current;
+                if let Some(event) = agent_blocks_event(&contents, &doc_id) {
+                    return Some((Ok(event), (doc_id, path, contents)));
+                }
+            }
+        },
+    );
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+fn agent_blocks_event(contents: &str, doc_id: &str) -> Option<Event> {
+    let (blocks, line_count) = agent_blocks(contents, doc_id);
+    let html = match (templates::AgentBlocksTemplate { blocks }).render() {
+        Ok(html) => html,
+        Err(err) => {
+            eprintln!("failed to render agent blocks for {doc_id}: {err}");
+            return None;
+        }
+    };
+    match Event::default()
+        .event("blocks")
+        .json_data(AgentBlocksEvent { html, line_count })
+    {
+        Ok(event) => Some(event),
+        Err(err) => {
+            eprintln!("failed to encode agent blocks for {doc_id}: {err}");
+            None
+        }
+    }
+}
+
+/// The agent view's blocks and the document's line count.
+fn agent_blocks(contents: &str, doc_id: &str) -> (Vec<templates::AgentBlock>, usize) {
+    let display_lines = lines_for_display(contents);
+    let scan_blocks = scan_block_ranges(contents);
+    let magent_regions = find_magent_regions(contents);
+    let blocks = build_agent_blocks(
+        contents,
+        &display_lines,
+        &scan_blocks,
+        &magent_regions,
+        doc_id,
+    );
+    (blocks, display_lines.len())
+}
+
 fn build_agent_blocks(
+    contents: &str,
     lines: &[String],
     scan_blocks: &[crate::documents::BlockRange],
     magent_regions: &[MagentRegion],
@@ -503,7 +618,12 @@ fn build_agent_blocks(
         {
             // Render the full magent response block.
             let raw_text = lines[region.start_line..=region.end_line].join("\n") + "\n";
-            let (html, _) = render_magent_blocks(&raw_text);
+            // Edit indexes count every `<magent-edit>` in the document.
+            let first_edit_index = lines[..region.start_line]
+                .iter()
+                .map(|line| line.matches("<magent-edit").count())
+                .sum();
+            let (html, _) = render_magent_blocks(&raw_text, contents, first_edit_index);
             result.push(templates::AgentBlock {
                 html,
                 start_line: region.start_line,
@@ -600,9 +720,19 @@ pub(crate) async fn document_save(
     if let Err(err) = refresh_push_state(&state) {
         eprintln!("failed to reload push registries after save: {err}");
     }
+    if let Err(err) = refresh_search_document(&state, &doc_id) {
+        eprintln!("failed to update search index after save: {err}");
+    }
+    start_agent(&state, &doc_id, &path);
     if !mentions.is_empty() {
         let registries_snapshot = state.registries.lock().expect("registries lock").clone();
-        push_service::send_mentions(&state.config, &registries_snapshot, &doc_id, &mentions).await;
+        push_service::send_mentions(
+            state.push_scheduler.as_ref(),
+            &registries_snapshot,
+            &doc_id,
+            &mentions,
+        )
+        .await;
     }
 
     Ok(templates::EditTemplate {
@@ -659,6 +789,9 @@ pub(crate) async fn document_toggle_task(
     if let Err(err) = refresh_push_state(&state) {
         eprintln!("failed to reload push registries after toggle: {err}");
     }
+    if let Err(err) = refresh_search_document(&state, doc_id) {
+        eprintln!("failed to update search index after toggle: {err}");
+    }
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -673,7 +806,60 @@ pub(crate) async fn document_accept_magent_edit(
     State(state): State<state::AppState>,
     Form(form): Form<AcceptMagentEditForm>,
 ) -> Result<StatusCode, (StatusCode, &'static str)> {
-    let doc_id = form.doc_id.trim();
+    let apply = state.config.apply_magent_edits;
+    review_magent_edit(&state, &form.doc_id, "accept", |contents| {
+        if !apply {
+            return accept_magent_edit(contents, form.edit_index).ok_or(EDIT_NOT_FOUND);
+        }
+        apply_magent_edit(contents, form.edit_index).map_err(|err| match err {
+            ApplyEditError::NotFound => EDIT_NOT_FOUND,
+            ApplyEditError::Conflict(_) => (
+                StatusCode::CONFLICT,
+                "search text does not match exactly once",
+            ),
+        })
+    })
+}
+
+pub(crate) async fn document_reject_magent_edit(
+    State(state): State<state::AppState>,
+    Form(form): Form<AcceptMagentEditForm>,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    review_magent_edit(&state, &form.doc_id, "reject", |contents| {
+        reject_magent_edit(contents, form.edit_index).ok_or(EDIT_NOT_FOUND)
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ReviseMagentEditForm {
+    pub(crate) doc_id: String,
+    pub(crate) edit_index: usize,
+    pub(crate) comment: String,
+}
+
+pub(crate) async fn document_revise_magent_edit(
+    State(state): State<state::AppState>,
+    Form(form): Form<ReviseMagentEditForm>,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    if form.comment.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "comment is required"));
+    }
+    review_magent_edit(&state, &form.doc_id, "revise", |contents| {
+        request_magent_edit_changes(contents, form.edit_index, &form.comment).ok_or(EDIT_NOT_FOUND)
+    })
+}
+
+const EDIT_NOT_FOUND: (StatusCode, &str) = (StatusCode::NOT_FOUND, "edit not found");
+
+/// Applies a review action to a proposed `<magent-edit>` and saves the
+/// document. `update` returns the updated document or the error response.
+fn review_magent_edit(
+    state: &state::AppState,
+    doc_id: &str,
+    action: &str,
+    update: impl FnOnce(&str) -> Result<String, (StatusCode, &'static str)>,
+) -> Result<StatusCode, (StatusCode, &'static str)> {
+    let doc_id = doc_id.trim();
     if doc_id.is_empty() {
         return Err((StatusCode::BAD_REQUEST, "doc_id is required"));
     }
@@ -696,16 +882,18 @@ pub(crate) async fn document_accept_magent_edit(
         }
     })?;
 
-    let updated = accept_magent_edit(&contents, form.edit_index)
-        .ok_or((StatusCode::NOT_FOUND, "edit not found"))?;
+    let updated = update(&contents)?;
 
     atomic_write(&path, &updated).map_err(|err| {
         eprintln!("failed to save document {doc_id}: {err}");
         (StatusCode::INTERNAL_SERVER_ERROR, "internal error")
     })?;
 
-    if let Err(err) = refresh_push_state(&state) {
-        eprintln!("failed to reload push registries after accept: {err}");
+    if let Err(err) = refresh_push_state(state) {
+        eprintln!("failed to reload push registries after {action}: {err}");
+    }
+    if let Err(err) = refresh_search_document(state, doc_id) {
+        eprintln!("failed to update search index after {action}: {err}");
     }
 
     Ok(StatusCode::NO_CONTENT)
@@ -756,10 +944,32 @@ pub(crate) async fn document_insert_magent_directive(
     if let Err(err) = refresh_push_state(&state) {
         eprintln!("failed to reload push registries after insert: {err}");
     }
+    if let Err(err) = refresh_search_document(&state, doc_id) {
+        eprintln!("failed to update search index after insert: {err}");
+    }
+    start_agent(&state, doc_id, &path);
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Lets the built-in agent answer new `@magent` directives in the document,
+/// when one is configured.
+fn start_agent(state: &state::AppState, doc_id: &str, path: &Path) {
+    let Some(agent) = &state.agent else {
+        return;
+    };
+    let refreshed = state.clone();
+    let refreshed_doc_id = doc_id.to_string();
+    agent.spawn(doc_id, path, move || {
+        if let Err(err) = refresh_push_state(&refreshed) {
+            eprintln!("failed to reload push registries after agent response: {err}");
+        }
+        if let Err(err) = refresh_search_document(&refreshed, &refreshed_doc_id) {
+            eprintln!("failed to update search index after agent response: {err}");
+        }
+    });
+}
+
 #[derive(Debug, Deserialize)]
 pub(crate) struct RemoveMagentInteractionForm {
     pub(crate) doc_id: String,
@@ -804,6 +1014,9 @@ pub(crate) async fn document_remove_magent_interaction(
     if let Err(err) = refresh_push_state(&state) {
         eprintln!("failed to reload push registries after remove: {err}");
     }
+    if let Err(err) = refresh_search_document(&state, doc_id) {
+        eprintln!("failed to update search index after remove: {err}");
+    }
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -855,6 +1068,9 @@ pub(crate) async fn document_add_task(
     if let Err(err) = refresh_push_state(&state) {
         eprintln!("failed to reload push registries after add task: {err}");
     }
+    if let Err(err) = refresh_search_document(&state, doc_id) {
+        eprintln!("failed to update search index after add task: {err}");
+    }
 
     Ok(Redirect::to(&format!("/d/{doc_id}")))
 }
@@ -945,6 +1161,9 @@ pub(crate) async fn document_reorder_range(
     if let Err(err) = refresh_push_state(&state) {
         eprintln!("failed to reload push registries after reorder: {err}");
     }
+    if let Err(err) = refresh_search_document(&state, doc_id) {
+        eprintln!("failed to update search index after reorder: {err}");
+    }
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -953,28 +1172,104 @@ pub(crate) async fn document_reorder_range(
 pub(crate) struct MoveFileForm {
     pub(crate) source_path: String,
     pub(crate) target_dir: String,
+    #[serde(default)]
+    pub(crate) new_name: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct MoveFileResponse {
+    pub(crate) moved_to: String,
+    pub(crate) updated: Vec<RelinkedEntry>,
+    /// Documents whose links to the moved file could not be rewritten.
+    pub(crate) failed: Vec<RelinkFailureEntry>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct RelinkedEntry {
+    pub(crate) doc_id: String,
+    pub(crate) changes: Vec<LinkChangeEntry>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct RelinkFailureEntry {
+    pub(crate) doc_id: String,
+    pub(crate) error: String,
+}
+
+#[derive(Serialize)]
+pub(crate) struct LinkChangeEntry {
+    /// One-based line number of the rewritten link.
+    pub(crate) line: usize,
+    pub(crate) from: String,
+    pub(crate) to: String,
 }
 
 pub(crate) async fn document_move_file(
     State(state): State<state::AppState>,
     Form(form): Form<MoveFileForm>,
-) -> Result<StatusCode, (StatusCode, &'static str)> {
-    move_file(&state.config.root, &form.source_path, &form.target_dir).map_err(
-        |err| match err {
-            DocError::BadPath => (StatusCode::BAD_REQUEST, "invalid path"),
-            DocError::NotFound => (StatusCode::NOT_FOUND, "not found"),
-            DocError::Conflict => (StatusCode::CONFLICT, "destination already exists"),
-            DocError::Io(err) => {
-                eprintln!(
-                    "failed to move file {} to {}: {err}",
-                    form.source_path, form.target_dir
-                );
-                (StatusCode::INTERNAL_SERVER_ERROR, "internal error")
-            }
-        },
-    )?;
+) -> Result<Json<MoveFileResponse>, (StatusCode, &'static str)> {
+    let moved = move_file(
+        &state.config.root,
+        &form.source_path,
+        &form.target_dir,
+        form.new_name.as_deref(),
+    )
+    .map_err(|err| match err {
+        DocError::BadPath => (StatusCode::BAD_REQUEST, "invalid path"),
+        DocError::NotFound => (StatusCode::NOT_FOUND, "not found"),
+        DocError::Conflict => (StatusCode::CONFLICT, "destination already exists"),
+        DocError::Io(err) => {
+            eprintln!(
+                "failed to move file {} to {}: {err}",
+                form.source_path, form.target_dir
+            );
+            (StatusCode::INTERNAL_SERVER_ERROR, "internal error")
+        }
+    })?;
 
-    Ok(StatusCode::NO_CONTENT)
+    let relinked_ids = moved
+        .relinked
+        .documents
+        .iter()
+        .map(|doc| doc.doc_id.as_str());
+    for doc_id in [form.source_path.as_str(), moved.doc_id.as_str()]
+        .into_iter()
+        .chain(relinked_ids)
+    {
+        if let Err(err) = refresh_search_document(&state, doc_id) {
+            eprintln!("failed to update search index after move: {err}");
+        }
+    }
+
+    Ok(Json(MoveFileResponse {
+        updated: moved
+            .relinked
+            .documents
+            .into_iter()
+            .map(|doc| RelinkedEntry {
+                doc_id: doc.doc_id,
+                changes: doc
+                    .changes
+                    .into_iter()
+                    .map(|change| LinkChangeEntry {
+                        line: change.line + 1,
+                        from: change.from,
+                        to: change.to,
+                    })
+                    .collect(),
+            })
+            .collect(),
+        failed: moved
+            .relinked
+            .failed
+            .into_iter()
+            .map(|failure| RelinkFailureEntry {
+                doc_id: failure.doc_id,
+                error: failure.error,
+            })
+            .collect(),
+        moved_to: moved.doc_id,
+    }))
 }
 
 #[derive(Debug, Deserialize)]
@@ -996,6 +1291,10 @@ pub(crate) async fn document_delete_file(
         }
     })?;
 
+    if let Err(err) = refresh_search_document(&state, &form.file_path) {
+        eprintln!("failed to update search index after delete: {err}");
+    }
+
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -1029,6 +1328,10 @@ pub(crate) async fn document_restore_file(
         }
     })?;
 
+    if let Err(err) = refresh_search_document(&state, &form.doc_id) {
+        eprintln!("failed to update search index after restore: {err}");
+    }
+
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -1081,24 +1384,7 @@ fn file_manage_view(
         .unwrap_or(&current_dir)
         .to_string();
 
-    let files = listing
-        .files
-        .into_iter()
-        .map(|f| {
-            let full_path = format!("{path_prefix}{}", f.name);
-            let url = match f.kind {
-                FileKind::Document => format!("/d/{full_path}"),
-                FileKind::Pdf => format!("/pdf/{full_path}"),
-                FileKind::Image => format!("/file/{full_path}"),
-                FileKind::Text => format!("/view/{full_path}"),
-            };
-            templates::DirectoryFileEntry {
-                name: f.name,
-                kind: f.kind,
-                url,
-            }
-        })
-        .collect();
+    let files = directory_file_entries(&state, &path_prefix, listing.files);
 
     Ok(templates::FileManageTemplate {
         app_name: state.config.app_name,
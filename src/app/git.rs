@@ -1,4 +1,5 @@
 use crate::app::auth;
+use crate::app::search;
 use crate::git;
 use crate::state;
 use crate::templates;
@@ -101,6 +102,7 @@ pub(crate) async fn git_pull(
             return git_template(&state, String::new(), err.to_string(), String::new());
         }
     };
+    search::rebuild_search_index(&state);
 
     git_template(&state, String::new(), String::new(), notice)
 }
@@ -118,6 +120,7 @@ pub(crate) async fn git_reset(
             return git_template(&state, String::new(), err.to_string(), String::new());
         }
     };
+    search::rebuild_search_index(&state);
 
     git_template(&state, String::new(), String::new(), notice)
 }
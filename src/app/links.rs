@@ -0,0 +1,66 @@
+use crate::documents::{check_links, link_graph};
+use crate::state;
+use crate::templates;
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub(crate) struct GraphResponse {
+    pub(crate) nodes: Vec<GraphNodeEntry>,
+    pub(crate) edges: Vec<GraphEdgeEntry>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct GraphNodeEntry {
+    pub(crate) id: String,
+    pub(crate) title: String,
+}
+
+#[derive(Serialize)]
+pub(crate) struct GraphEdgeEntry {
+    pub(crate) source: String,
+    pub(crate) target: String,
+}
+
+pub(crate) async fn graph_api(State(state): State<state::AppState>) -> Json<GraphResponse> {
+    let graph = {
+        let index = state.search_index.lock().expect("search index lock");
+        link_graph(&index)
+    };
+    Json(GraphResponse {
+        nodes: graph
+            .nodes
+            .into_iter()
+            .map(|node| GraphNodeEntry {
+                id: node.id,
+                title: node.title,
+            })
+            .collect(),
+        edges: graph
+            .edges
+            .into_iter()
+            .map(|edge| GraphEdgeEntry {
+                source: edge.source,
+                target: edge.target,
+            })
+            .collect(),
+    })
+}
+
+pub(crate) async fn broken_links_view(
+    State(state): State<state::AppState>,
+) -> Result<templates::BrokenLinksTemplate, (StatusCode, &'static str)> {
+    let git_enabled = state.git_dir.is_some();
+    let links = check_links(&state.config.root).map_err(|err| {
+        eprintln!("failed to check links: {err}");
+        (StatusCode::INTERNAL_SERVER_ERROR, "failed to check links")
+    })?;
+    Ok(templates::BrokenLinksTemplate {
+        app_name: state.config.app_name,
+        links,
+        git_enabled,
+    })
+}
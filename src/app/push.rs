@@ -1,17 +1,22 @@
 use crate::adapters::WebPushSender;
 use crate::ports::push::PushSender;
 use crate::push as push_service;
+use crate::push::history::DeliveryRecord;
+use crate::push::time_zone::format_local;
 use crate::state;
 use crate::templates;
 use crate::types::directives;
 
 use axum::Json;
+use axum::extract::Query;
 use axum::extract::State;
 use axum::http::StatusCode;
 use serde::Deserialize;
 use serde::Serialize;
 use time::OffsetDateTime;
 
+use super::search::refresh_search_document;
+
 pub(crate) async fn push_registry_debug(
     State(state): State<state::AppState>,
 ) -> Json<directives::DirectiveRegistries> {
@@ -27,10 +32,25 @@ pub(crate) struct PushScheduleDebugResponse {
 
 #[derive(Serialize, Deserialize)]
 pub(crate) struct PushScheduleEntry {
+    /// Matches `notification_id` in the delivery history.
+    pub(crate) id: String,
     pub(crate) doc_id: String,
     pub(crate) at: OffsetDateTime,
     pub(crate) message: String,
     pub(crate) to: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) repeat: Option<directives::Repeat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) until: Option<OffsetDateTime>,
+    /// The next time the notification goes out, if it still will.
+    pub(crate) next_at: Option<OffsetDateTime>,
+    /// Time zone of the local times; UTC when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) tz: Option<String>,
+    pub(crate) at_utc: String,
+    pub(crate) at_local: String,
+    pub(crate) next_at_utc: Option<String>,
+    pub(crate) next_at_local: Option<String>,
     pub(crate) scheduled_at: OffsetDateTime,
     pub(crate) finished: bool,
 }
@@ -43,13 +63,27 @@ pub(crate) async fn push_schedule_debug(
         let handles = state.push_handles.lock().expect("push handles lock");
         handles
             .iter()
-            .map(|handle| PushScheduleEntry {
-                doc_id: handle.notification.doc_id.clone(),
-                at: handle.notification.at,
-                message: handle.notification.message.clone(),
-                to: handle.notification.to.clone(),
-                scheduled_at: handle.scheduled_at,
-                finished: handle.is_finished(),
+            .map(|handle| {
+                let notification = &handle.notification;
+                let tz = notification.tz.as_deref();
+                let next_at = push_service::recurrence::next_occurrence(notification, server_time);
+                PushScheduleEntry {
+                    id: handle.id.clone(),
+                    doc_id: notification.doc_id.clone(),
+                    at: notification.at,
+                    message: notification.message.clone(),
+                    to: notification.to.clone(),
+                    repeat: notification.repeat.clone(),
+                    until: notification.until,
+                    next_at,
+                    tz: notification.tz.clone(),
+                    at_utc: format_local(notification.at, None),
+                    at_local: format_local(notification.at, tz),
+                    next_at_utc: next_at.map(|next_at| format_local(next_at, None)),
+                    next_at_local: next_at.map(|next_at| format_local(next_at, tz)),
+                    scheduled_at: handle.scheduled_at,
+                    finished: handle.is_finished(),
+                }
             })
             .collect()
     };
@@ -159,6 +193,7 @@ pub(crate) async fn push_test(
         endpoint: request.endpoint,
         p256dh: request.p256dh,
         auth: request.auth,
+        doc_id: String::new(),
     };
 
     if let Err(err) = sender.send(&subscription, message).await {
@@ -183,6 +218,77 @@ pub(crate) async fn push_subscribe(
     }
 }
 
+/// Records per history page unless `limit` asks for another number.
+const HISTORY_PAGE_SIZE: usize = 100;
+const MAX_HISTORY_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PushHistoryQuery {
+    limit: Option<usize>,
+    /// Only records attempted before this time, for the next page.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    before: Option<OffsetDateTime>,
+}
+
+pub(crate) async fn push_history(
+    State(state): State<state::AppState>,
+    Query(query): Query<PushHistoryQuery>,
+) -> templates::PushHistoryTemplate {
+    let limit = history_limit(&query);
+    let records = state.delivery_log.newest(limit, query.before);
+    let older = (records.len() >= limit)
+        .then(|| records.last())
+        .flatten()
+        .and_then(|record| {
+            record
+                .attempted_at
+                .to_offset(time::UtcOffset::UTC)
+                .format(&time::format_description::well_known::Rfc3339)
+                .ok()
+        });
+    templates::PushHistoryTemplate {
+        app_name: state.config.app_name.clone(),
+        records,
+        older,
+        git_enabled: state.git_dir.is_some(),
+    }
+}
+
+pub(crate) async fn push_history_api(
+    State(state): State<state::AppState>,
+    Query(query): Query<PushHistoryQuery>,
+) -> Json<Vec<DeliveryRecord>> {
+    Json(
+        state
+            .delivery_log
+            .newest(history_limit(&query), query.before),
+    )
+}
+
+fn history_limit(query: &PushHistoryQuery) -> usize {
+    query
+        .limit
+        .unwrap_or(HISTORY_PAGE_SIZE)
+        .clamp(1, MAX_HISTORY_PAGE_SIZE)
+}
+
+/// Refreshes the registries and search index after the scheduler removes an
+/// expired subscription block from a document.
+pub(crate) fn refresh_after_pruning(state: &state::AppState) {
+    let Some(scheduler) = &state.push_scheduler else {
+        return;
+    };
+    let refreshed = state.clone();
+    scheduler.on_pruned(move |doc_id| {
+        if let Err(err) = refresh_push_state(&refreshed) {
+            eprintln!("failed to reload push registries after pruning: {err}");
+        }
+        if let Err(err) = refresh_search_document(&refreshed, doc_id) {
+            eprintln!("failed to update search index after pruning: {err}");
+        }
+    });
+}
+
 pub(crate) fn refresh_push_state(state: &state::AppState) -> std::io::Result<()> {
     let registries = directives::DirectiveRegistries::load(&state.config.root)?;
     {
@@ -190,7 +296,7 @@ pub(crate) fn refresh_push_state(state: &state::AppState) -> std::io::Result<()>
         *guard = registries.clone();
     }
     push_service::restart_scheduler(
-        &state.config,
+        state.push_scheduler.as_ref(),
         std::sync::Arc::new(registries),
         std::sync::Arc::clone(&state.push_handles),
     );
@@ -0,0 +1,163 @@
+use crate::config;
+use crate::documents::{DocumentRefresh, SearchIndex, SearchResult, search_documents};
+use crate::state;
+use crate::templates;
+
+use axum::Json;
+use axum::extract::Query;
+use axum::extract::State;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Results returned when a search does not ask for a `limit`.
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+const MAX_SEARCH_LIMIT: usize = 500;
+
+pub(crate) async fn document_search(
+    State(state): State<state::AppState>,
+    Query(query): Query<SearchQuery>,
+) -> templates::SearchTemplate {
+    let git_enabled = state.git_dir.is_some();
+    let limit = search_limit(&query);
+    let query = query.q.unwrap_or_default();
+    let trimmed = query.trim();
+    let results = if trimmed.is_empty() {
+        Vec::new()
+    } else {
+        let index = state.search_index.lock().expect("search index lock");
+        search_documents(&index, trimmed, limit)
+    };
+
+    templates::SearchTemplate {
+        app_name: state.config.app_name,
+        query: trimmed.to_string(),
+        truncated: results.len() == limit,
+        results,
+        git_enabled,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SearchQuery {
+    pub(crate) q: Option<String>,
+    /// Maximum number of results, best first.
+    pub(crate) limit: Option<usize>,
+}
+
+fn search_limit(query: &SearchQuery) -> usize {
+    query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT)
+}
+
+#[derive(Serialize)]
+pub(crate) struct SearchHit {
+    pub(crate) doc_id: String,
+    pub(crate) kind: &'static str,
+    pub(crate) title: String,
+    pub(crate) score: f32,
+    pub(crate) url: String,
+    pub(crate) snippets: Vec<SearchHitSnippet>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SearchHitSnippet {
+    pub(crate) text: String,
+    /// Byte ranges within `text` that matched the query.
+    pub(crate) highlights: Vec<ByteRange>,
+    /// 1-based line number within the file (or the extracted text of a PDF).
+    pub(crate) line: usize,
+    /// Byte range of `text` within the file, excluding any added `…`.
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) anchor: Option<String>,
+    pub(crate) page: Option<usize>,
+    pub(crate) url: String,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ByteRange {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+pub(crate) async fn search_api(
+    State(state): State<state::AppState>,
+    Query(query): Query<SearchQuery>,
+) -> Json<Vec<SearchHit>> {
+    let limit = search_limit(&query);
+    let query = query.q.unwrap_or_default();
+    let trimmed = query.trim();
+    if trimmed.is_empty() {
+        return Json(Vec::new());
+    }
+    let results = {
+        let index = state.search_index.lock().expect("search index lock");
+        search_documents(&index, trimmed, limit)
+    };
+    Json(results.into_iter().map(search_hit).collect())
+}
+
+fn search_hit(result: SearchResult) -> SearchHit {
+    let url = result.url();
+    let snippets = result
+        .snippets
+        .iter()
+        .map(|snippet| SearchHitSnippet {
+            text: snippet.text.clone(),
+            highlights: snippet
+                .highlights
+                .iter()
+                .map(|range| ByteRange {
+                    start: range.start,
+                    end: range.end,
+                })
+                .collect(),
+            line: snippet.line,
+            start: snippet.source.start,
+            end: snippet.source.end,
+            anchor: snippet.anchor.clone(),
+            page: snippet.page,
+            url: result.snippet_url(snippet),
+        })
+        .collect();
+    SearchHit {
+        kind: result.kind.as_str(),
+        doc_id: result.doc_id,
+        title: result.title,
+        score: result.score,
+        url,
+        snippets,
+    }
+}
+
+pub(crate) fn load_search_index(config: &config::AppConfig) -> SearchIndex {
+    let loaded = if config.search_index_cache {
+        SearchIndex::load_cached(&config.root)
+    } else {
+        SearchIndex::build(&config.root)
+    };
+    loaded.unwrap_or_else(|err| {
+        eprintln!("failed to build search index: {err}");
+        SearchIndex::default()
+    })
+}
+
+/// Re-reads `doc_id` from disk into the search index, or drops it if it is gone.
+pub(crate) fn refresh_search_document(
+    state: &state::AppState,
+    doc_id: &str,
+) -> std::io::Result<()> {
+    // Read first: PDF text extraction must not hold up searches.
+    let refresh = DocumentRefresh::read(&state.config.root, doc_id)?;
+    let mut index = state.search_index.lock().expect("search index lock");
+    index.apply_refresh(refresh);
+    Ok(())
+}
+
+/// Rebuilds the whole search index after changes the server did not make itself.
+pub(crate) fn rebuild_search_index(state: &state::AppState) {
+    let rebuilt = load_search_index(&state.config);
+    *state.search_index.lock().expect("search index lock") = rebuilt;
+}
@@ -0,0 +1,36 @@
+use crate::documents::{normalize_tag, tag_counts, tagged_documents};
+use crate::state;
+use crate::templates;
+
+use axum::extract::{Path, State};
+
+pub(crate) async fn tag_list_view(State(state): State<state::AppState>) -> templates::TagsTemplate {
+    let git_enabled = state.git_dir.is_some();
+    let tags = {
+        let index = state.search_index.lock().expect("search index lock");
+        tag_counts(&index)
+    };
+    templates::TagsTemplate {
+        app_name: state.config.app_name,
+        tags,
+        git_enabled,
+    }
+}
+
+pub(crate) async fn tag_view(
+    State(state): State<state::AppState>,
+    Path(tag): Path<String>,
+) -> templates::TagTemplate {
+    let git_enabled = state.git_dir.is_some();
+    let tag = normalize_tag(&tag);
+    let documents = {
+        let index = state.search_index.lock().expect("search index lock");
+        tagged_documents(&index, &tag)
+    };
+    templates::TagTemplate {
+        app_name: state.config.app_name,
+        tag,
+        documents,
+        git_enabled,
+    }
+}
@@ -0,0 +1,54 @@
+use crate::documents::{TaskFilter, TaskStatus, collect_board_tasks, task_filter_options};
+use crate::state;
+use crate::templates;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct TaskBoardQuery {
+    #[serde(default)]
+    pub(crate) folder: String,
+    #[serde(default)]
+    pub(crate) assignee: String,
+    #[serde(default)]
+    pub(crate) status: String,
+    #[serde(default)]
+    pub(crate) tag: String,
+}
+
+pub(crate) async fn task_board_view(
+    State(state): State<state::AppState>,
+    Query(query): Query<TaskBoardQuery>,
+) -> Result<templates::TaskBoardTemplate, (StatusCode, &'static str)> {
+    let git_enabled = state.git_dir.is_some();
+    let status = if query.status.is_empty() {
+        TaskStatus::default()
+    } else {
+        TaskStatus::parse(&query.status).ok_or((StatusCode::BAD_REQUEST, "invalid status"))?
+    };
+    let filter = TaskFilter {
+        folder: Some(query.folder.clone()),
+        assignee: Some(query.assignee.clone()),
+        status,
+        tag: Some(query.tag.clone()),
+    };
+    let (tasks, options) = {
+        let index = state.search_index.lock().expect("search index lock");
+        (
+            collect_board_tasks(&index, &filter),
+            task_filter_options(&index),
+        )
+    };
+    Ok(templates::TaskBoardTemplate {
+        app_name: state.config.app_name,
+        tasks,
+        options,
+        folder: query.folder,
+        assignee: query.assignee,
+        status: status.as_str(),
+        tag: query.tag,
+        git_enabled,
+    })
+}
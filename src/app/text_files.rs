@@ -1,3 +1,4 @@
+use crate::app::search::refresh_search_document;
 use crate::documents::{
     DocError, highlight_lang_for_extension, load_text_file, resolve_text_file_path,
 };
@@ -114,6 +115,9 @@ pub(crate) async fn text_save(
         eprintln!("failed to save text file {file_id}: {err}");
         (StatusCode::INTERNAL_SERVER_ERROR, "internal error")
     })?;
+    if let Err(err) = refresh_search_document(&state, &file_id) {
+        eprintln!("failed to update search index after text save: {err}");
+    }
 
     Ok(templates::TextEditTemplate {
         app_name: state.config.app_name,
@@ -1,3 +1,4 @@
+use crate::app::search::refresh_search_document;
 use crate::state;
 use crate::templates;
 use crate::uploads;
@@ -200,6 +201,18 @@ pub(crate) async fn upload_image(
         }
     };
 
+    // Uploaded PDFs have their text extracted, which takes a while.
+    let indexed = {
+        let state = state.clone();
+        let rel_path = stored.rel_path.clone();
+        tokio::task::spawn_blocking(move || refresh_search_document(&state, &rel_path))
+    };
+    match indexed.await {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => eprintln!("failed to update search index after upload: {err}"),
+        Err(err) => eprintln!("failed to update search index after upload: {err}"),
+    }
+
     let url = format!("/file/{}", stored.rel_path);
     let display_name = Path::new(&stored.rel_path)
         .file_name()
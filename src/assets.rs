@@ -208,6 +208,16 @@ pub(crate) async fn fuzzy_script() -> axum::response::Response {
         .unwrap()
 }
 
+pub(crate) async fn pdf_page_script() -> axum::response::Response {
+    const PDF_PAGE_JS_CONTENT: &str = include_str!("../assets/features/pdf_page.js");
+    axum::response::Response::builder()
+        .status(200)
+        .header("content-type", "application/javascript")
+        .header("cache-control", "public, max-age=3600")
+        .body(PDF_PAGE_JS_CONTENT.into())
+        .unwrap()
+}
+
 pub(crate) async fn sw_register_script() -> axum::response::Response {
     const SW_REGISTER_JS_CONTENT: &str = include_str!("../assets/features/sw_register.js");
     axum::response::Response::builder()
@@ -13,8 +13,25 @@ pub struct AppConfig {
     pub vapid_private_key: Option<String>,
     pub vapid_public_key: Option<String>,
     pub vapid_subject: Option<String>,
+    /// SMTP server URL for the email notification channel.
+    pub smtp_url: Option<String>,
+    /// Sender address of notification emails.
+    pub smtp_from: Option<String>,
     pub auth: Option<AuthConfig>,
     pub git_allowed_remote_roots: Vec<PathBuf>,
+    pub search_index_cache: bool,
+    /// How long before a task is due its assignees get a push reminder.
+    pub task_reminder_lead: Duration,
+    /// Whether accepting a magent edit also performs its search/replace.
+    pub apply_magent_edits: bool,
+    /// Shell command that answers `@magent` directives over stdin/stdout.
+    pub agent_command: Option<String>,
+    /// Base URL of an OpenAI-compatible API that answers `@magent` directives.
+    pub agent_url: Option<String>,
+    pub agent_model: Option<String>,
+    pub agent_api_key: Option<String>,
+    /// How long the agent may take to answer one directive.
+    pub agent_timeout: Duration,
 }
 
 #[derive(Clone)]
@@ -38,8 +55,18 @@ impl Default for AppConfig {
             vapid_private_key: None,
             vapid_public_key: None,
             vapid_subject: None,
+            smtp_url: None,
+            smtp_from: None,
             auth: None,
             git_allowed_remote_roots: Vec::new(),
+            search_index_cache: false,
+            task_reminder_lead: Duration::days(1),
+            apply_magent_edits: false,
+            agent_command: None,
+            agent_url: None,
+            agent_model: None,
+            agent_api_key: None,
+            agent_timeout: Duration::minutes(5),
         }
     }
 }
@@ -1,9 +1,14 @@
-use crate::documents::{collect_markdown_paths, doc_id_from_path};
-use crate::types::directives::{DirectiveRegistries, Notification, Subscription, User};
+use crate::documents::{collect_markdown_paths, collect_mentions, collect_tasks, doc_id_from_path};
+use crate::push::recurrence::parse_repeat;
+use crate::push::time_zone::{from_local, group_by_zone, parse_zone};
+use crate::types::directives::{
+    Channel, DirectiveRegistries, LocalTimes, Notification, Repeat, Subscription, TaskReminder,
+    User,
+};
 
 use serde::Deserialize;
-use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, PrimitiveDateTime};
 
 use std::path::Path;
 
@@ -24,7 +29,9 @@ impl DirectiveRegistries {
                     warning.doc_id, warning.line, warning.message
                 );
             }
+            collect_task_reminders(&doc_id, &contents, &mut registries);
         }
+        resolve_local_times(&mut registries);
         Ok(registries)
     }
 }
@@ -233,6 +240,11 @@ struct UserToml {
     display_name: Option<String>,
     email: Option<String>,
     password_hash: Option<String>,
+    tz: Option<String>,
+    #[serde(default)]
+    channels: Vec<String>,
+    webhook: Option<String>,
+    ntfy: Option<String>,
 }
 
 fn parse_user_block(
@@ -295,6 +307,59 @@ fn parse_user_block(
         return;
     }
 
+    let tz = parsed
+        .tz
+        .map(|tz| tz.trim().to_string())
+        .filter(|tz| !tz.is_empty());
+    let tz = match tz {
+        Some(tz) => match parse_zone(&tz) {
+            Ok(_) => Some(tz),
+            Err(err) => {
+                push_warning(
+                    warnings,
+                    doc_id,
+                    block_line,
+                    format!("invalid /user block: {err}, using UTC"),
+                );
+                None
+            }
+        },
+        None => None,
+    };
+
+    let webhook = notification_url(parsed.webhook, "webhook", warnings, doc_id, block_line);
+    let ntfy = notification_url(parsed.ntfy, "ntfy", warnings, doc_id, block_line);
+    let mut channels: Vec<Channel> = Vec::new();
+    for value in &parsed.channels {
+        let value = value.trim().to_ascii_lowercase();
+        let Some(channel) = Channel::parse(&value) else {
+            push_warning(
+                warnings,
+                doc_id,
+                block_line,
+                format!("invalid /user block: unknown channel '{value}'"),
+            );
+            continue;
+        };
+        let missing = match channel {
+            Channel::Webhook if webhook.is_none() => Some("webhook"),
+            Channel::Ntfy if ntfy.is_none() => Some("ntfy"),
+            _ => None,
+        };
+        if let Some(key) = missing {
+            push_warning(
+                warnings,
+                doc_id,
+                block_line,
+                format!("invalid /user block: the {key} channel needs a {key} URL"),
+            );
+            continue;
+        }
+        if !channels.contains(&channel) {
+            channels.push(channel);
+        }
+    }
+
     if registries.users.contains_key(name) {
         push_warning(
             warnings,
@@ -312,6 +377,10 @@ fn parse_user_block(
             display_name,
             email: email.to_string(),
             password_hash: password_hash.to_string(),
+            tz,
+            channels,
+            webhook,
+            ntfy,
         },
     );
 }
@@ -377,9 +446,66 @@ fn parse_subscription_block(
             endpoint: endpoint.to_string(),
             p256dh: p256dh.to_string(),
             auth: auth.to_string(),
+            doc_id: doc_id.to_string(),
         });
 }
 
+/// Replaces the `/subscription` block for `endpoint`, from the directive line
+/// through its closing fence, with `note`. Returns `None` when `contents` has
+/// no such block.
+pub(crate) fn remove_subscription_block(
+    contents: &str,
+    endpoint: &str,
+    note: &str,
+) -> Option<String> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut idx = 0usize;
+    while idx < lines.len() {
+        let Some(kind) = parse_directive_line(lines[idx].trim()) else {
+            idx += 1;
+            continue;
+        };
+        let directive_idx = idx;
+        let is_subscription = matches!(kind, DirectiveKind::Subscription);
+        idx += 1;
+        while idx < lines.len()
+            && parse_directive_line(lines[idx].trim()).is_none()
+            && parse_fence_line(lines[idx].trim()).is_none()
+        {
+            idx += 1;
+        }
+        let Some(fence) = lines
+            .get(idx)
+            .and_then(|line| parse_fence_line(line.trim()))
+        else {
+            continue;
+        };
+        let block_start = idx + 1;
+        let close_idx = (block_start..lines.len())
+            .find(|&close_idx| is_fence_close(lines[close_idx], fence.ch, fence.count))?;
+        idx = close_idx + 1;
+        if !is_subscription || !is_toml_language(fence.language.as_deref()) {
+            continue;
+        }
+        let toml_text = lines[block_start..close_idx].join("\n");
+        let matches = toml::from_str::<SubscriptionToml>(&toml_text)
+            .is_ok_and(|parsed| parsed.endpoint.trim() == endpoint);
+        if !matches {
+            continue;
+        }
+
+        let mut updated: Vec<&str> = lines[..directive_idx].to_vec();
+        updated.push(note);
+        updated.extend_from_slice(&lines[close_idx + 1..]);
+        let mut updated = updated.join("\n");
+        if contents.ends_with('\n') {
+            updated.push('\n');
+        }
+        return Some(updated);
+    }
+    None
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 enum NotifyTo {
@@ -392,6 +518,11 @@ struct NotificationToml {
     to: NotifyTo,
     at: String,
     message: String,
+    every: Option<String>,
+    cron: Option<String>,
+    rrule: Option<String>,
+    until: Option<String>,
+    tz: Option<String>,
 }
 
 fn parse_notify_block(
@@ -422,27 +553,188 @@ fn parse_notify_block(
         }
     };
 
-    let at = match OffsetDateTime::parse(parsed.at.trim(), &Rfc3339) {
-        Ok(at) => at,
-        Err(err) => {
+    let repeat = match (parsed.every, parsed.cron, parsed.rrule) {
+        (None, None, None) => None,
+        (Some(every), None, None) => Some(Repeat::Every(every)),
+        (None, Some(cron), None) => Some(Repeat::Cron(cron)),
+        (None, None, Some(rrule)) => Some(Repeat::Rrule(rrule)),
+        _ => {
             push_warning(
                 warnings,
                 doc_id,
                 block_line,
-                format!("invalid /notify block: at must be RFC3339 ({err})"),
+                "invalid /notify block: use only one of every, cron and rrule",
             );
             return;
         }
     };
+    if let Some(repeat) = &repeat
+        && let Err(err) = parse_repeat(repeat)
+    {
+        let field = match repeat {
+            Repeat::Every(_) => "every",
+            Repeat::Cron(_) => "cron",
+            Repeat::Rrule(_) => "rrule",
+        };
+        push_warning(
+            warnings,
+            doc_id,
+            block_line,
+            format!("invalid /notify block: {field} ({err})"),
+        );
+        return;
+    }
+
+    let tz = match parsed.tz.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(tz) => match parse_zone(tz) {
+            Ok(zone) => Some((tz.to_string(), zone)),
+            Err(err) => {
+                push_warning(
+                    warnings,
+                    doc_id,
+                    block_line,
+                    format!("invalid /notify block: {err}"),
+                );
+                return;
+            }
+        },
+    };
+    let zone = tz.as_ref().map(|(_, zone)| zone);
+
+    let at = match parse_notify_time(parsed.at.trim()) {
+        Some(at) => at,
+        None => {
+            push_warning(
+                warnings,
+                doc_id,
+                block_line,
+                "invalid /notify block: at must be RFC3339 or a local YYYY-MM-DDTHH:MM[:SS]",
+            );
+            return;
+        }
+    };
+    let until = match parsed.until.as_deref().map(str::trim) {
+        None => None,
+        Some(until) => match parse_notify_time(until) {
+            Some(until) => Some(until),
+            None => {
+                push_warning(
+                    warnings,
+                    doc_id,
+                    block_line,
+                    "invalid /notify block: until must be RFC3339 or a local YYYY-MM-DDTHH:MM[:SS]",
+                );
+                return;
+            }
+        },
+    };
+
+    // Local times in a block without `tz` wait for the recipients' zones.
+    let local = LocalTimes {
+        at: at.local(),
+        until: until.and_then(NotifyTime::local),
+    };
+    let pending_local =
+        (zone.is_none() && (local.at.is_some() || local.until.is_some())).then_some(local);
+    let Some(at) = at.resolve(zone) else {
+        push_warning(
+            warnings,
+            doc_id,
+            block_line,
+            "invalid /notify block: at is out of range",
+        );
+        return;
+    };
+    let until = until.and_then(|until| until.resolve(zone));
 
     registries.notifications.push(Notification {
         to,
         at,
         message: parsed.message,
         doc_id: doc_id.to_string(),
+        line: block_line,
+        repeat,
+        until,
+        tz: tz.map(|(name, _)| name),
+        pending_local,
     });
 }
 
+/// A `/notify` time, with an offset or as a wall clock time.
+#[derive(Debug, Clone, Copy)]
+enum NotifyTime {
+    Exact(OffsetDateTime),
+    Local(PrimitiveDateTime),
+}
+
+impl NotifyTime {
+    fn local(self) -> Option<PrimitiveDateTime> {
+        match self {
+            NotifyTime::Exact(_) => None,
+            NotifyTime::Local(local) => Some(local),
+        }
+    }
+
+    fn resolve(self, zone: Option<&jiff::tz::TimeZone>) -> Option<OffsetDateTime> {
+        match self {
+            NotifyTime::Exact(at) => Some(at),
+            NotifyTime::Local(local) => from_local(local, zone),
+        }
+    }
+}
+
+fn parse_notify_time(value: &str) -> Option<NotifyTime> {
+    if let Ok(at) = OffsetDateTime::parse(value, &Rfc3339) {
+        return Some(NotifyTime::Exact(at));
+    }
+    [
+        "[year]-[month]-[day]T[hour]:[minute]:[second]",
+        "[year]-[month]-[day]T[hour]:[minute]",
+        "[year]-[month]-[day] [hour]:[minute]:[second]",
+        "[year]-[month]-[day] [hour]:[minute]",
+    ]
+    .iter()
+    .filter_map(|description| time::format_description::parse(description).ok())
+    .find_map(|format| PrimitiveDateTime::parse(value, &format).ok())
+    .map(NotifyTime::Local)
+}
+
+/// Resolves notifications with pending local times in the time zone of each
+/// recipient's `/user` block, splitting them when recipients are in different
+/// zones. Recipients without a zone get UTC.
+fn resolve_local_times(registries: &mut DirectiveRegistries) {
+    let notifications = std::mem::take(&mut registries.notifications);
+    for notification in notifications {
+        let Some(local) = notification.pending_local else {
+            registries.notifications.push(notification);
+            continue;
+        };
+        for (tz, to) in group_by_zone(&notification.to, &registries.users) {
+            let zone = tz.as_deref().and_then(|tz| parse_zone(tz).ok());
+            let at = match local.at {
+                Some(at) => from_local(at, zone.as_ref()),
+                None => Some(notification.at),
+            };
+            let Some(at) = at else {
+                continue;
+            };
+            let until = match local.until {
+                Some(until) => from_local(until, zone.as_ref()),
+                None => notification.until,
+            };
+            registries.notifications.push(Notification {
+                to,
+                at,
+                until,
+                tz,
+                pending_local: None,
+                ..notification.clone()
+            });
+        }
+    }
+}
+
 fn normalize_recipients(to: NotifyTo) -> Result<Vec<String>, &'static str> {
     let raw = match to {
         NotifyTo::One(value) => vec![value],
@@ -461,6 +753,75 @@ fn normalize_recipients(to: NotifyTo) -> Result<Vec<String>, &'static str> {
     }
 }
 
+/// Registers a reminder for every open task with a due date that mentions
+/// at least one user. Checked tasks are left out, so toggling a task and
+/// reloading the registries drops its reminder.
+fn collect_task_reminders(doc_id: &str, contents: &str, registries: &mut DirectiveRegistries) {
+    for task in collect_tasks(contents) {
+        let Some(due) = task.metadata.due else {
+            continue;
+        };
+        if task.checked {
+            continue;
+        }
+        let mut to: Vec<String> = Vec::new();
+        for (user, _) in collect_mentions(&task.text) {
+            if !to.contains(&user) {
+                to.push(user);
+            }
+        }
+        if to.is_empty() {
+            continue;
+        }
+        registries.task_reminders.push(TaskReminder {
+            to,
+            due,
+            message: format!("Due {due}: {}", task.label()),
+            doc_id: doc_id.to_string(),
+            task_index: task.index,
+            line: task.line + 1,
+        });
+    }
+}
+
+/// The trimmed `webhook` or `ntfy` URL of a `/user` block. Only `http` and
+/// `https` URLs are kept, so a document cannot make the server fetch
+/// `file:` or other local resources.
+fn notification_url(
+    value: Option<String>,
+    key: &str,
+    warnings: &mut Vec<DirectiveWarning>,
+    doc_id: &str,
+    block_line: usize,
+) -> Option<String> {
+    let url = value.map(|url| url.trim().to_string())?;
+    if url.is_empty() {
+        return None;
+    }
+    if !is_http_url(&url) {
+        push_warning(
+            warnings,
+            doc_id,
+            block_line,
+            format!("invalid /user block: {key} must be an http or https URL"),
+        );
+        return None;
+    }
+    Some(url)
+}
+
+fn is_http_url(url: &str) -> bool {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return false;
+    };
+    if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+        return false;
+    }
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    !host.is_empty() && !host.starts_with(':') && !url.contains(char::is_whitespace)
+}
+
 fn push_warning(
     warnings: &mut Vec<DirectiveWarning>,
     doc_id: &str,
@@ -526,6 +887,7 @@ message = "Check the daily log."
         assert_eq!(subscriptions[0].endpoint, "https://push.example/123");
         assert_eq!(subscriptions[0].p256dh, "p256");
         assert_eq!(subscriptions[0].auth, "auth");
+        assert_eq!(subscriptions[0].doc_id, "note.md");
 
         assert_eq!(registries.notifications.len(), 1);
         let notification = &registries.notifications[0];
@@ -539,6 +901,343 @@ message = "Check the daily log."
         std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
+    #[test]
+    fn remove_subscription_block__should_replace_only_the_matching_block() {
+        // Given
+        let block = |endpoint: &str| {
+            format!(
+                "/subscription\n```toml\nuser = \"marten\"\nendpoint = \"{endpoint}\"\np256dh = \"p\"\nauth = \"a\"\n```\n"
+            )
+        };
+        let contents = format!(
+            "# Devices\n\n{}\nPhone:\n{}",
+            block("https://push.example/old"),
+            block("https://push.example/new")
+        );
+
+        // When
+        let updated =
+            remove_subscription_block(&contents, "https://push.example/old", "<!-- removed -->");
+        let missing = remove_subscription_block(&contents, "https://push.example/other", "x");
+
+        // Then
+        assert_eq!(
+            updated.as_deref(),
+            Some(
+                format!(
+                    "# Devices\n\n<!-- removed -->\n\nPhone:\n{}",
+                    block("https://push.example/new")
+                )
+                .as_str()
+            )
+        );
+        assert!(missing.is_none());
+    }
+
+    #[test]
+    fn load_directive_registries__should_collect_open_due_tasks_with_mentions() {
+        // Given
+        let root = create_temp_root("task-reminders");
+        let contents = "\
+- [ ] pay rent @marten @Ana @marten due:2026-11-01
+- [x] file taxes @marten due:2026-10-01
+- [ ] water plants @marten
+- [ ] call the vet 📅 2026-11-02
+```
+- [ ] fenced @marten due:2026-11-03
+```
+";
+        std::fs::write(root.join("chores.md"), contents).expect("write chores.md");
+
+        // When
+        let registries = DirectiveRegistries::load(&root).expect("load registries");
+
+        // Then
+        assert_eq!(registries.task_reminders.len(), 1);
+        let reminder = &registries.task_reminders[0];
+        assert_eq!(reminder.to, vec!["marten".to_string(), "Ana".to_string()]);
+        assert_eq!(reminder.due.to_string(), "2026-11-01");
+        assert_eq!(
+            reminder.message,
+            "Due 2026-11-01: pay rent @marten @Ana @marten"
+        );
+        assert_eq!(reminder.doc_id, "chores.md");
+        assert_eq!(reminder.task_index, 0);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn parse_document__should_parse_repeating_notifications() {
+        // Given
+        let contents = r#"/notify
+```toml
+to = "marten"
+at = "2026-10-19T09:00:00Z"
+message = "Stand-up"
+every = "weekdays"
+until = "2026-12-18T23:59:59Z"
+```
+
+/notify
+```toml
+to = "marten"
+at = "2026-10-19T09:00:00Z"
+message = "Take out the bins"
+cron = "0 7 * * MON"
+```
+
+/notify
+```toml
+to = "marten"
+at = "2026-10-19T09:00:00Z"
+message = "Both"
+every = "daily"
+rrule = "FREQ=DAILY"
+```
+
+/notify
+```toml
+to = "marten"
+at = "2026-10-19T09:00:00Z"
+message = "Bad"
+cron = "61 * * * *"
+```
+"#;
+        let mut registries = DirectiveRegistries::default();
+
+        // When
+        let warnings = parse_document("note.md", contents, &mut registries);
+
+        // Then
+        assert_eq!(registries.notifications.len(), 2);
+        assert_eq!(
+            registries.notifications[0].repeat,
+            Some(Repeat::Every("weekdays".to_string()))
+        );
+        assert_eq!(
+            registries.notifications[0].until,
+            Some(OffsetDateTime::parse("2026-12-18T23:59:59Z", &Rfc3339).expect("until"))
+        );
+        assert_eq!(
+            registries.notifications[1].repeat,
+            Some(Repeat::Cron("0 7 * * MON".to_string()))
+        );
+        let messages: Vec<&str> = warnings
+            .iter()
+            .map(|warning| warning.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "invalid /notify block: use only one of every, cron and rrule",
+                "invalid /notify block: cron (invalid minute '61')",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_document__should_parse_user_channels() {
+        // Given
+        let contents = r#"/user
+```toml
+name = "marten"
+email = "marten@example.com"
+password_hash = "hash"
+channels = ["Email", "ntfy", "webhook", "pager", "email"]
+ntfy = "https://ntfy.example/mindex-marten"
+```
+"#;
+        let mut registries = DirectiveRegistries::default();
+
+        // When
+        let warnings = parse_document("users.md", contents, &mut registries);
+
+        // Then
+        let user = registries.users.get("marten").expect("user entry");
+        assert_eq!(user.channels, vec![Channel::Email, Channel::Ntfy]);
+        assert_eq!(
+            user.ntfy.as_deref(),
+            Some("https://ntfy.example/mindex-marten")
+        );
+        assert_eq!(user.webhook, None);
+        let messages: Vec<&str> = warnings
+            .iter()
+            .map(|warning| warning.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "invalid /user block: the webhook channel needs a webhook URL",
+                "invalid /user block: unknown channel 'pager'",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_document__should_reject_notification_urls_that_are_not_http() {
+        // Given
+        let contents = r#"/user
+```toml
+name = "marten"
+email = "marten@example.com"
+password_hash = "hash"
+channels = ["webhook", "ntfy", "email"]
+webhook = "file:///etc/passwd"
+ntfy = "HTTPS://ntfy.example/mindex-marten"
+```
+"#;
+        let mut registries = DirectiveRegistries::default();
+
+        // When
+        let warnings = parse_document("users.md", contents, &mut registries);
+
+        // Then
+        let user = registries.users.get("marten").expect("user entry");
+        assert_eq!(user.webhook, None);
+        assert_eq!(
+            user.ntfy.as_deref(),
+            Some("HTTPS://ntfy.example/mindex-marten")
+        );
+        assert_eq!(user.channels, vec![Channel::Ntfy, Channel::Email]);
+        let messages: Vec<&str> = warnings
+            .iter()
+            .map(|warning| warning.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "invalid /user block: webhook must be an http or https URL",
+                "invalid /user block: the webhook channel needs a webhook URL",
+            ]
+        );
+    }
+
+    #[test]
+    fn is_http_url__should_require_an_http_scheme_and_a_host() {
+        assert!(is_http_url("http://localhost:8080/hook"));
+        assert!(is_http_url("https://user@hooks.example/a?b#c"));
+        assert!(!is_http_url("ftp://hooks.example/"));
+        assert!(!is_http_url("https:///path"));
+        assert!(!is_http_url("https://:443/"));
+        assert!(!is_http_url("https://hooks.example/a b"));
+        assert!(!is_http_url("hooks.example"));
+    }
+
+    #[test]
+    fn load_directive_registries__should_resolve_local_times_in_time_zones() {
+        // Given
+        let root = create_temp_root("notify-time-zones");
+        let notify = r#"/notify
+```toml
+to = ["marten", "ana", "bo"]
+at = "2026-10-20T09:00"
+until = "2026-10-30 18:00"
+message = "Stand-up"
+every = "daily"
+```
+
+/notify
+```toml
+to = "bo"
+at = "2026-10-20T09:00:00"
+tz = "America/New_York"
+message = "Fixed zone"
+```
+
+/notify
+```toml
+to = "bo"
+at = "tomorrow 09:00"
+message = "Bad time"
+```
+
+/notify
+```toml
+to = "bo"
+at = "2026-10-20T09:00:00"
+tz = "Mars/Olympus"
+message = "Bad zone"
+```
+"#;
+        let users = r#"/user
+```toml
+name = "marten"
+email = "marten@example.com"
+password_hash = "hash"
+tz = "Europe/Berlin"
+```
+
+/user
+```toml
+name = "ana"
+email = "ana@example.com"
+password_hash = "hash"
+tz = "Europe/Berlin"
+```
+
+/user
+```toml
+name = "bo"
+email = "bo@example.com"
+password_hash = "hash"
+tz = "Nowhere/Special"
+```
+"#;
+        std::fs::write(root.join("a-notify.md"), notify).expect("write notify");
+        std::fs::write(root.join("b-users.md"), users).expect("write users");
+
+        // When
+        let registries = DirectiveRegistries::load(&root).expect("load registries");
+
+        // Then
+        assert_eq!(
+            registries.users["marten"].tz.as_deref(),
+            Some("Europe/Berlin")
+        );
+        assert_eq!(registries.users["bo"].tz, None);
+        type Entry<'a> = (Vec<String>, Option<&'a str>, String, Option<String>);
+        let summary: Vec<Entry> = registries
+            .notifications
+            .iter()
+            .map(|notification| {
+                (
+                    notification.to.clone(),
+                    notification.tz.as_deref(),
+                    notification.at.format(&Rfc3339).expect("format"),
+                    notification
+                        .until
+                        .map(|until| until.format(&Rfc3339).expect("format")),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    vec!["marten".to_string(), "ana".to_string()],
+                    Some("Europe/Berlin"),
+                    "2026-10-20T07:00:00Z".to_string(),
+                    Some("2026-10-30T17:00:00Z".to_string()),
+                ),
+                (
+                    vec!["bo".to_string()],
+                    None,
+                    "2026-10-20T09:00:00Z".to_string(),
+                    Some("2026-10-30T18:00:00Z".to_string()),
+                ),
+                (
+                    vec!["bo".to_string()],
+                    Some("America/New_York"),
+                    "2026-10-20T13:00:00Z".to_string(),
+                    None,
+                ),
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
     #[test]
     fn parse_document__should_collect_warnings() {
         // Given
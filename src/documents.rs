@@ -3,20 +3,31 @@ use std::io::ErrorKind;
 use std::path::Path;
 
 mod editing;
+mod front_matter;
+mod graph;
+mod link_check;
 mod magent;
 mod paths;
+mod relink;
 mod rendering;
 mod search;
+mod tags;
+mod task_board;
 mod tasks;
+mod wikilinks;
+mod word_diff;
 
 #[allow(unused_imports)]
 pub(crate) use editing::{
     BlockKind, BlockRange, ReorderError, line_count, lines_for_display, reorder_range,
     scan_block_ranges,
 };
+pub(crate) use graph::{Backlink, backlinks, link_graph};
+pub(crate) use link_check::{BrokenLink, check_links};
 pub(crate) use magent::{
-    MagentRegion, accept_magent_edit, find_magent_regions, insert_directive,
-    remove_magent_interaction, render_magent_blocks,
+    ApplyEditError, MagentRegion, accept_magent_edit, apply_magent_edit, find_magent_regions,
+    insert_directive, insert_magent_response, reject_magent_edit, remove_magent_interaction,
+    render_magent_blocks, request_magent_edit_changes, unanswered_directives,
 };
 #[allow(unused_imports)]
 pub(crate) use paths::{
@@ -26,13 +37,21 @@ pub(crate) use paths::{
 };
 #[allow(unused_imports)]
 pub(crate) use rendering::{
-    RenderedDocument, render_document_html, render_markdown_snippet, render_task_list_markdown,
-    rewrite_relative_image_links, rewrite_relative_md_links,
+    HeadingAnchor, RenderedDocument, heading_anchors, render_document_html,
+    render_markdown_snippet, render_task_list_markdown, rewrite_relative_image_links,
+    rewrite_relative_md_links, task_badges_html,
 };
-pub(crate) use search::{SearchResult, search_documents};
-pub(crate) use tasks::{add_task_item_in_list, collect_mentions, toggle_task_item};
+pub(crate) use search::{
+    DocumentRefresh, STATE_DIR, SearchIndex, SearchResult, search_documents, state_file,
+};
+pub(crate) use tags::{TagCount, TaggedDocument, normalize_tag, tag_counts, tagged_documents};
+pub(crate) use task_board::{
+    BoardTask, TaskFilter, TaskFilterOptions, TaskStatus, collect_board_tasks, task_filter_options,
+};
+pub(crate) use tasks::{add_task_item_in_list, collect_mentions, collect_tasks, toggle_task_item};
 
 use paths::{dir_to_path, doc_id_to_path, supported_file_id_to_path};
+use relink::{RelinkFailure, Relinked};
 
 #[derive(Debug)]
 pub(crate) enum DocError {
@@ -80,9 +99,31 @@ pub(crate) fn create_document(root: &Path, doc_id: &str, contents: &str) -> Resu
     atomic_write(&target, contents).map_err(DocError::Io)
 }
 
-pub(crate) fn move_file(root: &Path, source_path: &str, target_dir: &str) -> Result<(), DocError> {
+/// A moved file and the documents whose links were, or could not be,
+/// rewritten to follow it.
+#[derive(Debug)]
+pub(crate) struct MovedFile {
+    pub(crate) doc_id: String,
+    pub(crate) relinked: Relinked,
+}
+
+/// Moves a file into `target_dir`, optionally renaming it to `new_name`, and
+/// rewrites links to it (and relative links inside it) in the same operation.
+pub(crate) fn move_file(
+    root: &Path,
+    source_path: &str,
+    target_dir: &str,
+    new_name: Option<&str>,
+) -> Result<MovedFile, DocError> {
     let source_rel = supported_file_id_to_path(source_path).ok_or(DocError::BadPath)?;
     let target_dir_rel = dir_to_path(target_dir).ok_or(DocError::BadPath)?;
+    let new_name = match new_name.map(str::trim).filter(|name| !name.is_empty()) {
+        Some(name) if !name.contains('/') => {
+            Some(supported_file_id_to_path(name).ok_or(DocError::BadPath)?)
+        }
+        Some(_) => return Err(DocError::BadPath),
+        None => None,
+    };
 
     // Resolve source: must exist, be a file, and be within root.
     let source_abs = root.join(&source_rel);
@@ -116,13 +157,34 @@ pub(crate) fn move_file(root: &Path, source_path: &str, target_dir: &str) -> Res
     }
 
     // Build destination and check for conflicts.
-    let file_name = source_rel.file_name().ok_or(DocError::BadPath)?;
+    let file_name = match &new_name {
+        Some(name) => name.as_os_str(),
+        None => source_rel.file_name().ok_or(DocError::BadPath)?,
+    };
     let dest = target_resolved.join(file_name);
     if dest.exists() {
         return Err(DocError::Conflict);
     }
-
-    std::fs::rename(&source_resolved, &dest).map_err(DocError::Io)
+    let source_id =
+        paths::doc_id_from_path(root, &root.join(&source_rel)).ok_or(DocError::BadPath)?;
+    let dest_id = paths::doc_id_from_path(root, &root.join(&target_dir_rel).join(file_name))
+        .ok_or(DocError::BadPath)?;
+
+    std::fs::rename(&source_resolved, &dest).map_err(DocError::Io)?;
+    let relinked = relink::relink_moved_file(root, &source_id, &dest_id).unwrap_or_else(|err| {
+        eprintln!("failed to rewrite links to {source_id}: {err}");
+        Relinked {
+            documents: Vec::new(),
+            failed: vec![RelinkFailure {
+                doc_id: dest_id.clone(),
+                error: err.to_string(),
+            }],
+        }
+    });
+    Ok(MovedFile {
+        doc_id: dest_id,
+        relinked,
+    })
 }
 
 pub(crate) fn delete_file(root: &Path, file_path: &str) -> Result<(), DocError> {
@@ -276,9 +338,10 @@ mod tests {
         std::fs::write(root.join("a/b.md"), "# B").expect("write");
 
         // When
-        move_file(&root, "a/b.md", "x").expect("move file");
+        let moved = move_file(&root, "a/b.md", "x", None).expect("move file");
 
         // Then
+        assert_eq!(moved.doc_id, "x/b.md");
         assert!(!root.join("a/b.md").exists());
         assert_eq!(
             std::fs::read_to_string(root.join("x/b.md")).expect("read"),
@@ -294,7 +357,7 @@ mod tests {
         std::fs::write(root.join("sub/doc.md"), "# Doc").expect("write");
 
         // When
-        move_file(&root, "sub/doc.md", "").expect("move file");
+        move_file(&root, "sub/doc.md", "", None).expect("move file");
 
         // Then
         assert!(!root.join("sub/doc.md").exists());
@@ -313,7 +376,7 @@ mod tests {
         std::fs::write(root.join("a/photo.png"), "png-data").expect("write");
 
         // When
-        move_file(&root, "a/photo.png", "b").expect("move file");
+        move_file(&root, "a/photo.png", "b", None).expect("move file");
 
         // Then
         assert!(!root.join("a/photo.png").exists());
@@ -323,6 +386,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn move_file__should_rename_and_rewrite_inbound_links() {
+        // Given
+        let root = create_temp_root("move-rename-relink");
+        std::fs::create_dir_all(root.join("img")).expect("mkdir img");
+        std::fs::create_dir_all(root.join("archive")).expect("mkdir archive");
+        std::fs::write(root.join("img/photo.png"), "png-data").expect("write");
+        std::fs::write(
+            root.join("index.md"),
+            "![Photo](img/photo.png)\n[raw](/file/img/photo.png)\n",
+        )
+        .expect("write");
+
+        // When
+        let moved =
+            move_file(&root, "img/photo.png", "archive", Some("garden.png")).expect("move file");
+
+        // Then
+        assert_eq!(moved.doc_id, "archive/garden.png");
+        assert!(root.join("archive/garden.png").exists());
+        assert_eq!(moved.relinked.documents.len(), 1);
+        assert_eq!(moved.relinked.documents[0].doc_id, "index.md");
+        assert_eq!(
+            std::fs::read_to_string(root.join("index.md")).expect("read"),
+            "![Photo](archive/garden.png)\n[raw](/file/archive/garden.png)\n"
+        );
+    }
+
+    #[test]
+    fn move_file__should_reject_new_names_with_folders_or_unsupported_extensions() {
+        // Given
+        let root = create_temp_root("move-rename-bad");
+        std::fs::write(root.join("doc.md"), "# Doc").expect("write");
+
+        // When
+        let nested = move_file(&root, "doc.md", "", Some("a/doc.md")).expect_err("should fail");
+        let bad_ext = move_file(&root, "doc.md", "", Some("doc.sh")).expect_err("should fail");
+
+        // Then
+        assert!(matches!(nested, DocError::BadPath));
+        assert!(matches!(bad_ext, DocError::BadPath));
+        assert!(root.join("doc.md").exists());
+    }
+
     #[test]
     fn move_file__should_return_not_found_for_missing_source() {
         // Given
@@ -330,7 +437,7 @@ mod tests {
         std::fs::create_dir_all(root.join("target")).expect("mkdir");
 
         // When
-        let err = move_file(&root, "missing.md", "target").expect_err("should fail");
+        let err = move_file(&root, "missing.md", "target", None).expect_err("should fail");
 
         // Then
         assert!(matches!(err, DocError::NotFound));
@@ -346,7 +453,7 @@ mod tests {
         std::fs::write(root.join("b/doc.md"), "existing").expect("write");
 
         // When
-        let err = move_file(&root, "a/doc.md", "b").expect_err("should fail");
+        let err = move_file(&root, "a/doc.md", "b", None).expect_err("should fail");
 
         // Then
         assert!(matches!(err, DocError::Conflict));
@@ -364,7 +471,7 @@ mod tests {
         std::fs::create_dir_all(root.join("target")).expect("mkdir");
 
         // When
-        let err = move_file(&root, "../escape.md", "target").expect_err("should fail");
+        let err = move_file(&root, "../escape.md", "target", None).expect_err("should fail");
 
         // Then
         assert!(matches!(err, DocError::BadPath));
@@ -377,7 +484,7 @@ mod tests {
         std::fs::write(root.join("doc.md"), "# Doc").expect("write");
 
         // When
-        let err = move_file(&root, "doc.md", "../").expect_err("should fail");
+        let err = move_file(&root, "doc.md", "../", None).expect_err("should fail");
 
         // Then
         assert!(matches!(err, DocError::BadPath));
@@ -391,7 +498,7 @@ mod tests {
         std::fs::write(root.join("script.sh"), "#!/bin/sh").expect("write");
 
         // When
-        let err = move_file(&root, "script.sh", "target").expect_err("should fail");
+        let err = move_file(&root, "script.sh", "target", None).expect_err("should fail");
 
         // Then
         assert!(matches!(err, DocError::BadPath));
@@ -404,7 +511,7 @@ mod tests {
         std::fs::write(root.join("doc.md"), "# Doc").expect("write");
 
         // When
-        let err = move_file(&root, "doc.md", "nonexistent").expect_err("should fail");
+        let err = move_file(&root, "doc.md", "nonexistent", None).expect_err("should fail");
 
         // Then
         assert!(matches!(err, DocError::NotFound));
@@ -423,7 +530,7 @@ mod tests {
         std::fs::create_dir_all(root.join("target")).expect("mkdir");
 
         // When
-        let err = move_file(&root, "link/secret.md", "target").expect_err("should fail");
+        let err = move_file(&root, "link/secret.md", "target", None).expect_err("should fail");
 
         // Then
         assert!(matches!(err, DocError::BadPath));
@@ -441,7 +548,7 @@ mod tests {
         std::fs::write(root.join("doc.md"), "# Doc").expect("write");
 
         // When
-        let err = move_file(&root, "doc.md", "link").expect_err("should fail");
+        let err = move_file(&root, "doc.md", "link", None).expect_err("should fail");
 
         // Then
         assert!(matches!(err, DocError::BadPath));
@@ -0,0 +1,341 @@
+use crate::html;
+use std::borrow::Cow;
+
+/// Keys whose values are treated as dates.
+const DATE_KEYS: [&str; 4] = ["date", "created", "updated", "modified"];
+
+/// Metadata from a `---` YAML or `+++` TOML block at the start of a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct FrontMatter {
+    /// Every property in source order (sorted by key for TOML).
+    pub(crate) properties: Vec<Property>,
+    pub(crate) title: Option<String>,
+    /// Lowercased tags without a leading `#`.
+    pub(crate) tags: Vec<String>,
+    pub(crate) aliases: Vec<String>,
+    /// `(key, value)` pairs for `date`, `created`, `updated` and `modified`.
+    pub(crate) dates: Vec<(String, String)>,
+    /// Number of source lines taken by the block, including its delimiters.
+    pub(crate) line_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Property {
+    pub(crate) key: String,
+    pub(crate) value: PropertyValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PropertyValue {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl PropertyValue {
+    fn items(&self) -> Vec<&str> {
+        match self {
+            PropertyValue::Text(text) => vec![text.as_str()],
+            PropertyValue::List(items) => items.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// Parses the front matter at the very start of `markdown`.
+///
+/// Returns `None` when there is no closed block or it does not parse to a
+/// table of properties, in which case the document renders as before.
+pub(crate) fn parse_front_matter(markdown: &str) -> Option<FrontMatter> {
+    let (delimiter, body, line_count) = front_matter_block(markdown)?;
+    let properties = if delimiter == "+++" {
+        parse_toml(body)?
+    } else {
+        parse_yaml(body)?
+    };
+
+    let mut front_matter = FrontMatter {
+        line_count,
+        ..FrontMatter::default()
+    };
+    for property in &properties {
+        let key = property.key.to_ascii_lowercase();
+        match key.as_str() {
+            "title" => {
+                front_matter.title = property
+                    .value
+                    .items()
+                    .first()
+                    .map(|title| title.trim().to_string())
+                    .filter(|title| !title.is_empty());
+            }
+            _ if is_tags_key(&key) => front_matter.tags.extend(split_tags(&property.value)),
+            "aliases" | "alias" => front_matter.aliases.extend(
+                property
+                    .value
+                    .items()
+                    .into_iter()
+                    .map(str::trim)
+                    .filter(|alias| !alias.is_empty())
+                    .map(str::to_string),
+            ),
+            _ if DATE_KEYS.contains(&key.as_str()) => {
+                if let PropertyValue::Text(value) = &property.value {
+                    front_matter.dates.push((key, value.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    front_matter.tags.retain(|tag| seen.insert(tag.clone()));
+    front_matter.properties = properties;
+    Some(front_matter)
+}
+
+/// Replaces the front matter lines with empty lines so the markdown parser
+/// skips them while line numbers stay the same.
+pub(crate) fn blank_front_matter(markdown: &str) -> Cow<'_, str> {
+    let Some(front_matter) = parse_front_matter(markdown) else {
+        return Cow::Borrowed(markdown);
+    };
+    let mut output = String::with_capacity(markdown.len());
+    for (idx, segment) in markdown.split_inclusive('\n').enumerate() {
+        if idx < front_matter.line_count {
+            let (_, ending) = super::split_line_ending(segment);
+            output.push_str(ending);
+        } else {
+            output.push_str(segment);
+        }
+    }
+    Cow::Owned(output)
+}
+
+/// Renders the properties as a compact table, collapsed by default.
+pub(crate) fn render_front_matter_html(front_matter: &FrontMatter) -> String {
+    if front_matter.properties.is_empty() {
+        return String::new();
+    }
+    let mut output =
+        String::from("<details class=\"front-matter\"><summary>Properties</summary><table><tbody>");
+    for property in &front_matter.properties {
+        let value = match &property.value {
+            value if is_tags_key(&property.key) => split_tags(value)
+                .iter()
+                .map(|tag| {
+                    let tag = html::escape(tag);
+                    format!("<a class=\"tag front-matter-item\" href=\"/tags/{tag}\">#{tag}</a>")
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+            PropertyValue::Text(text) => html::escape(text),
+            PropertyValue::List(items) => items
+                .iter()
+                .map(|item| {
+                    format!(
+                        "<span class=\"front-matter-item\">{}</span>",
+                        html::escape(item)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        output.push_str(&format!(
+            "<tr><th scope=\"row\">{}</th><td>{value}</td></tr>",
+            html::escape(&property.key)
+        ));
+    }
+    output.push_str("</tbody></table></details>\n");
+    output
+}
+
+/// Finds the delimiter, the raw block body and the number of lines the block spans.
+fn front_matter_block(markdown: &str) -> Option<(&'static str, &str, usize)> {
+    let markdown = markdown.strip_prefix('\u{feff}').unwrap_or(markdown);
+    let mut lines = markdown.split_inclusive('\n');
+    let first = lines.next()?.trim_end();
+    let delimiter = match first {
+        "---" => "---",
+        "+++" => "+++",
+        _ => return None,
+    };
+    let body_start = markdown.find('\n')? + 1;
+    let mut offset = body_start;
+    for (idx, line) in lines.enumerate() {
+        let trimmed = line.trim_end();
+        if trimmed == delimiter || (delimiter == "---" && trimmed == "...") {
+            return Some((delimiter, &markdown[body_start..offset], idx + 2));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn parse_yaml(body: &str) -> Option<Vec<Property>> {
+    if body.trim().is_empty() {
+        return Some(Vec::new());
+    }
+    let value: serde_yaml_ng::Value = serde_yaml_ng::from_str(body).ok()?;
+    let serde_yaml_ng::Value::Mapping(mapping) = value else {
+        return None;
+    };
+    Some(
+        mapping
+            .iter()
+            .map(|(key, value)| Property {
+                key: yaml_text(key),
+                value: match value {
+                    serde_yaml_ng::Value::Sequence(items) => {
+                        PropertyValue::List(items.iter().map(yaml_text).collect())
+                    }
+                    other => PropertyValue::Text(yaml_text(other)),
+                },
+            })
+            .collect(),
+    )
+}
+
+fn yaml_text(value: &serde_yaml_ng::Value) -> String {
+    match value {
+        serde_yaml_ng::Value::Null => String::new(),
+        serde_yaml_ng::Value::Bool(value) => value.to_string(),
+        serde_yaml_ng::Value::Number(value) => value.to_string(),
+        serde_yaml_ng::Value::String(value) => value.clone(),
+        serde_yaml_ng::Value::Sequence(items) => {
+            items.iter().map(yaml_text).collect::<Vec<_>>().join(", ")
+        }
+        serde_yaml_ng::Value::Mapping(mapping) => mapping
+            .iter()
+            .map(|(key, value)| format!("{}: {}", yaml_text(key), yaml_text(value)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        serde_yaml_ng::Value::Tagged(tagged) => yaml_text(&tagged.value),
+    }
+}
+
+fn parse_toml(body: &str) -> Option<Vec<Property>> {
+    let table: toml::Table = toml::from_str(body).ok()?;
+    Some(
+        table
+            .iter()
+            .map(|(key, value)| Property {
+                key: key.clone(),
+                value: match value {
+                    toml::Value::Array(items) => {
+                        PropertyValue::List(items.iter().map(toml_text).collect())
+                    }
+                    other => PropertyValue::Text(toml_text(other)),
+                },
+            })
+            .collect(),
+    )
+}
+
+fn toml_text(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(value) => value.clone(),
+        toml::Value::Integer(value) => value.to_string(),
+        toml::Value::Float(value) => value.to_string(),
+        toml::Value::Boolean(value) => value.to_string(),
+        toml::Value::Datetime(value) => value.to_string(),
+        toml::Value::Array(items) => items.iter().map(toml_text).collect::<Vec<_>>().join(", "),
+        toml::Value::Table(table) => table
+            .iter()
+            .map(|(key, value)| format!("{key} = {}", toml_text(value)))
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+fn is_tags_key(key: &str) -> bool {
+    key.eq_ignore_ascii_case("tags") || key.eq_ignore_ascii_case("tag")
+}
+
+/// Tags may be a list or a comma or space separated string, with or without `#`.
+fn split_tags(value: &PropertyValue) -> Vec<String> {
+    value
+        .items()
+        .into_iter()
+        .flat_map(|item| item.split(|ch: char| ch == ',' || ch.is_whitespace()))
+        .map(|tag| tag.trim_start_matches('#').to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_front_matter__should_read_yaml_metadata() {
+        // Given
+        let markdown = "\
+---
+title: Garden Plan
+tags: [Garden, \"#projects/house\"]
+aliases:
+  - Plan
+  - Allotment
+date: 2026-10-01
+draft: true
+---
+# Heading
+";
+
+        // When
+        let front_matter = parse_front_matter(markdown).expect("front matter");
+
+        // Then
+        assert_eq!(front_matter.title.as_deref(), Some("Garden Plan"));
+        assert_eq!(front_matter.tags, vec!["garden", "projects/house"]);
+        assert_eq!(front_matter.aliases, vec!["Plan", "Allotment"]);
+        assert_eq!(
+            front_matter.dates,
+            vec![("date".to_string(), "2026-10-01".to_string())]
+        );
+        assert_eq!(front_matter.line_count, 9);
+        assert_eq!(
+            front_matter.properties.last(),
+            Some(&Property {
+                key: "draft".to_string(),
+                value: PropertyValue::Text("true".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_front_matter__should_read_toml_metadata() {
+        // Given
+        let markdown = "+++\ntitle = \"Notes\"\ntags = \"a, b\"\nupdated = 2026-10-02\n+++\nBody\n";
+
+        // When
+        let front_matter = parse_front_matter(markdown).expect("front matter");
+
+        // Then
+        assert_eq!(front_matter.title.as_deref(), Some("Notes"));
+        assert_eq!(front_matter.tags, vec!["a", "b"]);
+        assert_eq!(
+            front_matter.dates,
+            vec![("updated".to_string(), "2026-10-02".to_string())]
+        );
+        assert_eq!(front_matter.line_count, 5);
+    }
+
+    #[test]
+    fn parse_front_matter__should_ignore_unclosed_or_non_table_blocks() {
+        assert_eq!(parse_front_matter("---\ntitle: x\n"), None);
+        assert_eq!(parse_front_matter("---\njust text\n---\n"), None);
+        assert_eq!(parse_front_matter("Intro\n---\ntitle: x\n---\n"), None);
+    }
+
+    #[test]
+    fn blank_front_matter__should_keep_line_count() {
+        // Given
+        let markdown = "---\r\ntitle: x\r\n---\r\n# Heading\n";
+
+        // When
+        let blanked = blank_front_matter(markdown);
+
+        // Then
+        assert_eq!(blanked, "\r\n\r\n\r\n# Heading\n");
+    }
+}
@@ -0,0 +1,218 @@
+use super::paths::FileKind;
+use super::search::SearchIndex;
+use std::collections::BTreeSet;
+
+/// Documents and the links between them.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct LinkGraph {
+    pub(crate) nodes: Vec<GraphNode>,
+    pub(crate) edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct GraphNode {
+    pub(crate) id: String,
+    pub(crate) title: String,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct GraphEdge {
+    pub(crate) source: String,
+    pub(crate) target: String,
+}
+
+/// A link to a document from another one, with the line it appears on.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Backlink {
+    pub(crate) doc_id: String,
+    pub(crate) title: String,
+    /// Zero-based line index of the link in the linking document.
+    pub(crate) line: usize,
+    pub(crate) context: String,
+}
+
+/// Builds the link graph over all indexed markdown documents.
+///
+/// Edges point at existing documents only and are deduplicated; self-links are
+/// left out.
+pub(crate) fn link_graph(index: &SearchIndex) -> LinkGraph {
+    let mut nodes = Vec::new();
+    let mut edges = BTreeSet::new();
+    for (doc_id, document) in index
+        .documents()
+        .filter(|(_, document)| document.kind == FileKind::Document)
+    {
+        nodes.push(GraphNode {
+            id: doc_id.clone(),
+            title: document.title.clone(),
+        });
+        for link in &document.links {
+            if let Some(target) = index.resolve_link(doc_id, &link.target) {
+                edges.insert(GraphEdge {
+                    source: doc_id.clone(),
+                    target,
+                });
+            }
+        }
+    }
+    LinkGraph {
+        nodes,
+        edges: edges.into_iter().collect(),
+    }
+}
+
+/// Lists every link pointing at `doc_id` from other documents, in path and line order.
+pub(crate) fn backlinks(index: &SearchIndex, doc_id: &str) -> Vec<Backlink> {
+    index
+        .linked_from(doc_id)
+        .map(|(source_id, document, link)| Backlink {
+            doc_id: source_id.clone(),
+            title: document.title.clone(),
+            line: link.line,
+            context: link.context.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::test_support::build_index;
+
+    #[test]
+    fn link_graph__should_collect_markdown_and_wiki_link_edges() {
+        // Given
+        let (root, index) = build_index(
+            "graph-edges",
+            &[
+                (
+                    "a.md",
+                    "# Alpha\n[B](notes/b.md) and [[Gamma]] and [[Gamma#Top]]\n",
+                ),
+                ("notes/b.md", "[Back](../a.md) [Self](b.md) [[Missing]]\n"),
+                ("gamma.md", "No links, see https://example.com\n"),
+            ],
+        );
+
+        // When
+        let graph = link_graph(&index);
+
+        // Then
+        let edge = |source: &str, target: &str| GraphEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+        };
+        assert_eq!(
+            graph.edges,
+            vec![
+                edge("a.md", "gamma.md"),
+                edge("a.md", "notes/b.md"),
+                edge("notes/b.md", "a.md"),
+            ]
+        );
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.nodes[0].title, "Alpha");
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn backlinks__should_list_linking_lines_with_context() {
+        // Given
+        let (root, index) = build_index(
+            "graph-backlinks",
+            &[
+                ("target.md", "# Target\n"),
+                ("one.md", "# One\nintro\n  See [[Target]] for details.\n"),
+                (
+                    "two.md",
+                    "[t](target.md)\n<magent-response>\n[[Target]]\n</magent-response>\n",
+                ),
+            ],
+        );
+
+        // When
+        let backlinks = backlinks(&index, "target.md");
+
+        // Then
+        assert_eq!(
+            backlinks,
+            vec![
+                Backlink {
+                    doc_id: "one.md".to_string(),
+                    title: "One".to_string(),
+                    line: 2,
+                    context: "See [[Target]] for details.".to_string(),
+                },
+                Backlink {
+                    doc_id: "two.md".to_string(),
+                    title: "two".to_string(),
+                    line: 0,
+                    context: "[t](target.md)".to_string(),
+                },
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn backlinks__should_follow_refreshed_documents() {
+        // Given
+        let (root, mut index) = build_index(
+            "graph-backlinks-refresh",
+            &[
+                (
+                    "target.md",
+                    "# Target
+",
+                ),
+                (
+                    "one.md",
+                    "[[Target]] and [[Later]]
+",
+                ),
+            ],
+        );
+
+        // When
+        std::fs::write(
+            root.join("one.md"),
+            "nothing here
+",
+        )
+        .expect("write one");
+        index
+            .refresh_document(&root, "one.md")
+            .expect("refresh one");
+        std::fs::write(
+            root.join("two.md"),
+            "[[Later]]
+",
+        )
+        .expect("write two");
+        index
+            .refresh_document(&root, "two.md")
+            .expect("refresh two");
+        std::fs::write(
+            root.join("later.md"),
+            "# Later
+",
+        )
+        .expect("write later");
+        index
+            .refresh_document(&root, "later.md")
+            .expect("refresh later");
+
+        // Then
+        assert_eq!(backlinks(&index, "target.md"), Vec::new());
+        let sources: Vec<String> = backlinks(&index, "later.md")
+            .into_iter()
+            .map(|backlink| backlink.doc_id)
+            .collect();
+        assert_eq!(sources, vec!["two.md".to_string()]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+}
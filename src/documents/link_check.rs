@@ -0,0 +1,289 @@
+use super::paths::{collect_markdown_paths, doc_id_from_path};
+use super::rendering::{
+    StrippedSource, heading_anchors, heading_slug, is_absolute_or_scheme, resolve_relative_path,
+    split_link_fragment,
+};
+use crate::html::decode_percent;
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A link whose target does not exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BrokenLink {
+    pub(crate) doc_id: String,
+    /// Zero-based line index of the link in the source markdown.
+    pub(crate) line: usize,
+    /// The link destination as written.
+    pub(crate) target: String,
+    pub(crate) problem: LinkProblem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LinkProblem {
+    MissingFile,
+    MissingHeading,
+    OutsideRoot,
+}
+
+impl LinkProblem {
+    pub(crate) fn describe(self) -> &'static str {
+        match self {
+            LinkProblem::MissingFile => "missing file",
+            LinkProblem::MissingHeading => "missing heading",
+            LinkProblem::OutsideRoot => "points outside the root",
+        }
+    }
+}
+
+/// Checks the links and images of every markdown document under `root`.
+///
+/// Relative links, `/d/`, `/pdf/` and `/file/` references must point at an
+/// existing file under the root, and heading fragments on markdown targets
+/// must match a heading slug of the target. External URLs, other app routes
+/// and `[[wiki links]]` (which offer to create missing notes) are not checked.
+/// Links inside `<magent-response>` blocks are skipped, like in the rendered view.
+pub(crate) fn check_links(root: &Path) -> std::io::Result<Vec<BrokenLink>> {
+    let root = std::fs::canonicalize(root)?;
+    let mut checker = LinkChecker {
+        root: &root,
+        slugs: HashMap::new(),
+    };
+    let mut paths = collect_markdown_paths(&root)?;
+    paths.sort();
+
+    let mut broken = Vec::new();
+    for path in paths {
+        let Some(doc_id) = doc_id_from_path(&root, &path) else {
+            continue;
+        };
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                eprintln!("skipping {doc_id} while checking links: {err}");
+                continue;
+            }
+        };
+        broken.extend(checker.check_document(&doc_id, &contents));
+    }
+    Ok(broken)
+}
+
+struct LinkChecker<'a> {
+    root: &'a Path,
+    /// Heading slugs per markdown file, `None` if the file cannot be read.
+    slugs: HashMap<String, Option<Vec<String>>>,
+}
+
+impl LinkChecker<'_> {
+    fn check_document(&mut self, doc_id: &str, markdown: &str) -> Vec<BrokenLink> {
+        let stripped = StrippedSource::new(markdown);
+        let mut options = Options::empty();
+        options.insert(Options::ENABLE_TABLES);
+        options.insert(Options::ENABLE_MATH);
+        options.insert(Options::ENABLE_WIKILINKS);
+
+        let mut broken = Vec::new();
+        for (event, range) in Parser::new_ext(&stripped.text, options).into_offset_iter() {
+            let (dest_url, is_image) = match event {
+                Event::Start(Tag::Link {
+                    link_type,
+                    dest_url,
+                    ..
+                }) => {
+                    if matches!(
+                        link_type,
+                        LinkType::WikiLink { .. } | LinkType::Autolink | LinkType::Email
+                    ) {
+                        continue;
+                    }
+                    (dest_url, false)
+                }
+                Event::Start(Tag::Image { dest_url, .. }) => (dest_url, true),
+                _ => continue,
+            };
+            if let Some(problem) = self.check_target(doc_id, &dest_url, is_image) {
+                broken.push(BrokenLink {
+                    doc_id: doc_id.to_string(),
+                    line: stripped.source_line(range.start),
+                    target: dest_url.to_string(),
+                    problem,
+                });
+            }
+        }
+        broken
+    }
+
+    fn check_target(
+        &mut self,
+        doc_id: &str,
+        dest_url: &str,
+        is_image: bool,
+    ) -> Option<LinkProblem> {
+        let (path_part, fragment) = split_link_fragment(dest_url.trim());
+        let path_part = decode_percent(path_part);
+        let fragment = fragment
+            .filter(|fragment| !fragment.is_empty())
+            .map(decode_percent);
+
+        let target_id = if path_part.is_empty() {
+            doc_id.to_string()
+        } else if let Some(absolute) = path_part.strip_prefix('/') {
+            let (route, rest) = absolute.split_once('/')?;
+            if !matches!(route, "d" | "pdf" | "file") {
+                return None;
+            }
+            match resolve_relative_path("", rest) {
+                Some(target_id) => target_id,
+                None => return Some(LinkProblem::OutsideRoot),
+            }
+        } else if is_absolute_or_scheme(&path_part) {
+            return None;
+        } else {
+            match resolve_relative_path(doc_id, path_part.trim_end_matches('/')) {
+                Some(target_id) => target_id,
+                None => return Some(LinkProblem::OutsideRoot),
+            }
+        };
+
+        if !self.exists(&target_id) {
+            return Some(LinkProblem::MissingFile);
+        }
+        let fragment = fragment.filter(|_| !is_image && is_markdown(&target_id))?;
+        let slugs = self.heading_slugs(&target_id)?;
+        let found = slugs
+            .iter()
+            .any(|slug| *slug == fragment || *slug == heading_slug(&fragment));
+        (!found).then_some(LinkProblem::MissingHeading)
+    }
+
+    fn target_path(&self, target_id: &str) -> Option<PathBuf> {
+        let resolved = std::fs::canonicalize(self.root.join(target_id)).ok()?;
+        resolved.starts_with(self.root).then_some(resolved)
+    }
+
+    fn exists(&self, target_id: &str) -> bool {
+        self.target_path(target_id).is_some()
+    }
+
+    fn heading_slugs(&mut self, target_id: &str) -> Option<&Vec<String>> {
+        if !self.slugs.contains_key(target_id) {
+            let slugs = self
+                .target_path(target_id)
+                .and_then(|path| std::fs::read_to_string(path).ok())
+                .map(|contents| {
+                    heading_anchors(&contents)
+                        .into_iter()
+                        .map(|anchor| anchor.slug)
+                        .collect()
+                });
+            self.slugs.insert(target_id.to_string(), slugs);
+        }
+        self.slugs.get(target_id)?.as_ref()
+    }
+}
+
+fn is_markdown(target_id: &str) -> bool {
+    target_id
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| ext.eq_ignore_ascii_case("md"))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_temp_root, write_files};
+
+    fn problems(broken: &[BrokenLink]) -> Vec<(&str, usize, &str, LinkProblem)> {
+        broken
+            .iter()
+            .map(|link| {
+                (
+                    link.doc_id.as_str(),
+                    link.line,
+                    link.target.as_str(),
+                    link.problem,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check_links__should_report_missing_files_headings_and_escapes() {
+        // Given
+        let root = create_temp_root("link-check-broken");
+        write_files(
+            &root,
+            &[
+                (
+                    "index.md",
+                    "# Index\n\
+[ok](notes/plan.md#next-steps) [gone](notes/gone.md)\n\
+[bad heading](notes/plan.md#later)\n\
+![missing](img/missing.png) [up](../outside.md)\n\
+[self](#index) [no such section](#nope)\n",
+                ),
+                (
+                    "notes/plan.md",
+                    "# Plan\n## Next Steps\n[file](/file/notes/data.csv) [doc](/d/notes/none.md)\n",
+                ),
+                ("notes/data.csv", "a,b\n"),
+            ],
+        );
+
+        // When
+        let broken = check_links(&root).expect("check links");
+
+        // Then
+        assert_eq!(
+            problems(&broken),
+            vec![
+                ("index.md", 1, "notes/gone.md", LinkProblem::MissingFile),
+                (
+                    "index.md",
+                    2,
+                    "notes/plan.md#later",
+                    LinkProblem::MissingHeading
+                ),
+                ("index.md", 3, "img/missing.png", LinkProblem::MissingFile),
+                ("index.md", 3, "../outside.md", LinkProblem::OutsideRoot),
+                ("index.md", 4, "#nope", LinkProblem::MissingHeading),
+                (
+                    "notes/plan.md",
+                    2,
+                    "/d/notes/none.md",
+                    LinkProblem::MissingFile
+                ),
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn check_links__should_ignore_external_wiki_encoded_and_magent_links() {
+        // Given
+        let root = create_temp_root("link-check-ignored");
+        write_files(
+            &root,
+            &[
+                (
+                    "index.md",
+                    "[web](https://example.com/x.md) <https://example.com> [mail](mailto:a@b.c)\n\
+[[Missing Note]] [search](/search?q=x) [pdf](docs/My%20Paper.pdf#page=3)\n\
+<magent-response>\n[gone](gone.md)\n</magent-response>\n",
+                ),
+                ("docs/My Paper.pdf", "%PDF-1.4\n"),
+            ],
+        );
+
+        // When
+        let broken = check_links(&root).expect("check links");
+
+        // Then
+        assert!(broken.is_empty(), "{broken:?}");
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+}
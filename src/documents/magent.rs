@@ -5,6 +5,7 @@
 //! structured HTML before pulldown-cmark processes the document, avoiding the
 //! CommonMark HTML-block parsing issues that would otherwise garble the output.
 
+use super::word_diff::{DiffKind, word_diff};
 use crate::html;
 use pulldown_cmark::{Options, Parser};
 
@@ -115,15 +116,27 @@ pub(super) fn strip_magent_blocks(markdown: &str) -> String {
 /// blocks) and replaces them with structured HTML. Returns the processed
 /// markdown and whether any magent blocks were found.
 ///
+/// `markdown` may be a slice of `document`, such as a single response region.
+/// Edit previews check their search text against `document`, and edits are
+/// numbered from `first_edit_index`, the number of `<magent-edit>` blocks in
+/// `document` before `markdown`.
+///
 /// Not called from the normal document view (which uses `strip_magent_blocks`),
 /// but used by the agent view to render response blocks with full structure.
-pub(crate) fn render_magent_blocks(markdown: &str) -> (String, bool) {
+pub(crate) fn render_magent_blocks(
+    markdown: &str,
+    document: &str,
+    first_edit_index: usize,
+) -> (String, bool) {
     let mut output = String::with_capacity(markdown.len());
     let mut has_magent = false;
     let mut in_fence = false;
     let mut depth: usize = 0;
     let mut response_buf = String::new();
-    let mut edit_index: usize = 0;
+    let mut context = RenderContext {
+        document,
+        edit_index: first_edit_index,
+    };
 
     for segment in markdown.split_inclusive('\n') {
         let (line, _) = super::split_line_ending(segment);
@@ -143,7 +156,7 @@ pub(crate) fn render_magent_blocks(markdown: &str) -> (String, bool) {
         } else if is_response_close(line) {
             depth -= 1;
             if depth == 0 {
-                output.push_str(&render_response(&response_buf, &mut edit_index));
+                output.push_str(&render_response(&response_buf, &mut context));
                 output.push('\n');
                 has_magent = true;
                 response_buf.clear();
@@ -180,21 +193,27 @@ fn is_response_close(line: &str) -> bool {
 // Response rendering
 // ---------------------------------------------------------------------------
 
+/// The document being rendered and the index of the next `<magent-edit>`.
+struct RenderContext<'a> {
+    document: &'a str,
+    edit_index: usize,
+}
+
 /// Render the inner content of a `<magent-response>` block to structured HTML.
 ///
 /// The output is a single `<div class="magent-response">` with no internal
 /// blank lines, so pulldown-cmark treats it as one HTML block.
-fn render_response(inner: &str, edit_index: &mut usize) -> String {
+fn render_response(inner: &str, context: &mut RenderContext) -> String {
     let mut html = String::new();
     html.push_str("<div class=\"magent-response\">\n");
-    render_elements(inner, &mut html, edit_index);
+    render_elements(inner, &mut html, context);
     html.push_str("</div>");
     strip_blank_lines(&html)
 }
 
 /// Walk through response content, emitting HTML for each magent element and
 /// rendering interstitial text as markdown.
-fn render_elements(content: &str, html: &mut String, edit_index: &mut usize) {
+fn render_elements(content: &str, html: &mut String, context: &mut RenderContext) {
     let mut pos = 0;
 
     while pos < content.len() {
@@ -227,7 +246,7 @@ fn render_elements(content: &str, html: &mut String, edit_index: &mut usize) {
                     Some(inner_len) => {
                         let element_inner =
                             &content[abs_content_start..abs_content_start + inner_len];
-                        render_element(tag.name, tag.attrs, element_inner, html, edit_index);
+                        render_element(tag.name, tag.attrs, element_inner, html, context);
                         pos = abs_content_start + inner_len + close_pattern.len();
                     }
                 }
@@ -292,7 +311,13 @@ fn find_next_element<'a>(haystack: &'a str) -> Option<TagMatch<'a>> {
 // Element rendering
 // ---------------------------------------------------------------------------
 
-fn render_element(name: &str, attrs: &str, inner: &str, html: &mut String, edit_index: &mut usize) {
+fn render_element(
+    name: &str,
+    attrs: &str,
+    inner: &str,
+    html: &mut String,
+    context: &mut RenderContext,
+) {
     match name {
         "magent-thinking" => {
             html.push_str("<details class=\"magent-thinking\">\n");
@@ -327,20 +352,38 @@ fn render_element(name: &str, attrs: &str, inner: &str, html: &mut String, edit_
             html.push_str("<div class=\"magent-edit\" data-status=\"");
             html.push_str(&html::escape(status));
             html.push_str("\" data-edit-index=\"");
-            html.push_str(&edit_index.to_string());
+            html.push_str(&context.edit_index.to_string());
             html.push_str("\">\n");
-            if let Some(search) = extract_inner(inner, "magent-search") {
+            let search = extract_inner(inner, "magent-search");
+            let replace = extract_inner(inner, "magent-replace");
+            if let (Some(search), Some(replace)) = (search, replace) {
+                if matches!(status, "proposed" | "revise") {
+                    render_search_status(search.trim(), context.document, html);
+                }
+                render_edit_diff(search.trim(), replace.trim(), html);
+                html.push_str("<details class=\"magent-edit-raw\">\n");
+                html.push_str("<summary>Search and replace</summary>\n");
+            }
+            if let Some(search) = search {
                 html.push_str("<div class=\"magent-edit-search\"><pre><code>");
                 html.push_str(&html::escape(search.trim()));
                 html.push_str("</code></pre></div>\n");
             }
-            if let Some(replace) = extract_inner(inner, "magent-replace") {
+            if let Some(replace) = replace {
                 html.push_str("<div class=\"magent-edit-replace\"><pre><code>");
                 html.push_str(&html::escape(replace.trim()));
                 html.push_str("</code></pre></div>\n");
             }
+            if search.is_some() && replace.is_some() {
+                html.push_str("</details>\n");
+            }
+            if let Some(comment) = extract_inner(inner, "magent-comment") {
+                html.push_str("<div class=\"magent-edit-comment\">");
+                html.push_str(&render_markdown_fragment(comment.trim()));
+                html.push_str("</div>\n");
+            }
             html.push_str("</div>\n");
-            *edit_index += 1;
+            context.edit_index += 1;
         }
         _ => {
             let trimmed = inner.trim();
@@ -351,6 +394,50 @@ fn render_element(name: &str, attrs: &str, inner: &str, html: &mut String, edit_
     }
 }
 
+/// Whether the edit's search text still occurs exactly once in the document,
+/// outside magent responses, as accepting with `--apply-magent-edits` needs.
+fn render_search_status(search: &str, document: &str, html: &mut String) {
+    let (found, message) = match matches_outside_responses(document, search).len() {
+        0 => (
+            "missing",
+            "Search text not found in the document".to_string(),
+        ),
+        1 => ("once", "Search text found in the document".to_string()),
+        count => (
+            "multiple",
+            format!("Search text found {count} times in the document"),
+        ),
+    };
+    html.push_str("<div class=\"magent-edit-found\" data-found=\"");
+    html.push_str(found);
+    html.push_str("\">");
+    html.push_str(&message);
+    html.push_str("</div>\n");
+}
+
+/// Unified word-level diff of an edit, with removed words in `<del>` and
+/// added words in `<ins>`.
+fn render_edit_diff(search: &str, replace: &str, html: &mut String) {
+    html.push_str("<pre class=\"magent-edit-diff\"><code>");
+    for span in word_diff(search, replace) {
+        let text = html::escape(&span.text);
+        match span.kind {
+            DiffKind::Same => html.push_str(&text),
+            DiffKind::Removed => {
+                html.push_str("<del>");
+                html.push_str(&text);
+                html.push_str("</del>");
+            }
+            DiffKind::Added => {
+                html.push_str("<ins>");
+                html.push_str(&text);
+                html.push_str("</ins>");
+            }
+        }
+    }
+    html.push_str("</code></pre>\n");
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -402,7 +489,7 @@ fn strip_blank_lines(html: &str) -> String {
 }
 
 // ---------------------------------------------------------------------------
-// Accept edit
+// Review edit
 // ---------------------------------------------------------------------------
 
 /// Accept a proposed magent edit by index.
@@ -412,17 +499,152 @@ fn strip_blank_lines(html: &str) -> String {
 /// magent — mindex only signals approval. Returns the updated document or
 /// `None` if the edit was not found.
 pub(crate) fn accept_magent_edit(contents: &str, edit_index: usize) -> Option<String> {
+    review_proposed_edit(contents, edit_index, "accepted", None)
+}
+
+/// Reject a proposed magent edit by index, changing its status to
+/// `"rejected"`. Returns `None` if the edit was not found.
+pub(crate) fn reject_magent_edit(contents: &str, edit_index: usize) -> Option<String> {
+    review_proposed_edit(contents, edit_index, "rejected", None)
+}
+
+/// Ask magent to revise a proposed edit by index.
+///
+/// Changes the status to `"revise"` and appends the reviewer's comment as a
+/// `<magent-comment>` element inside the edit block, where magent reads it
+/// on its next run. `<`, `>` and `&` in the comment are escaped so it cannot
+/// close the surrounding elements. Returns `None` if the edit was not found
+/// or the comment is empty.
+pub(crate) fn request_magent_edit_changes(
+    contents: &str,
+    edit_index: usize,
+    comment: &str,
+) -> Option<String> {
+    let comment = comment.trim();
+    if comment.is_empty() {
+        return None;
+    }
+    review_proposed_edit(contents, edit_index, "revise", Some(comment))
+}
+
+fn review_proposed_edit(
+    contents: &str,
+    edit_index: usize,
+    status: &str,
+    comment: Option<&str>,
+) -> Option<String> {
     let (_search, _replace, edit_start, edit_tag_end) = find_proposed_edit(contents, edit_index)?;
 
-    let mut output = String::with_capacity(contents.len());
+    let mut edit_block = contents[edit_start..edit_tag_end].replacen(
+        "status=\"proposed\"",
+        &format!("status=\"{status}\""),
+        1,
+    );
+    if let Some(comment) = comment {
+        let close_at = edit_block.len() - "</magent-edit>".len();
+        let newline = if edit_block[..close_at].ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+        edit_block.insert_str(
+            close_at,
+            &format!(
+                "{newline}<magent-comment>{}</magent-comment>\n",
+                escape_comment(comment)
+            ),
+        );
+    }
+
+    let mut output = String::with_capacity(contents.len() + edit_block.len());
     output.push_str(&contents[..edit_start]);
-    let edit_block = &contents[edit_start..edit_tag_end];
-    output.push_str(&edit_block.replacen("status=\"proposed\"", "status=\"accepted\"", 1));
+    output.push_str(&edit_block);
     output.push_str(&contents[edit_tag_end..]);
 
     Some(output)
 }
 
+/// Why an accepted edit could not be applied to the document.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ApplyEditError {
+    /// No proposed edit at that index.
+    NotFound,
+    /// The search text occurs this many times outside magent responses
+    /// instead of exactly once.
+    Conflict(usize),
+}
+
+/// Accept a proposed magent edit by index and perform it.
+///
+/// Replaces the edit's search text with its replacement in the document
+/// text outside `<magent-response>` regions and changes the status to
+/// `"applied"`, so the edit is recorded and performed in one write. Fails
+/// with [`ApplyEditError::Conflict`] unless the search text occurs exactly
+/// once there.
+pub(crate) fn apply_magent_edit(
+    contents: &str,
+    edit_index: usize,
+) -> Result<String, ApplyEditError> {
+    let (search, replace, _, _) =
+        find_proposed_edit(contents, edit_index).ok_or(ApplyEditError::NotFound)?;
+    let reviewed = review_proposed_edit(contents, edit_index, "applied", None)
+        .ok_or(ApplyEditError::NotFound)?;
+
+    // The status change only touched the inside of a response region.
+    let matches = matches_outside_responses(&reviewed, &search);
+    let [start] = matches[..] else {
+        return Err(ApplyEditError::Conflict(matches.len()));
+    };
+
+    let mut output = String::with_capacity(reviewed.len() + replace.len());
+    output.push_str(&reviewed[..start]);
+    output.push_str(&replace);
+    output.push_str(&reviewed[start + search.len()..]);
+    Ok(output)
+}
+
+/// Byte offsets of the non-overlapping occurrences of `search` in `contents`
+/// that do not touch a `<magent-response>` region.
+fn matches_outside_responses(contents: &str, search: &str) -> Vec<usize> {
+    if search.is_empty() {
+        return Vec::new();
+    }
+
+    let mut line_starts = vec![0];
+    line_starts.extend(contents.split_inclusive('\n').scan(0, |offset, segment| {
+        *offset += segment.len();
+        Some(*offset)
+    }));
+    let regions: Vec<(usize, usize)> = find_magent_regions(contents)
+        .iter()
+        .map(|region| {
+            (
+                line_starts[region.start_line],
+                line_starts[region.end_line + 1],
+            )
+        })
+        .collect();
+
+    contents
+        .match_indices(search)
+        .map(|(start, _)| start)
+        .filter(|start| {
+            let end = start + search.len();
+            !regions
+                .iter()
+                .any(|(region_start, region_end)| *start < *region_end && end > *region_start)
+        })
+        .collect()
+}
+
+/// Escape only what could open or close a tag; quotes stay readable.
+fn escape_comment(comment: &str) -> String {
+    comment
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
 /// Locate the Nth edit block and return (search, replace, block_start, block_end).
 ///
 /// The index counts *all* `<magent-edit>` blocks (proposed and accepted) to
@@ -588,6 +810,108 @@ pub(crate) fn insert_directive(
     Some(output)
 }
 
+// ---------------------------------------------------------------------------
+// Unanswered directives (for the built-in agent runner)
+// ---------------------------------------------------------------------------
+
+/// An `@magent` directive line with no `<magent-response>` after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PendingDirective {
+    /// 0-based line index of the directive.
+    pub(crate) line: usize,
+    /// The directive text after `@magent `.
+    pub(crate) prompt: String,
+}
+
+/// Find `@magent` directives outside fenced code and response blocks whose
+/// next non-blank line does not open a `<magent-response>`.
+pub(crate) fn unanswered_directives(contents: &str) -> Vec<PendingDirective> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let regions = find_magent_regions(contents);
+    let mut pending = Vec::new();
+    let mut in_fence = false;
+
+    for (line_idx, line) in lines.iter().enumerate() {
+        if regions
+            .iter()
+            .any(|region| line_idx >= region.start_line && line_idx <= region.end_line)
+        {
+            continue;
+        }
+        if super::is_fence_line(line) {
+            in_fence = !in_fence;
+            continue;
+        }
+        let Some(prompt) = line.trim().strip_prefix("@magent ") else {
+            continue;
+        };
+        if in_fence || prompt.trim().is_empty() {
+            continue;
+        }
+        let answered = lines[line_idx + 1..]
+            .iter()
+            .find(|next| !next.trim().is_empty())
+            .is_some_and(|next| is_response_open(next));
+        if !answered {
+            pending.push(PendingDirective {
+                line: line_idx,
+                prompt: prompt.trim().to_string(),
+            });
+        }
+    }
+
+    pending
+}
+
+/// Insert `response` as a `<magent-response>` block below a pending directive.
+///
+/// The document may have changed while the response was generated, so the
+/// directive is looked up again by its prompt, preferring the one closest to
+/// its old line. Lines of `response` that would open or close a response
+/// block are escaped. Returns `None` if the directive is gone or answered.
+pub(crate) fn insert_magent_response(
+    contents: &str,
+    directive: &PendingDirective,
+    response: &str,
+) -> Option<String> {
+    let target = unanswered_directives(contents)
+        .into_iter()
+        .filter(|pending| pending.prompt == directive.prompt)
+        .min_by_key(|pending| pending.line.abs_diff(directive.line))?;
+
+    let mut block = String::from("\n<magent-response>\n");
+    for line in response.trim().lines() {
+        if is_response_open(line) || is_response_close(line) {
+            block.push_str(&line.replacen('<', "&lt;", 1));
+        } else {
+            block.push_str(line);
+        }
+        block.push('\n');
+    }
+    block.push_str("</magent-response>\n");
+
+    let segments: Vec<&str> = contents.split_inclusive('\n').collect();
+    let mut output = String::with_capacity(contents.len() + block.len() + 2);
+    for seg in &segments[..=target.line] {
+        output.push_str(seg);
+    }
+    if !output.ends_with('\n') {
+        output.push('\n');
+    }
+    output.push_str(&block);
+    if segments
+        .get(target.line + 1)
+        .is_some_and(|next| !next.trim().is_empty())
+    {
+        output.push('\n');
+    }
+    for seg in &segments[target.line + 1..] {
+        output.push_str(seg);
+    }
+
+    Some(output)
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -822,7 +1146,7 @@ More text after.
     #[test]
     fn render_magent_blocks__no_magent_content() {
         let md = "# Hello\n\nA paragraph.\n";
-        let (result, has_magent) = render_magent_blocks(md);
+        let (result, has_magent) = render_magent_blocks(md, md, 0);
 
         assert!(!has_magent);
         assert_eq!(result, md);
@@ -837,7 +1161,7 @@ More text after.
 Hello there!
 </magent-response>
 ";
-        let (result, has_magent) = render_magent_blocks(md);
+        let (result, has_magent) = render_magent_blocks(md, md, 0);
 
         assert!(has_magent);
         assert!(result.contains("class=\"magent-response\""));
@@ -855,7 +1179,7 @@ The user wants a greeting.
 Hi!
 </magent-response>
 ";
-        let (result, has_magent) = render_magent_blocks(md);
+        let (result, has_magent) = render_magent_blocks(md, md, 0);
 
         assert!(has_magent);
         assert!(result.contains("class=\"magent-thinking\""));
@@ -877,7 +1201,7 @@ Hi!
 Based on the results, the answer is 42.
 </magent-response>
 ";
-        let (result, has_magent) = render_magent_blocks(md);
+        let (result, has_magent) = render_magent_blocks(md, md, 0);
 
         assert!(has_magent);
         assert!(result.contains("class=\"magent-tool-call\""));
@@ -900,7 +1224,7 @@ Fixed the URL:
 </magent-edit>
 </magent-response>
 ";
-        let (result, has_magent) = render_magent_blocks(md);
+        let (result, has_magent) = render_magent_blocks(md, md, 0);
 
         assert!(has_magent);
         assert!(result.contains("class=\"magent-edit\""));
@@ -911,6 +1235,37 @@ Fixed the URL:
         assert!(result.contains("https://example.com"));
     }
 
+    #[test]
+    fn render_magent_blocks__should_preview_edits_as_a_word_diff() {
+        let region = "\
+<magent-response>
+<magent-edit status=\"proposed\">
+<magent-search>the quick brown fox</magent-search>
+<magent-replace>the quick red fox</magent-replace>
+</magent-edit>
+<magent-edit status=\"accepted\">
+<magent-search>lazy dog</magent-search>
+<magent-replace>sleepy dog</magent-replace>
+</magent-edit>
+</magent-response>
+";
+        let document = format!("the quick brown fox\n\n{region}");
+        let stale = format!("the quick red fox\n\n{region}");
+
+        let (result, _) = render_magent_blocks(region, &document, 3);
+        let (stale_result, _) = render_magent_blocks(region, &stale, 3);
+
+        assert!(result.contains(
+            "<pre class=\"magent-edit-diff\"><code>the quick <del>brown</del><ins>red</ins> fox</code></pre>"
+        ));
+        assert!(result.contains("data-edit-index=\"3\""));
+        assert!(result.contains("data-edit-index=\"4\""));
+        // Only pending edits say whether their search text is still there.
+        assert_eq!(result.matches("class=\"magent-edit-found\"").count(), 1);
+        assert!(result.contains("data-found=\"once\""));
+        assert!(stale_result.contains("data-found=\"missing\""));
+    }
+
     #[test]
     fn render_magent_blocks__skips_fenced_code_blocks() {
         let md = "\
@@ -920,7 +1275,7 @@ This should not be processed.
 </magent-response>
 ```
 ";
-        let (result, has_magent) = render_magent_blocks(md);
+        let (result, has_magent) = render_magent_blocks(md, md, 0);
 
         assert!(!has_magent);
         assert!(result.contains("<magent-response>"));
@@ -937,7 +1292,7 @@ First.
 Second.
 </magent-response>
 ";
-        let (result, has_magent) = render_magent_blocks(md);
+        let (result, has_magent) = render_magent_blocks(md, md, 0);
 
         assert!(has_magent);
         assert!(result.contains("<p>First.</p>"));
@@ -951,7 +1306,7 @@ Second.
 <magent-response>
 Unclosed content.
 ";
-        let (result, has_magent) = render_magent_blocks(md);
+        let (result, has_magent) = render_magent_blocks(md, md, 0);
 
         assert!(!has_magent);
         assert!(result.contains("<magent-response>"));
@@ -971,7 +1326,7 @@ Nested response content.
 The summary.
 </magent-response>
 ";
-        let (result, has_magent) = render_magent_blocks(md);
+        let (result, has_magent) = render_magent_blocks(md, md, 0);
 
         assert!(has_magent);
         assert!(result.contains("class=\"magent-tool-result\""));
@@ -988,7 +1343,7 @@ The summary.
 </magent-tool-result>
 </magent-response>
 ";
-        let (result, has_magent) = render_magent_blocks(md);
+        let (result, has_magent) = render_magent_blocks(md, md, 0);
 
         assert!(has_magent);
         assert!(result.contains("1 &lt; 2 &amp;&amp; 3 &gt; 2"));
@@ -1004,7 +1359,7 @@ Thinking hard.
 The answer.
 </magent-response>
 ";
-        let (result, _) = render_magent_blocks(md);
+        let (result, _) = render_magent_blocks(md, md, 0);
 
         // Extract the HTML block (between the first <div and the trailing newlines)
         let div_start = result.find("<div").unwrap();
@@ -1033,7 +1388,7 @@ Fixed URLs:
 </magent-edit>
 </magent-response>
 ";
-        let (result, has_magent) = render_magent_blocks(md);
+        let (result, has_magent) = render_magent_blocks(md, md, 0);
 
         assert!(has_magent);
         assert_eq!(result.matches("magent-edit-search").count(), 2);
@@ -1054,7 +1409,7 @@ Answer.
 
 More text after.
 ";
-        let (result, has_magent) = render_magent_blocks(md);
+        let (result, has_magent) = render_magent_blocks(md, md, 0);
 
         assert!(has_magent);
         assert!(result.starts_with("# Title\n"));
@@ -1243,6 +1598,156 @@ no search or replace tags here
         assert!(accept_magent_edit(doc, 0).is_none());
     }
 
+    // -- reject_magent_edit / request_magent_edit_changes ---------------------
+
+    const PROPOSED_EDIT: &str = "\
+colour
+
+<magent-response>
+<magent-edit status=\"proposed\">
+<magent-search>colour</magent-search>
+<magent-replace>color</magent-replace>
+</magent-edit>
+</magent-response>
+";
+
+    #[test]
+    fn reject_magent_edit__should_mark_the_edit_rejected() {
+        let result = reject_magent_edit(PROPOSED_EDIT, 0).expect("should succeed");
+
+        assert!(result.contains("<magent-edit status=\"rejected\">"));
+        assert!(result.starts_with("colour\n"));
+        // A rejected edit can no longer be accepted.
+        assert!(accept_magent_edit(&result, 0).is_none());
+        assert!(reject_magent_edit(PROPOSED_EDIT, 1).is_none());
+    }
+
+    #[test]
+    fn request_magent_edit_changes__should_append_an_escaped_comment() {
+        let result = request_magent_edit_changes(
+            PROPOSED_EDIT,
+            0,
+            "  Keep <b>British</b> & don't touch it\n",
+        )
+        .expect("should succeed");
+
+        assert!(result.contains(
+            "<magent-replace>color</magent-replace>\n\
+             <magent-comment>Keep &lt;b&gt;British&lt;/b&gt; &amp; don't touch it</magent-comment>\n\
+             </magent-edit>"
+        ));
+        assert!(result.contains("status=\"revise\""));
+        assert!(request_magent_edit_changes(PROPOSED_EDIT, 0, "  ").is_none());
+        assert!(request_magent_edit_changes(&result, 0, "again").is_none());
+
+        let (html, _) = render_magent_blocks(&result, &result, 0);
+        assert!(html.contains("data-status=\"revise\""));
+        assert!(html.contains("<div class=\"magent-edit-comment\"><p>Keep &lt;b&gt;British&lt;/b&gt; &amp; don't touch it</p>"));
+    }
+
+    // -- apply_magent_edit ----------------------------------------------------
+
+    #[test]
+    fn apply_magent_edit__should_replace_the_text_outside_responses() {
+        let doc = "\
+Link to htps://rust-lang.org here.
+
+<magent-response>
+Fixed htps://rust-lang.org:
+<magent-edit status=\"proposed\">
+<magent-search>htps://rust-lang.org</magent-search>
+<magent-replace>https://rust-lang.org</magent-replace>
+</magent-edit>
+</magent-response>
+";
+        let result = apply_magent_edit(doc, 0).expect("should apply");
+
+        assert!(result.starts_with("Link to https://rust-lang.org here.\n"));
+        // The response itself is left as magent wrote it.
+        assert!(result.contains("Fixed htps://rust-lang.org:"));
+        assert!(result.contains("<magent-search>htps://rust-lang.org</magent-search>"));
+        assert!(result.contains("status=\"applied\""));
+        assert_eq!(apply_magent_edit(&result, 0), Err(ApplyEditError::NotFound));
+    }
+
+    #[test]
+    fn apply_magent_edit__should_conflict_unless_the_search_text_matches_once() {
+        let edit = "\
+<magent-response>
+<magent-edit status=\"proposed\">
+<magent-search>teh</magent-search>
+<magent-replace>the</magent-replace>
+</magent-edit>
+</magent-response>
+";
+        let twice = format!("teh cat and teh dog\n\n{edit}");
+        let gone = format!("the cat\n\n{edit}");
+        let after = format!("{edit}\nteh end\n");
+
+        assert_eq!(
+            apply_magent_edit(&twice, 0),
+            Err(ApplyEditError::Conflict(2))
+        );
+        assert_eq!(
+            apply_magent_edit(&gone, 0),
+            Err(ApplyEditError::Conflict(0))
+        );
+        assert!(
+            apply_magent_edit(&after, 0)
+                .expect("should apply")
+                .ends_with("</magent-response>\n\nthe end\n")
+        );
+    }
+
+    // -- unanswered_directives / insert_magent_response -----------------------
+
+    #[test]
+    fn unanswered_directives__should_skip_answered_fenced_and_empty_directives() {
+        let doc = "\
+@magent first
+
+<magent-response>
+@magent inside a response
+</magent-response>
+```
+@magent in a fence
+```
+  @magent second
+Text after.
+@magent
+";
+        assert_eq!(
+            unanswered_directives(doc),
+            vec![PendingDirective {
+                line: 8,
+                prompt: "second".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn insert_magent_response__should_follow_the_directive_after_edits() {
+        // Given
+        let directive = PendingDirective {
+            line: 0,
+            prompt: "summarize".to_string(),
+        };
+        // A heading was added above the directive while the agent ran.
+        let doc = "# Title\n@magent summarize\nNext paragraph.\n";
+
+        // When
+        let result = insert_magent_response(doc, &directive, "Short.\n</magent-response>\n")
+            .expect("should insert");
+
+        // Then
+        assert_eq!(
+            result,
+            "# Title\n@magent summarize\n\n<magent-response>\nShort.\n&lt;/magent-response>\n</magent-response>\n\nNext paragraph.\n"
+        );
+        assert!(unanswered_directives(&result).is_empty());
+        assert!(insert_magent_response(&result, &directive, "Again").is_none());
+    }
+
     // -----------------------------------------------------------------------
     // insert_directive
     // -----------------------------------------------------------------------
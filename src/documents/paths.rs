@@ -195,6 +195,21 @@ pub(crate) fn resolve_text_file_path(root: &Path, file_id: &str) -> Result<PathB
     Ok(resolved)
 }
 
+/// Resolves any browsable file (document, PDF, image or text file) under root.
+pub(crate) fn resolve_browsable_file_path(root: &Path, file_id: &str) -> Result<PathBuf, DocError> {
+    let path = supported_file_id_to_path(file_id).ok_or(DocError::BadPath)?;
+    let joined = root.join(path);
+    let resolved = match std::fs::canonicalize(&joined) {
+        Ok(path) => path,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Err(DocError::NotFound),
+        Err(err) => return Err(DocError::Io(err)),
+    };
+    if !resolved.starts_with(root) {
+        return Err(DocError::NotFound);
+    }
+    Ok(resolved)
+}
+
 pub(super) fn doc_id_to_path(doc_id: &str) -> Option<PathBuf> {
     if doc_id.is_empty() {
         return None;
@@ -234,7 +249,10 @@ fn collect_browsable_files_recursive(
 
         let path = entry.path();
         if file_type.is_dir() {
-            collect_browsable_files_recursive(root, &path, files)?;
+            // One unreadable folder should not hide every other file.
+            if let Err(err) = collect_browsable_files_recursive(root, &path, files) {
+                eprintln!("skipping {}: {err}", path.display());
+            }
             continue;
         }
 
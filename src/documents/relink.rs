@@ -0,0 +1,394 @@
+use super::paths::{collect_markdown_paths, doc_id_from_path};
+use super::rendering::{is_absolute_or_scheme, resolve_relative_path, split_link_fragment};
+use crate::fs::atomic_write;
+use crate::html::decode_percent;
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag};
+use std::ops::Range;
+use std::path::Path;
+
+/// A document whose links were rewritten after a file moved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RelinkedDocument {
+    pub(crate) doc_id: String,
+    pub(crate) changes: Vec<LinkChange>,
+}
+
+/// A document whose links to a moved file could not be rewritten.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RelinkFailure {
+    pub(crate) doc_id: String,
+    pub(crate) error: String,
+}
+
+/// The outcome of [`relink_moved_file`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Relinked {
+    pub(crate) documents: Vec<RelinkedDocument>,
+    pub(crate) failed: Vec<RelinkFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LinkChange {
+    /// Zero-based line index of the link.
+    pub(crate) line: usize,
+    pub(crate) from: String,
+    pub(crate) to: String,
+}
+
+/// Rewrites links after the file `old_id` moved to `new_id`.
+///
+/// Relative links and images, and `/d/`, `/pdf/` and `/file/` references to
+/// the old path are pointed at the new one, keeping any fragment. When the
+/// moved file is a markdown document, its own relative links are adjusted to
+/// its new folder. Documents are rewritten atomically; one that fails to read
+/// or write is left as it was and listed in [`Relinked::failed`].
+pub(crate) fn relink_moved_file(
+    root: &Path,
+    old_id: &str,
+    new_id: &str,
+) -> std::io::Result<Relinked> {
+    let mut paths = collect_markdown_paths(root)?;
+    paths.sort();
+
+    let mut relinked = Relinked::default();
+    for path in paths {
+        let Some(doc_id) = doc_id_from_path(root, &path) else {
+            continue;
+        };
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                eprintln!("skipping {doc_id} while rewriting links: {err}");
+                relinked.failed.push(RelinkFailure {
+                    doc_id,
+                    error: err.to_string(),
+                });
+                continue;
+            }
+        };
+        // The moved document still resolves its links from where it used to be.
+        let base_id = if doc_id == new_id { old_id } else { &doc_id };
+        let (updated, changes) = rewrite_links(&contents, base_id, &doc_id, old_id, new_id);
+        if changes.is_empty() {
+            continue;
+        }
+        if let Err(err) = atomic_write(&path, &updated) {
+            eprintln!("failed to rewrite links in {doc_id}: {err}");
+            relinked.failed.push(RelinkFailure {
+                doc_id,
+                error: err.to_string(),
+            });
+            continue;
+        }
+        relinked
+            .documents
+            .push(RelinkedDocument { doc_id, changes });
+    }
+    Ok(relinked)
+}
+
+/// Rewrites the link destinations in `markdown`, a document that used to live
+/// at `base_id` and now lives at `doc_id`.
+fn rewrite_links(
+    markdown: &str,
+    base_id: &str,
+    doc_id: &str,
+    old_id: &str,
+    new_id: &str,
+) -> (String, Vec<LinkChange>) {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_MATH);
+    options.insert(Options::ENABLE_WIKILINKS);
+    let parser = Parser::new_ext(markdown, options);
+
+    // Reference-style links are rewritten at their definition.
+    let mut spans: Vec<(Range<usize>, String)> = parser
+        .reference_definitions()
+        .iter()
+        .filter_map(|(_, def)| {
+            let dest = find_destination(markdown, def.span.clone(), &def.dest, "]:")?;
+            Some((dest, def.dest.to_string()))
+        })
+        .collect();
+    for (event, range) in parser.into_offset_iter() {
+        let (link_type, dest_url) = match event {
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                ..
+            })
+            | Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                ..
+            }) => (link_type, dest_url),
+            _ => continue,
+        };
+        if link_type != LinkType::Inline {
+            continue;
+        }
+        if let Some(dest) = find_destination(markdown, range, &dest_url, "](") {
+            spans.push((dest, dest_url.to_string()));
+        }
+    }
+    spans.sort_by_key(|(range, _)| range.start);
+
+    let mut output = String::with_capacity(markdown.len());
+    let mut changes = Vec::new();
+    let mut copied = 0;
+    for (range, dest_url) in spans {
+        let Some(rewritten) = rewrite_destination(&dest_url, base_id, doc_id, old_id, new_id)
+        else {
+            continue;
+        };
+        if rewritten == dest_url || range.start < copied {
+            continue;
+        }
+        output.push_str(&markdown[copied..range.start]);
+        output.push_str(&rewritten);
+        copied = range.end;
+        changes.push(LinkChange {
+            line: markdown[..range.start].matches('\n').count(),
+            from: dest_url,
+            to: rewritten,
+        });
+    }
+    output.push_str(&markdown[copied..]);
+    (output, changes)
+}
+
+/// Locates the destination as written inside a link's source span: right
+/// after `opener` (`](` for inline links, `]:` for reference definitions) and
+/// any whitespace or `<`, so link text and titles that mention the destination
+/// are skipped. Inline links take the last such match, since images nested in
+/// the link text come before the link's own destination.
+///
+/// Destinations that differ from their source text (e.g. with backslash
+/// escapes) are not found and so left alone.
+fn find_destination(
+    markdown: &str,
+    span: Range<usize>,
+    dest_url: &str,
+    opener: &str,
+) -> Option<Range<usize>> {
+    if dest_url.is_empty() {
+        return None;
+    }
+    let source = markdown.get(span.clone())?;
+    let mut candidates = source
+        .match_indices(dest_url)
+        .map(|(offset, _)| offset)
+        .filter(|&offset| {
+            source[..offset]
+                .trim_end_matches(|ch: char| ch.is_whitespace() || ch == '<')
+                .ends_with(opener)
+        });
+    let offset = if opener == "](" {
+        candidates.last()
+    } else {
+        candidates.next()
+    }?;
+    let start = span.start + offset;
+    Some(start..start + dest_url.len())
+}
+
+fn rewrite_destination(
+    dest_url: &str,
+    base_id: &str,
+    doc_id: &str,
+    old_id: &str,
+    new_id: &str,
+) -> Option<String> {
+    let (path_part, fragment) = split_link_fragment(dest_url);
+    let decoded = decode_percent(path_part);
+    let fragment = fragment
+        .map(|fragment| format!("#{fragment}"))
+        .unwrap_or_default();
+
+    if let Some(absolute) = decoded.strip_prefix('/') {
+        let (route, rest) = absolute.split_once('/')?;
+        if !matches!(route, "d" | "pdf" | "file") || rest != old_id {
+            return None;
+        }
+        let new_path = encode_like(path_part, new_id);
+        return Some(format!("/{route}/{new_path}{fragment}"));
+    }
+    if decoded.is_empty() || is_absolute_or_scheme(&decoded) {
+        return None;
+    }
+
+    let target = resolve_relative_path(base_id, &decoded)?;
+    let target = if target == old_id {
+        new_id.to_string()
+    } else if base_id != doc_id {
+        target
+    } else {
+        return None;
+    };
+    let relative = relative_link_path(doc_id, &target);
+    Some(format!("{}{fragment}", encode_like(path_part, &relative)))
+}
+
+/// The path of `target_id` relative to the folder of `from_doc_id`.
+fn relative_link_path(from_doc_id: &str, target_id: &str) -> String {
+    let from_dirs: Vec<&str> = from_doc_id.split('/').collect();
+    let from_dirs = &from_dirs[..from_dirs.len() - 1];
+    let target_parts: Vec<&str> = target_id.split('/').collect();
+    let common = from_dirs
+        .iter()
+        .zip(&target_parts[..target_parts.len() - 1])
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts: Vec<&str> = vec![".."; from_dirs.len() - common];
+    parts.extend(&target_parts[common..]);
+    parts.join("/")
+}
+
+/// Percent-encodes spaces in `path` when the original destination was written
+/// without raw spaces, which markdown only allows inside `<...>`.
+fn encode_like(original: &str, path: &str) -> String {
+    if original.contains(' ') {
+        path.to_string()
+    } else {
+        path.replace(' ', "%20")
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_temp_root;
+
+    #[test]
+    fn rewrite_links__should_point_inbound_links_at_the_new_path() {
+        // Given
+        let markdown = "\
+See [plan](projects/plan.md#next-steps) and ![chart](projects/plan.md).
+[raw](/file/projects/plan.md) [other](projects/other.md) [[plan]]
+
+[ref]: ./projects/plan.md \"Plan\"
+";
+
+        // When
+        let (updated, changes) = rewrite_links(
+            markdown,
+            "index.md",
+            "index.md",
+            "projects/plan.md",
+            "archive/2026 plan.md",
+        );
+
+        // Then
+        assert_eq!(
+            updated,
+            "\
+See [plan](archive/2026%20plan.md#next-steps) and ![chart](archive/2026%20plan.md).
+[raw](/file/archive/2026%20plan.md) [other](projects/other.md) [[plan]]
+
+[ref]: archive/2026%20plan.md \"Plan\"
+"
+        );
+        assert_eq!(
+            changes.iter().map(|change| change.line).collect::<Vec<_>>(),
+            vec![0, 0, 1, 3]
+        );
+    }
+
+    #[test]
+    fn rewrite_links__should_adjust_relative_links_of_the_moved_document() {
+        // Given
+        let markdown =
+            "[up](../index.md) [self](plan.md#top) [top](#top) [web](https://x.y/a.md)\n";
+
+        // When
+        let (updated, _) = rewrite_links(
+            markdown,
+            "projects/plan.md",
+            "archive/old/plan.md",
+            "projects/plan.md",
+            "archive/old/plan.md",
+        );
+
+        // Then
+        assert_eq!(
+            updated,
+            "[up](../../index.md) [self](plan.md#top) [top](#top) [web](https://x.y/a.md)\n"
+        );
+    }
+
+    #[test]
+    fn rewrite_links__should_rewrite_the_destination_not_the_text_or_title() {
+        // Given
+        let markdown = "\
+[a.md](a.md \"see a.md\") [![a](a.md)](a.md)
+
+[ref]: a.md 'a.md]: a.md'
+";
+
+        // When
+        let (updated, _) = rewrite_links(markdown, "index.md", "index.md", "a.md", "b.md");
+
+        // Then
+        assert_eq!(
+            updated,
+            "\
+[a.md](b.md \"see a.md\") [![a](b.md)](b.md)
+
+[ref]: b.md 'a.md]: a.md'
+"
+        );
+    }
+
+    #[test]
+    fn relative_link_path__should_walk_up_to_the_common_folder() {
+        assert_eq!(relative_link_path("a/b/doc.md", "a/c/x.png"), "../c/x.png");
+        assert_eq!(relative_link_path("doc.md", "a/x.md"), "a/x.md");
+        assert_eq!(relative_link_path("a/doc.md", "x.md"), "../x.md");
+    }
+
+    #[test]
+    fn relink_moved_file__should_rewrite_and_report_linking_documents() {
+        // Given
+        let root = create_temp_root("relink-moved");
+        std::fs::create_dir_all(root.join("notes")).expect("mkdir");
+        std::fs::write(root.join("index.md"), "[Plan](plan.md)\n").expect("write");
+        std::fs::write(root.join("notes/plan.md"), "[Home](index.md)\n").expect("write");
+        std::fs::write(root.join("other.md"), "No links\n").expect("write");
+
+        // When
+        let relinked = relink_moved_file(&root, "plan.md", "notes/plan.md").expect("relink");
+
+        // Then
+        assert!(relinked.failed.is_empty());
+        assert_eq!(
+            relinked.documents,
+            vec![
+                RelinkedDocument {
+                    doc_id: "index.md".to_string(),
+                    changes: vec![LinkChange {
+                        line: 0,
+                        from: "plan.md".to_string(),
+                        to: "notes/plan.md".to_string(),
+                    }],
+                },
+                RelinkedDocument {
+                    doc_id: "notes/plan.md".to_string(),
+                    changes: vec![LinkChange {
+                        line: 0,
+                        from: "index.md".to_string(),
+                        to: "../index.md".to_string(),
+                    }],
+                },
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("index.md")).expect("read"),
+            "[Plan](notes/plan.md)\n"
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+}
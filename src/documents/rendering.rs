@@ -1,25 +1,40 @@
+use super::front_matter::{blank_front_matter, parse_front_matter, render_front_matter_html};
 use super::paths::doc_id_to_path;
-use super::tasks::{is_task_list_marker, parse_task_line};
+use super::tags::tag_spans;
+use super::tasks::{
+    MetadataKind, TaskMetadata, is_task_list_marker, parse_task_line, task_metadata_tokens,
+};
+use super::wikilinks::{WikiLinkDestination, WikiLinkResolver};
 use super::{is_fence_line, split_line_ending};
 use crate::html;
 use crate::math::{MathStyle, render_math};
-use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use pulldown_cmark::{CodeBlockKind, Event, LinkType, Options, Parser, Tag, TagEnd};
 use std::collections::HashMap;
 
 pub(crate) struct RenderedDocument {
     pub(crate) html: String,
+    /// Title from the front matter, if any.
+    pub(crate) title: Option<String>,
     pub(crate) has_mermaid: bool,
     pub(crate) has_abc: bool,
     pub(crate) has_code: bool,
 }
 
-pub(crate) fn render_document_html(markdown: &str, doc_id: &str) -> RenderedDocument {
-    let stripped = super::magent::strip_magent_blocks(markdown);
+pub(crate) fn render_document_html(
+    markdown: &str,
+    doc_id: &str,
+    wiki_links: &WikiLinkResolver,
+) -> RenderedDocument {
+    let front_matter = parse_front_matter(markdown);
+    let markdown = blank_front_matter(markdown);
+    let stripped = super::magent::strip_magent_blocks(&markdown);
     let rendered = render_task_list_markdown(&stripped, doc_id);
     let mut options = Options::empty();
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_MATH);
+    options.insert(Options::ENABLE_WIKILINKS);
     let parser = Parser::new_ext(&rendered, options).map(|event| {
+        let event = rewrite_wiki_links(event, doc_id, wiki_links);
         let event = rewrite_relative_md_links(event, doc_id);
         rewrite_relative_image_links(event, doc_id)
     });
@@ -36,6 +51,7 @@ pub(crate) fn render_document_html(markdown: &str, doc_id: &str) -> RenderedDocu
     let mut seen_slugs: HashMap<String, usize> = HashMap::new();
     let mut mermaid_buffer = String::new();
     let mut abc_buffer = String::new();
+    let mut tag_links = TagLinker::default();
     let mut events = Vec::new();
 
     for event in parser {
@@ -86,14 +102,14 @@ pub(crate) fn render_document_html(markdown: &str, doc_id: &str) -> RenderedDocu
                 }
                 Event::Text(ref text) | Event::Code(ref text) => {
                     heading_text.push_str(text);
-                    heading_events.push(event);
+                    tag_links.push(event, &mut heading_events);
                 }
                 Event::SoftBreak | Event::HardBreak => {
                     heading_text.push(' ');
-                    heading_events.push(event);
+                    tag_links.push(event, &mut heading_events);
                 }
                 _ => {
-                    heading_events.push(event);
+                    tag_links.push(event, &mut heading_events);
                 }
             }
             continue;
@@ -132,20 +148,79 @@ pub(crate) fn render_document_html(markdown: &str, doc_id: &str) -> RenderedDocu
             }
             other => other,
         };
-        events.push(event);
+        tag_links.push(event, &mut events);
     }
 
-    let mut html = String::new();
+    let mut html = front_matter
+        .as_ref()
+        .map(render_front_matter_html)
+        .unwrap_or_default();
     pulldown_cmark::html::push_html(&mut html, events.into_iter());
 
     RenderedDocument {
         html,
+        title: front_matter.and_then(|front_matter| front_matter.title),
         has_mermaid,
         has_abc,
         has_code,
     }
 }
 
+/// Turns `#tag` text into links to the tag pages, outside code blocks and links.
+#[derive(Default)]
+struct TagLinker {
+    in_code_block: bool,
+    link_depth: usize,
+    /// Last byte of the previous event when it was text.
+    previous_byte: Option<u8>,
+}
+
+impl TagLinker {
+    fn push<'a>(&mut self, event: Event<'a>, events: &mut Vec<Event<'a>>) {
+        let previous_byte = self.previous_byte.take();
+        match event {
+            Event::Text(text) if !self.in_code_block && self.link_depth == 0 => {
+                self.previous_byte = text.as_bytes().last().copied();
+                let spans = tag_spans(&text, previous_byte);
+                if spans.is_empty() {
+                    events.push(Event::Text(text));
+                    return;
+                }
+                let mut copied = 0;
+                for (range, tag) in spans {
+                    if range.start > copied {
+                        events.push(Event::Text(text[copied..range.start].to_string().into()));
+                    }
+                    let html = format!(
+                        "<a class=\"tag\" href=\"/tags/{tag}\">{}</a>",
+                        html::escape(&text[range.clone()])
+                    );
+                    events.push(Event::InlineHtml(html.into()));
+                    copied = range.end;
+                }
+                if copied < text.len() {
+                    events.push(Event::Text(text[copied..].to_string().into()));
+                }
+            }
+            event => {
+                match &event {
+                    Event::Start(Tag::CodeBlock(_)) => self.in_code_block = true,
+                    Event::End(TagEnd::CodeBlock) => self.in_code_block = false,
+                    Event::Start(Tag::Link { .. }) => self.link_depth += 1,
+                    Event::InlineHtml(html) if html.starts_with("<a ") => self.link_depth += 1,
+                    Event::End(TagEnd::Link) => self.link_depth = self.link_depth.saturating_sub(1),
+                    Event::InlineHtml(html) if html.starts_with("</a") => {
+                        self.link_depth = self.link_depth.saturating_sub(1)
+                    }
+                    Event::Text(text) => self.previous_byte = text.as_bytes().last().copied(),
+                    _ => {}
+                }
+                events.push(event);
+            }
+        }
+    }
+}
+
 /// Render a markdown snippet to HTML with link and math handling.
 ///
 /// A lightweight version of `render_document_html` for rendering individual
@@ -174,6 +249,134 @@ pub(crate) fn render_markdown_snippet(text: &str, doc_id: &str) -> String {
     html
 }
 
+/// A heading as rendered by `render_document_html`, with its anchor slug.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HeadingAnchor {
+    /// Zero-based line index of the heading in the source markdown.
+    pub(crate) line: usize,
+    pub(crate) level: u8,
+    pub(crate) text: String,
+    pub(crate) slug: String,
+}
+
+/// List the headings of a document with the same `id` slugs the rendered view uses.
+///
+/// Headings inside `<magent-response>` blocks are skipped since the document
+/// view strips those blocks before rendering.
+pub(crate) fn heading_anchors(markdown: &str) -> Vec<HeadingAnchor> {
+    let stripped = StrippedSource::new(markdown);
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_MATH);
+    options.insert(Options::ENABLE_WIKILINKS);
+
+    let mut anchors = Vec::new();
+    let mut seen_slugs: HashMap<String, usize> = HashMap::new();
+    let mut current: Option<(usize, u8, String)> = None;
+    for (event, range) in Parser::new_ext(&stripped.text, options).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                let line = stripped.source_line(range.start);
+                current = Some((line, level as u8, String::new()));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, heading_text)) = current.as_mut() {
+                    heading_text.push_str(&text);
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some((_, _, heading_text)) = current.as_mut() {
+                    heading_text.push(' ');
+                }
+            }
+            Event::End(TagEnd::Heading(..)) => {
+                if let Some((line, level, text)) = current.take() {
+                    let slug = unique_slug(&text, &mut seen_slugs);
+                    anchors.push(HeadingAnchor {
+                        line,
+                        level,
+                        text,
+                        slug,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    anchors
+}
+
+/// A link from a document to another document, as the rendered view resolves it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OutgoingLink {
+    pub(crate) target: LinkTarget,
+    /// Zero-based line index of the link in the source markdown.
+    pub(crate) line: usize,
+    /// The trimmed source line containing the link.
+    pub(crate) context: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LinkTarget {
+    /// A relative markdown link, resolved to a document id.
+    Document(String),
+    /// A `[[wiki link]]` target (without heading), resolved against all documents later.
+    WikiNote(String),
+}
+
+/// List links to other documents: relative `.md` links rewritten the same way
+/// as `rewrite_relative_md_links`, and `[[wiki links]]`.
+///
+/// Links inside `<magent-response>` blocks are skipped, like in the rendered view.
+pub(crate) fn outgoing_links(markdown: &str, doc_id: &str) -> Vec<OutgoingLink> {
+    let stripped = StrippedSource::new(markdown);
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_MATH);
+    options.insert(Options::ENABLE_WIKILINKS);
+
+    let source_lines: Vec<&str> = markdown.lines().collect();
+    let mut links = Vec::new();
+    for (event, range) in Parser::new_ext(&stripped.text, options).into_offset_iter() {
+        let Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            ..
+        }) = event
+        else {
+            continue;
+        };
+        let target = if let LinkType::WikiLink { .. } = link_type {
+            let (note, _) = split_link_fragment(&dest_url);
+            let note = note.trim();
+            if note.is_empty() {
+                continue;
+            }
+            LinkTarget::WikiNote(note.to_string())
+        } else {
+            let Some(rewritten) = rewrite_relative_md_link(doc_id, &dest_url) else {
+                continue;
+            };
+            let Some(target_id) = rewritten.strip_prefix("/d/") else {
+                continue;
+            };
+            let (target_id, _) = split_link_fragment(target_id);
+            LinkTarget::Document(target_id.to_string())
+        };
+        let line = stripped.source_line(range.start);
+        let context = source_lines
+            .get(line)
+            .map(|line| line.trim().to_string())
+            .unwrap_or_default();
+        links.push(OutgoingLink {
+            target,
+            line,
+            context,
+        });
+    }
+    links
+}
+
 pub(crate) fn render_task_list_markdown(contents: &str, doc_id: &str) -> String {
     let mut output = String::with_capacity(contents.len());
     let mut in_fence = false;
@@ -202,7 +405,7 @@ pub(crate) fn render_task_list_markdown(contents: &str, doc_id: &str) -> String
             );
             output.push_str(parts.prefix);
             output.push_str(&input);
-            output.push_str(parts.suffix);
+            output.push_str(&render_task_metadata_badges(parts.suffix));
             output.push_str(ending);
             task_index += 1;
             in_task_list = true;
@@ -227,6 +430,88 @@ pub(crate) fn render_task_list_markdown(contents: &str, doc_id: &str) -> String
     output
 }
 
+/// Replaces the metadata tokens of a task's text with badges.
+fn render_task_metadata_badges(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut copied = 0;
+    for token in task_metadata_tokens(text) {
+        output.push_str(&text[copied..token.range.start]);
+        output.push_str(&task_badge_html(token.kind));
+        copied = token.range.end;
+    }
+    output.push_str(&text[copied..]);
+    output
+}
+
+/// Badges for a task's due date, scheduled date and priority, for views
+/// that show the task text without its metadata tokens.
+pub(crate) fn task_badges_html(metadata: &TaskMetadata) -> String {
+    [
+        metadata.due.map(MetadataKind::Due),
+        metadata.scheduled.map(MetadataKind::Scheduled),
+        metadata.priority.map(MetadataKind::Priority),
+    ]
+    .into_iter()
+    .flatten()
+    .map(task_badge_html)
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Due badges carry `data-due` so the page can mark open tasks past their
+/// due date in the reader's own time zone.
+fn task_badge_html(kind: MetadataKind) -> String {
+    match kind {
+        MetadataKind::Due(date) => format!(
+            "<span class=\"task-badge task-due\" data-due=\"{date}\" title=\"Due\">📅 {date}</span>"
+        ),
+        MetadataKind::Scheduled(date) => format!(
+            "<span class=\"task-badge task-scheduled\" title=\"Scheduled\">⏳ {date}</span>"
+        ),
+        MetadataKind::Priority(priority) => {
+            let priority = priority.as_str();
+            format!(
+                "<span class=\"task-badge task-priority-{priority}\" title=\"Priority\">!{priority}</span>"
+            )
+        }
+    }
+}
+
+/// Points `[[wiki links]]` at the documents they resolve to. Unresolved links
+/// get the `wiki-link-missing` class and lead to `/new` to create the note.
+pub(crate) fn rewrite_wiki_links<'a>(
+    event: Event<'a>,
+    doc_id: &str,
+    wiki_links: &WikiLinkResolver,
+) -> Event<'a> {
+    match event {
+        Event::Start(Tag::Link {
+            link_type: link_type @ LinkType::WikiLink { .. },
+            dest_url,
+            title,
+            id,
+        }) => match wiki_links.destination(doc_id, &dest_url) {
+            WikiLinkDestination::Resolved(url) => Event::Start(Tag::Link {
+                link_type,
+                dest_url: url.into(),
+                title,
+                id,
+            }),
+            WikiLinkDestination::Missing(path) => {
+                let href = format!("/new?path={}", html::encode_query_value(&path));
+                let escaped_title = html::escape(&format!("Create {path}"));
+                Event::InlineHtml(
+                    format!(
+                        r#"<a class="wiki-link-missing" href="{href}" title="{escaped_title}">"#
+                    )
+                    .into(),
+                )
+            }
+        },
+        _ => event,
+    }
+}
+
 pub(crate) fn rewrite_relative_md_links<'a>(event: Event<'a>, doc_id: &str) -> Event<'a> {
     match event {
         Event::Start(Tag::Link {
@@ -283,6 +568,42 @@ pub(crate) fn rewrite_relative_image_links<'a>(event: Event<'a>, doc_id: &str) -
     }
 }
 
+/// Markdown with front matter blanked and magent blocks stripped, mapping
+/// offsets back to source lines.
+pub(super) struct StrippedSource {
+    pub(super) text: String,
+    /// Source line index for each line of `text`.
+    source_lines: Vec<usize>,
+}
+
+impl StrippedSource {
+    pub(super) fn new(markdown: &str) -> Self {
+        // Blanking keeps lines and stripping removes whole lines, so the kept
+        // lines map back one-to-one.
+        let markdown = &*blank_front_matter(markdown);
+        let magent_regions = super::magent::find_magent_regions(markdown);
+        let source_lines = (0..markdown.split_inclusive('\n').count())
+            .filter(|line| {
+                !magent_regions
+                    .iter()
+                    .any(|region| region.start_line <= *line && *line <= region.end_line)
+            })
+            .collect();
+        Self {
+            text: super::magent::strip_magent_blocks(markdown),
+            source_lines,
+        }
+    }
+
+    pub(super) fn source_line(&self, offset: usize) -> usize {
+        let stripped_line = self.text[..offset].matches('\n').count();
+        self.source_lines
+            .get(stripped_line)
+            .copied()
+            .unwrap_or(stripped_line)
+    }
+}
+
 fn is_mermaid_info(info: &str) -> bool {
     let language = info.split_whitespace().next().unwrap_or("");
     language.eq_ignore_ascii_case("mermaid")
@@ -305,7 +626,7 @@ fn unique_slug(text: &str, seen: &mut HashMap<String, usize>) -> String {
     slug
 }
 
-fn heading_slug(text: &str) -> String {
+pub(super) fn heading_slug(text: &str) -> String {
     let mut slug = String::with_capacity(text.len());
     for ch in text.to_lowercase().chars() {
         if ch.is_alphanumeric() || ch == '-' {
@@ -372,14 +693,14 @@ fn rewrite_relative_image_link(doc_id: &str, dest_url: &str) -> Option<String> {
     Some(new_dest)
 }
 
-fn split_link_fragment(dest_url: &str) -> (&str, Option<&str>) {
+pub(super) fn split_link_fragment(dest_url: &str) -> (&str, Option<&str>) {
     match dest_url.split_once('#') {
         Some((path, fragment)) => (path, Some(fragment)),
         None => (dest_url, None),
     }
 }
 
-fn is_absolute_or_scheme(path: &str) -> bool {
+pub(super) fn is_absolute_or_scheme(path: &str) -> bool {
     if path.starts_with('/') || path.contains("://") {
         return true;
     }
@@ -396,7 +717,7 @@ fn has_extension_ignore_ascii_case(path: &str, ext: &str) -> bool {
     path.len() >= ext.len() && path[path.len() - ext.len()..].eq_ignore_ascii_case(ext)
 }
 
-fn resolve_relative_path(doc_id: &str, dest_path: &str) -> Option<String> {
+pub(super) fn resolve_relative_path(doc_id: &str, dest_path: &str) -> Option<String> {
     let mut parts: Vec<&str> = doc_id.split('/').collect();
     if parts.is_empty() {
         return None;
@@ -519,6 +840,26 @@ mod tests {
         assert!(rendered.contains("```md\n- [ ] nope\n```"));
     }
 
+    #[test]
+    fn render_task_list_markdown__should_render_metadata_badges() {
+        // Given
+        let contents =
+            "- [ ] Ship due:2026-11-01 !high\n- [x] Plan 📅 2026-10-01 scheduled:2026-09-30\n";
+
+        // When
+        let rendered = render_task_list_markdown(contents, "todo.md");
+
+        // Then
+        assert!(rendered.contains(
+            "Ship <span class=\"task-badge task-due\" data-due=\"2026-11-01\" title=\"Due\">📅 2026-11-01</span> \
+<span class=\"task-badge task-priority-high\" title=\"Priority\">!high</span>\n"
+        ));
+        assert!(rendered.contains(
+            "Plan <span class=\"task-badge task-due\" data-due=\"2026-10-01\" title=\"Due\">📅 2026-10-01</span> \
+<span class=\"task-badge task-scheduled\" title=\"Scheduled\">⏳ 2026-09-30</span>\n"
+        ));
+    }
+
     #[test]
     fn render_task_list_markdown__should_render_per_list_forms() {
         // Given
@@ -554,7 +895,7 @@ mod tests {
         let markdown = "# Hello\n\nA paragraph.\n";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then
         assert!(result.html.contains("<h1 id=\"hello\">Hello</h1>"));
@@ -564,13 +905,39 @@ mod tests {
         assert!(!result.has_code);
     }
 
+    #[test]
+    fn render_document_html__should_resolve_wiki_links() {
+        // Given
+        let markdown = "See [[Garden]], [[Garden Plan#Next Steps|the plan]] and [[Seed List]].\n\n\
+```\n[[Not A Link]]\n```\n";
+        let wiki_links = WikiLinkResolver::new([
+            ("projects/plan.md", Some("Garden Plan")),
+            ("Garden.md", None),
+        ]);
+
+        // When
+        let result = render_document_html(markdown, "projects/index.md", &wiki_links);
+
+        // Then
+        assert!(result.html.contains(r#"<a href="/d/Garden.md">Garden</a>"#));
+        assert!(
+            result
+                .html
+                .contains(r#"<a href="/d/projects/plan.md#next-steps">the plan</a>"#)
+        );
+        assert!(result.html.contains(
+            r#"<a class="wiki-link-missing" href="/new?path=projects/Seed%20List.md" title="Create projects/Seed List.md">Seed List</a>"#
+        ));
+        assert!(result.html.contains("[[Not A Link]]"));
+    }
+
     #[test]
     fn render_document_html__should_extract_mermaid_blocks() {
         // Given
         let markdown = "```mermaid\ngraph TD;\nA-->B;\n```\n";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then
         assert!(result.has_mermaid);
@@ -584,7 +951,7 @@ mod tests {
         let markdown = "```abc\nX:1\nT:Test\nK:C\n```\n";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then
         assert!(result.has_abc);
@@ -597,7 +964,7 @@ mod tests {
         let markdown = "```rust\nfn main() {}\n```\n";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then
         assert!(result.has_code);
@@ -611,7 +978,7 @@ mod tests {
         let markdown = "```mermaid\ngraph TD;\nA-->B;\n```\n";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then
         assert!(!result.has_code);
@@ -624,7 +991,7 @@ mod tests {
         let markdown = "```abc\nX:1\nT:Test\nK:C\n```\n";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then
         assert!(!result.has_code);
@@ -637,7 +1004,7 @@ mod tests {
         let markdown = "Equation: $x^2$\n";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then
         assert!(result.html.contains("<math"));
@@ -649,7 +1016,7 @@ mod tests {
         let markdown = "| A | B |\n|---|---|\n| 1 | 2 |\n";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then
         assert!(result.html.contains("<table>"));
@@ -679,13 +1046,120 @@ mod tests {
         assert_eq!(unique_slug("Other", &mut seen), "other");
     }
 
+    #[test]
+    fn heading_anchors__should_match_rendered_ids_and_lines() {
+        // Given
+        let markdown = "# Intro\n\ntext\n\n## Setup `cargo`\n\n## Intro\n";
+
+        // When
+        let anchors = heading_anchors(markdown);
+        let rendered = render_document_html(markdown, "doc.md", &WikiLinkResolver::default());
+
+        // Then
+        let slugs: Vec<&str> = anchors.iter().map(|a| a.slug.as_str()).collect();
+        assert_eq!(slugs, vec!["intro", "setup-cargo", "intro-1"]);
+        let lines: Vec<usize> = anchors.iter().map(|a| a.line).collect();
+        assert_eq!(lines, vec![0, 4, 6]);
+        assert_eq!(anchors[1].text, "Setup cargo");
+        assert_eq!(anchors[1].level, 2);
+        for slug in slugs {
+            assert!(rendered.html.contains(&format!("id=\"{slug}\"")));
+        }
+    }
+
+    #[test]
+    fn heading_anchors__should_skip_headings_in_magent_responses() {
+        // Given
+        let markdown = "# Top\n<magent-response>\n# Hidden\n</magent-response>\n# Top\n";
+
+        // When
+        let anchors = heading_anchors(markdown);
+
+        // Then
+        let slugs: Vec<&str> = anchors.iter().map(|a| a.slug.as_str()).collect();
+        assert_eq!(slugs, vec!["top", "top-1"]);
+        assert_eq!(anchors[1].line, 4);
+    }
+
+    #[test]
+    fn render_document_html__should_render_front_matter_as_property_table() {
+        // Given
+        let markdown = "---\ntitle: Garden <Plan>\naliases: [a, b]\ntags: Garden\n---\n# Heading\n";
+
+        // When
+        let result = render_document_html(markdown, "x.md", &WikiLinkResolver::default());
+
+        // Then
+        assert_eq!(result.title.as_deref(), Some("Garden <Plan>"));
+        assert!(result.html.starts_with(
+            "<details class=\"front-matter\"><summary>Properties</summary><table><tbody>\
+<tr><th scope=\"row\">title</th><td>Garden &lt;Plan&gt;</td></tr>"
+        ));
+        assert!(
+            result
+                .html
+                .contains(r#"<span class="front-matter-item">a</span>"#)
+        );
+        assert!(
+            result
+                .html
+                .contains(r##"<a class="tag front-matter-item" href="/tags/garden">#garden</a>"##)
+        );
+        assert!(!result.html.contains("<hr"));
+        assert!(result.html.contains("<h1 id=\"heading\">Heading</h1>"));
+    }
+
+    #[test]
+    fn render_document_html__should_link_tags_outside_code_and_links() {
+        // Given
+        let markdown = "\
+# Plan #Work
+Buy seeds #garden/veg, `#code` and [#linked](x.md) x#no
+```
+#fenced
+```
+";
+
+        // When
+        let result = render_document_html(markdown, "x.md", &WikiLinkResolver::default());
+
+        // Then
+        assert!(result.html.contains(
+            r##"<h1 id="plan-work">Plan <a class="tag" href="/tags/work">#Work</a></h1>"##
+        ));
+        assert!(result.html.contains(
+            r##"Buy seeds <a class="tag" href="/tags/garden/veg">#garden/veg</a>, <code>#code</code>"##
+        ));
+        assert!(
+            result
+                .html
+                .contains(r##"<a href="/d/x.md">#linked</a> x#no"##)
+        );
+        assert!(result.html.contains("#fenced"));
+        assert_eq!(result.html.matches("class=\"tag\"").count(), 2);
+    }
+
+    #[test]
+    fn heading_anchors__should_skip_front_matter_and_keep_lines() {
+        // Given
+        let markdown = "---\ntitle: Not a heading\n---\n# Top\n";
+
+        // When
+        let anchors = heading_anchors(markdown);
+
+        // Then
+        assert_eq!(anchors.len(), 1);
+        assert_eq!(anchors[0].slug, "top");
+        assert_eq!(anchors[0].line, 3);
+    }
+
     #[test]
     fn render_document_html__should_add_id_to_headings() {
         // Given
         let markdown = "## 2. Proposed Design\n\nSome text.\n";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then
         assert!(
@@ -701,7 +1175,7 @@ mod tests {
         let markdown = "## Hello **world**\n";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then
         assert!(
@@ -717,7 +1191,7 @@ mod tests {
         let markdown = "## Section\n\n## Section\n\n## Section\n";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then
         assert!(result.html.contains("id=\"section\""));
@@ -731,7 +1205,7 @@ mod tests {
         let markdown = "## Target\n\n[Go](#target)\n";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then
         assert!(result.html.contains("<h2 id=\"target\">Target</h2>"));
@@ -754,7 +1228,7 @@ Hi there!
 ";
 
         // When
-        let result = render_document_html(markdown, "notes.md");
+        let result = render_document_html(markdown, "notes.md", &WikiLinkResolver::default());
 
         // Then — directive line is kept, response block is stripped
         assert!(result.html.contains("@magent hello"));
@@ -781,7 +1255,7 @@ The answer is here.
 ";
 
         // When
-        let result = render_document_html(markdown, "test.md");
+        let result = render_document_html(markdown, "test.md", &WikiLinkResolver::default());
 
         // Then — entire response block is stripped
         assert!(!result.html.contains("magent-thinking"));
@@ -1,94 +1,840 @@
-use super::paths::{collect_markdown_paths, doc_id_from_path};
-use std::path::Path;
+mod index;
+mod query;
+
+pub(crate) use index::{DocumentRefresh, STATE_DIR, SearchIndex, state_file};
+pub(crate) use query::{QueryFilter, QueryNode, parse_query};
+
+use super::paths::FileKind;
+use super::tags::tag_matches;
+use super::tasks::TaskItem;
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+/// Maximum number of snippets returned per matching document.
+const MAX_SNIPPETS: usize = 3;
+/// Snippets longer than this (in characters) are cut down to a window around the first match.
+const SNIPPET_MAX_CHARS: usize = 200;
+/// Characters of context kept before the first match when a snippet is cut down.
+const SNIPPET_LEAD_CHARS: usize = 60;
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
 
 pub(crate) struct SearchResult {
     pub(crate) doc_id: String,
-    pub(crate) snippet: String,
+    pub(crate) kind: FileKind,
+    pub(crate) title: String,
+    pub(crate) score: f32,
+    pub(crate) snippets: Vec<SearchSnippet>,
 }
 
-pub(crate) fn search_documents(root: &Path, query: &str) -> std::io::Result<Vec<SearchResult>> {
-    let mut results = Vec::new();
-    let needle = query.to_lowercase();
-    for path in collect_markdown_paths(root)? {
-        let doc_id = match doc_id_from_path(root, &path) {
-            Some(doc_id) => doc_id,
-            None => continue,
+pub(crate) struct SearchSnippet {
+    pub(crate) text: String,
+    /// Byte ranges within `text` that matched the query.
+    pub(crate) highlights: Vec<Range<usize>>,
+    /// 1-based line number of the snippet within the searched text.
+    pub(crate) line: usize,
+    /// Byte range of the snippet within the searched text, excluding any `…`
+    /// added when the line was clipped.
+    pub(crate) source: Range<usize>,
+    /// Slug of the nearest heading at or above the snippet.
+    pub(crate) anchor: Option<String>,
+    /// 1-based PDF page the snippet was found on.
+    pub(crate) page: Option<usize>,
+}
+
+pub(crate) struct SnippetSegment<'a> {
+    pub(crate) text: &'a str,
+    pub(crate) highlighted: bool,
+}
+
+impl SearchResult {
+    /// Link to the matching file in its viewer.
+    pub(crate) fn url(&self) -> String {
+        match self.kind {
+            FileKind::Document => format!("/d/{}", self.doc_id),
+            FileKind::Pdf => format!("/pdf/{}", self.doc_id),
+            FileKind::Image => format!("/file/{}", self.doc_id),
+            FileKind::Text => format!("/view/{}", self.doc_id),
+        }
+    }
+
+    /// Link to `snippet`, pointing at its heading or PDF page when known.
+    pub(crate) fn snippet_url(&self, snippet: &SearchSnippet) -> String {
+        let url = self.url();
+        match (&snippet.anchor, snippet.page) {
+            (Some(anchor), _) => format!("{url}#{anchor}"),
+            (None, Some(page)) => format!("{url}#page={page}"),
+            (None, None) => url,
+        }
+    }
+}
+
+impl SearchSnippet {
+    /// Splits the snippet text into plain and highlighted runs for rendering.
+    pub(crate) fn segments(&self) -> Vec<SnippetSegment<'_>> {
+        let mut segments = Vec::new();
+        let mut cursor = 0;
+        for range in &self.highlights {
+            if range.start > cursor {
+                segments.push(SnippetSegment {
+                    text: &self.text[cursor..range.start],
+                    highlighted: false,
+                });
+            }
+            segments.push(SnippetSegment {
+                text: &self.text[range.clone()],
+                highlighted: true,
+            });
+            cursor = range.end;
+        }
+        if cursor < self.text.len() {
+            segments.push(SnippetSegment {
+                text: &self.text[cursor..],
+                highlighted: false,
+            });
+        }
+        segments
+    }
+}
+
+/// Parses `query` with the search grammar and runs it against the index,
+/// returning at most `limit` results.
+pub(crate) fn search_documents(
+    index: &SearchIndex,
+    query: &str,
+    limit: usize,
+) -> Vec<SearchResult> {
+    run_query(index, &parse_query(query), limit)
+}
+
+/// Finds documents matching `query` and ranks them with BM25.
+///
+/// Each query word matches indexed words it is a prefix of. Term frequencies are
+/// boosted for headings and titles at index time, so those matches rank higher.
+/// Words without any word characters (e.g. `->`) match as case-insensitive
+/// substrings instead. Only the best `limit` results get snippets and are
+/// returned.
+pub(crate) fn run_query(index: &SearchIndex, query: &QueryNode, limit: usize) -> Vec<SearchResult> {
+    let mut positive = PositiveClauses::default();
+    positive.collect(query);
+    let mut terms: Vec<String> = positive
+        .texts
+        .iter()
+        .flat_map(|text| index::tokenize(text))
+        .collect();
+    terms.sort();
+    terms.dedup();
+    let matches: Vec<index::TermMatch> = terms
+        .iter()
+        .map(|term| index.prefix_matches(term))
+        .collect();
+    let highlighter = Highlighter {
+        words: terms,
+        substrings: positive.substrings,
+        tasks: positive.tasks,
+        task_filters: positive.task_filters,
+    };
+
+    let mut scored = Vec::new();
+    for doc_id in evaluate(index, query) {
+        let Some(document) = index.document(&doc_id) else {
+            continue;
         };
-        let contents = std::fs::read_to_string(&path)?;
-        if let Some(snippet) = find_match_snippet(&contents, &needle) {
-            results.push(SearchResult { doc_id, snippet });
+        let score = bm25_score(index, document, &matches);
+        scored.push((doc_id, document, score));
+    }
+    scored.sort_by(|a, b| b.2.total_cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+    scored.truncate(limit);
+
+    scored
+        .into_iter()
+        .map(|(doc_id, document, score)| SearchResult {
+            kind: document.kind,
+            title: document.title.clone(),
+            snippets: collect_snippets(document, |line_idx, line| {
+                highlighter.highlight(document, line_idx, line)
+            }),
+            doc_id,
+            score,
+        })
+        .collect()
+}
+
+/// Returns the ids of all documents matching `node`.
+fn evaluate(index: &SearchIndex, node: &QueryNode) -> BTreeSet<String> {
+    match node {
+        QueryNode::Term(text) => {
+            let tokens: Vec<String> = index::tokenize(text).collect();
+            if tokens.is_empty() {
+                return matching_documents(index, |_, document| document.lowercase.contains(text));
+            }
+            intersect_prefix_matches(index, &tokens)
+        }
+        QueryNode::Phrase(phrase) => {
+            let tokens: Vec<String> = index::tokenize(phrase).collect();
+            let candidates = if tokens.is_empty() {
+                all_documents(index)
+            } else {
+                intersect_prefix_matches(index, &tokens)
+            };
+            candidates
+                .into_iter()
+                .filter(|doc_id| {
+                    index
+                        .document(doc_id)
+                        .is_some_and(|document| document.lowercase.contains(phrase.as_str()))
+                })
+                .collect()
+        }
+        QueryNode::Filter(filter) => matching_documents(index, |doc_id, document| {
+            filter_matches(filter, doc_id, document)
+        }),
+        QueryNode::Not(inner) => {
+            let excluded = evaluate(index, inner);
+            all_documents(index)
+                .into_iter()
+                .filter(|doc_id| !excluded.contains(doc_id))
+                .collect()
+        }
+        QueryNode::Or(children) => children
+            .iter()
+            .flat_map(|child| evaluate(index, child))
+            .collect(),
+        QueryNode::And(children) => {
+            if children.is_empty() {
+                return BTreeSet::new();
+            }
+            // Evaluate positive clauses first so exclusions only filter their result.
+            let (negative, positive): (Vec<&QueryNode>, Vec<&QueryNode>) = children
+                .iter()
+                .partition(|child| matches!(child, QueryNode::Not(_)));
+            let mut result = match positive.split_first() {
+                Some((first, rest)) => {
+                    let mut result = evaluate(index, first);
+                    for child in rest {
+                        if result.is_empty() {
+                            break;
+                        }
+                        let matched = evaluate(index, child);
+                        result.retain(|doc_id| matched.contains(doc_id));
+                    }
+                    result
+                }
+                None => all_documents(index),
+            };
+            for child in negative {
+                if let QueryNode::Not(inner) = child {
+                    let excluded = evaluate(index, inner);
+                    result.retain(|doc_id| !excluded.contains(doc_id));
+                }
+            }
+            result
+        }
+    }
+}
+
+fn intersect_prefix_matches(index: &SearchIndex, tokens: &[String]) -> BTreeSet<String> {
+    let mut result: Option<BTreeSet<String>> = None;
+    for token in tokens {
+        let matched = index.prefix_matches(token).doc_ids;
+        result = Some(match result {
+            Some(mut current) => {
+                current.retain(|doc_id| matched.contains(doc_id));
+                current
+            }
+            None => matched,
+        });
+    }
+    result.unwrap_or_default()
+}
+
+fn all_documents(index: &SearchIndex) -> BTreeSet<String> {
+    index
+        .documents()
+        .map(|(doc_id, _)| doc_id.clone())
+        .collect()
+}
+
+fn matching_documents(
+    index: &SearchIndex,
+    predicate: impl Fn(&str, &index::IndexedDocument) -> bool,
+) -> BTreeSet<String> {
+    index
+        .documents()
+        .filter(|(doc_id, document)| predicate(doc_id, document))
+        .map(|(doc_id, _)| doc_id.clone())
+        .collect()
+}
+
+fn filter_matches(filter: &QueryFilter, doc_id: &str, document: &index::IndexedDocument) -> bool {
+    match filter {
+        QueryFilter::Path(folder) => {
+            let folder = folder.trim_end_matches('/');
+            folder.is_empty()
+                || doc_id == folder
+                || doc_id
+                    .strip_prefix(folder)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        }
+        QueryFilter::Ext(ext) => doc_id
+            .rsplit_once('.')
+            .is_some_and(|(_, doc_ext)| doc_ext.eq_ignore_ascii_case(ext)),
+        QueryFilter::Tag(tag) => document
+            .tags
+            .iter()
+            .any(|doc_tag| tag_matches(doc_tag, tag)),
+        QueryFilter::IsTask => !document.tasks.is_empty(),
+        QueryFilter::IsOpenTask => document.tasks.iter().any(|task| !task.checked),
+        QueryFilter::Mentions(user) => document.mentions.contains(user),
+        QueryFilter::Date(prefix) => document
+            .front_matter
+            .dates
+            .iter()
+            .any(|(_, date)| date.starts_with(prefix.as_str())),
+        QueryFilter::Due(_) | QueryFilter::Scheduled(_) | QueryFilter::Priority(_) => {
+            document.tasks.iter().any(|task| task_matches(filter, task))
         }
     }
-    results.sort_by(|a, b| a.doc_id.cmp(&b.doc_id));
-    Ok(results)
 }
 
-fn find_match_snippet(contents: &str, needle: &str) -> Option<String> {
-    for line in contents.lines() {
-        if line.to_lowercase().contains(needle) {
-            return Some(line.trim().to_string());
+/// Whether a task's metadata matches a `due:`, `scheduled:` or `priority:` filter.
+fn task_matches(filter: &QueryFilter, task: &TaskItem) -> bool {
+    let date_matches = |date: Option<time::Date>, prefix: &str| {
+        date.is_some_and(|date| date.to_string().starts_with(prefix))
+    };
+    match filter {
+        QueryFilter::Due(prefix) => date_matches(task.metadata.due, prefix),
+        QueryFilter::Scheduled(prefix) => date_matches(task.metadata.scheduled, prefix),
+        QueryFilter::Priority(priority) => task.metadata.priority == Some(*priority),
+        _ => false,
+    }
+}
+
+/// Text and filters outside any exclusion, used for scoring and highlighting.
+#[derive(Default)]
+struct PositiveClauses {
+    texts: Vec<String>,
+    substrings: Vec<String>,
+    tasks: Option<TaskLines>,
+    task_filters: Vec<QueryFilter>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TaskLines {
+    All,
+    Open,
+}
+
+impl PositiveClauses {
+    fn collect(&mut self, node: &QueryNode) {
+        match node {
+            QueryNode::Term(text) => {
+                if index::tokenize(text).next().is_some() {
+                    self.texts.push(text.clone());
+                } else {
+                    self.substrings.push(text.clone());
+                }
+            }
+            QueryNode::Phrase(phrase) => {
+                self.texts.push(phrase.clone());
+                self.substrings.push(phrase.clone());
+            }
+            QueryNode::Filter(QueryFilter::Tag(tag)) => self.substrings.push(format!("#{tag}")),
+            QueryNode::Filter(QueryFilter::Mentions(user)) => {
+                self.substrings.push(format!("@{user}"));
+            }
+            QueryNode::Filter(QueryFilter::IsTask) => {
+                self.tasks.get_or_insert(TaskLines::All);
+            }
+            QueryNode::Filter(QueryFilter::IsOpenTask) => self.tasks = Some(TaskLines::Open),
+            QueryNode::Filter(
+                filter @ (QueryFilter::Due(_)
+                | QueryFilter::Scheduled(_)
+                | QueryFilter::Priority(_)),
+            ) => self.task_filters.push(filter.clone()),
+            QueryNode::Filter(
+                QueryFilter::Path(_) | QueryFilter::Ext(_) | QueryFilter::Date(_),
+            ) => {}
+            QueryNode::Not(_) => {}
+            QueryNode::Or(children) | QueryNode::And(children) => {
+                for child in children {
+                    self.collect(child);
+                }
+            }
         }
     }
-    None
+}
+
+/// Marks matches in snippet lines. A line qualifies if any word or substring
+/// matches or, for task queries, if it is a matching task item.
+struct Highlighter {
+    words: Vec<String>,
+    substrings: Vec<String>,
+    tasks: Option<TaskLines>,
+    task_filters: Vec<QueryFilter>,
+}
+
+impl Highlighter {
+    fn highlight(
+        &self,
+        document: &index::IndexedDocument,
+        line_idx: usize,
+        line: &str,
+    ) -> Option<Vec<Range<usize>>> {
+        let mut highlights = word_highlights(line, &self.words);
+        for needle in &self.substrings {
+            highlights.extend(substring_highlights(line, needle));
+        }
+        let is_task_line = document.tasks.iter().any(|task| {
+            task.line == line_idx
+                && (self
+                    .tasks
+                    .is_some_and(|wanted| wanted == TaskLines::All || !task.checked)
+                    || self
+                        .task_filters
+                        .iter()
+                        .any(|filter| task_matches(filter, task)))
+        });
+        if highlights.is_empty() && !is_task_line {
+            return None;
+        }
+        Some(merge_ranges(highlights))
+    }
+}
+
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|range| (range.start, range.end));
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+fn bm25_score(
+    index: &SearchIndex,
+    document: &index::IndexedDocument,
+    matches: &[index::TermMatch],
+) -> f32 {
+    let doc_count = index.document_count() as f32;
+    let average_length = index.average_length().max(1.0);
+    let length_norm = 1.0 - BM25_B + BM25_B * document.length as f32 / average_length;
+    let mut score = 0.0;
+    for term_match in matches {
+        let frequency: f32 = term_match
+            .terms
+            .iter()
+            .filter_map(|term| document.term_weights.get(term))
+            .sum();
+        if frequency == 0.0 {
+            continue;
+        }
+        let doc_frequency = term_match.doc_ids.len() as f32;
+        let idf = (1.0 + (doc_count - doc_frequency + 0.5) / (doc_frequency + 0.5)).ln();
+        score += idf * frequency * (BM25_K1 + 1.0) / (frequency + BM25_K1 * length_norm);
+    }
+    score
+}
+
+/// Collects up to `MAX_SNIPPETS` matching lines, in document order.
+fn collect_snippets(
+    document: &index::IndexedDocument,
+    highlight: impl Fn(usize, &str) -> Option<Vec<Range<usize>>>,
+) -> Vec<SearchSnippet> {
+    let mut snippets = Vec::new();
+    let mut line_start = 0;
+    for (line_idx, raw_line) in document.contents.split_inclusive('\n').enumerate() {
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+        let text_start = line_start + (line.len() - line.trim_start().len());
+        line_start += raw_line.len();
+        let text = line.trim();
+        let Some(highlights) = highlight(line_idx, text) else {
+            continue;
+        };
+        let (text, highlights, kept) = clip_snippet(text, highlights);
+        let anchor = document
+            .headings
+            .iter()
+            .rev()
+            .find(|heading| heading.line <= line_idx)
+            .map(|heading| heading.slug.clone());
+        let page = match document
+            .page_lines
+            .partition_point(|&start| start <= line_idx)
+        {
+            0 => None,
+            page => Some(page),
+        };
+        snippets.push(SearchSnippet {
+            text,
+            highlights,
+            line: line_idx + 1,
+            source: text_start + kept.start..text_start + kept.end,
+            anchor,
+            page,
+        });
+        if snippets.len() == MAX_SNIPPETS {
+            break;
+        }
+    }
+    snippets
+}
+
+/// Marks every word in `line` that starts with one of `terms`.
+fn word_highlights(line: &str, terms: &[String]) -> Vec<Range<usize>> {
+    let mut highlights = Vec::new();
+    let mut word_start = None;
+    for (idx, ch) in line
+        .char_indices()
+        .chain(std::iter::once((line.len(), ' ')))
+    {
+        match (ch.is_alphanumeric(), word_start) {
+            (true, None) => word_start = Some(idx),
+            (false, Some(start)) => {
+                let word = line[start..idx].to_lowercase();
+                if terms.iter().any(|term| word.starts_with(term.as_str())) {
+                    highlights.push(start..idx);
+                }
+                word_start = None;
+            }
+            _ => {}
+        }
+    }
+    highlights
+}
+
+/// Marks case-insensitive occurrences of `needle` (already lowercase) in `line`.
+fn substring_highlights(line: &str, needle: &str) -> Vec<Range<usize>> {
+    let lowercase = line.to_lowercase();
+    if lowercase.len() != line.len() {
+        // Lowercasing changed byte offsets, so they cannot be mapped back.
+        return Vec::new();
+    }
+    lowercase
+        .match_indices(needle)
+        .map(|(start, matched)| start..start + matched.len())
+        .collect()
+}
+
+/// Cuts long lines down to a window around the first highlight. Also returns
+/// the byte range of `text` that was kept.
+fn clip_snippet(
+    text: &str,
+    highlights: Vec<Range<usize>>,
+) -> (String, Vec<Range<usize>>, Range<usize>) {
+    if text.chars().count() <= SNIPPET_MAX_CHARS {
+        return (text.to_string(), highlights, 0..text.len());
+    }
+    let first = highlights.first().map_or(0, |range| range.start);
+    let start = text[..first]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_LEAD_CHARS.saturating_sub(1))
+        .map_or(0, |(idx, _)| idx);
+    let end = text[start..]
+        .char_indices()
+        .nth(SNIPPET_MAX_CHARS)
+        .map_or(text.len(), |(idx, _)| start + idx);
+
+    let mut clipped = String::new();
+    let mut offset = 0;
+    if start > 0 {
+        clipped.push('…');
+        offset = '…'.len_utf8();
+    }
+    clipped.push_str(&text[start..end]);
+    if end < text.len() {
+        clipped.push('…');
+    }
+    let highlights = highlights
+        .into_iter()
+        .filter(|range| range.start >= start && range.end <= end)
+        .map(|range| range.start - start + offset..range.end - start + offset)
+        .collect();
+    (clipped, highlights, start..end)
 }
 
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod tests {
     use super::*;
-    use crate::test_support::create_temp_root;
+    use crate::test_support::build_index;
+
+    fn highlighted(snippet: &SearchSnippet) -> Vec<&str> {
+        snippet
+            .segments()
+            .into_iter()
+            .filter(|segment| segment.highlighted)
+            .map(|segment| segment.text)
+            .collect()
+    }
+
+    fn ids(results: &[SearchResult]) -> Vec<&str> {
+        let mut ids: Vec<&str> = results.iter().map(|r| r.doc_id.as_str()).collect();
+        ids.sort();
+        ids
+    }
 
     #[test]
-    fn find_match_snippet__should_return_first_matching_line() {
+    fn search_documents__should_match_exact_phrases() {
         // Given
-        let contents = "First line\nSecond contains hello world\nThird line\n";
+        let (root, index) = build_index(
+            "search-phrase",
+            &[
+                ("exact.md", "the weekly review happens friday"),
+                ("apart.md", "review the weekly plan"),
+            ],
+        );
 
         // When
-        let snippet = find_match_snippet(contents, "hello");
+        let results = search_documents(&index, r#""weekly review""#, 50);
 
         // Then
-        assert_eq!(snippet, Some("Second contains hello world".to_string()));
+        assert_eq!(ids(&results), vec!["exact.md"]);
+        assert_eq!(highlighted(&results[0].snippets[0]), vec!["weekly review"]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
     #[test]
-    fn find_match_snippet__should_be_case_insensitive() {
+    fn search_documents__should_exclude_negated_clauses() {
         // Given
-        let contents = "Title: Hello World\n";
+        let (root, index) = build_index(
+            "search-exclude",
+            &[("keep.md", "garden plan"), ("drop.md", "garden plan draft")],
+        );
 
         // When
-        let snippet = find_match_snippet(contents, "hello");
+        let results = search_documents(&index, "garden -draft", 50);
 
         // Then
-        assert_eq!(snippet, Some("Title: Hello World".to_string()));
+        assert_eq!(ids(&results), vec!["keep.md"]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
     #[test]
-    fn find_match_snippet__should_return_none_when_no_match() {
+    fn search_documents__should_support_or() {
         // Given
-        let contents = "Nothing relevant here\n";
+        let (root, index) = build_index(
+            "search-or",
+            &[
+                ("cats.md", "cats food"),
+                ("dogs.md", "dogs food"),
+                ("fish.md", "fish food"),
+                ("cats-only.md", "cats"),
+            ],
+        );
 
         // When
-        let snippet = find_match_snippet(contents, "missing");
+        let results = search_documents(&index, "cats OR dogs food", 50);
 
         // Then
-        assert_eq!(snippet, None);
+        assert_eq!(ids(&results), vec!["cats.md", "dogs.md"]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_filter_by_path_and_extension() {
+        // Given
+        let (root, index) = build_index(
+            "search-path",
+            &[
+                ("projects/alpha/plan.md", "plan"),
+                ("projects/beta.md", "plan"),
+                ("projects-old/plan.md", "plan"),
+                ("notes/plan.md", "plan"),
+            ],
+        );
+
+        // When
+        let in_projects = search_documents(&index, "plan path:projects", 50);
+        let filter_only = search_documents(&index, "path:projects/alpha ext:md", 50);
+        let wrong_ext = search_documents(&index, "plan ext:pdf", 50);
+
+        // Then
+        assert_eq!(
+            ids(&in_projects),
+            vec!["projects/alpha/plan.md", "projects/beta.md"]
+        );
+        assert_eq!(ids(&filter_only), vec!["projects/alpha/plan.md"]);
+        assert!(wrong_ext.is_empty());
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_filter_by_tag_and_mention() {
+        // Given
+        let (root, index) = build_index(
+            "search-tags",
+            &[
+                ("tagged.md", "Seeds for #garden, ask @Alice"),
+                ("nested.md", "Tomatoes #garden/vegetables"),
+                ("prefixed.md", "Tools #gardening"),
+                ("untagged.md", "garden seeds, ask @bob"),
+            ],
+        );
+
+        // When
+        let tagged = search_documents(&index, "tag:garden", 50);
+        let mentions = search_documents(&index, "seeds mentions:@alice", 50);
+
+        // Then
+        assert_eq!(ids(&tagged), vec!["nested.md", "tagged.md"]);
+        assert_eq!(highlighted(&tagged[0].snippets[0]), vec!["#garden"]);
+        assert_eq!(ids(&mentions), vec!["tagged.md"]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_use_front_matter_metadata() {
+        // Given
+        let (root, index) = build_index(
+            "search-front-matter",
+            &[
+                (
+                    "plan.md",
+                    "---\ntitle: Garden Plan\naliases: [Allotment]\ntags: [outdoors]\ndate: 2026-10-01\n---\n# Heading\n",
+                ),
+                ("other.md", "+++\ndate = \"2025-01-01\"\n+++\nNothing\n"),
+            ],
+        );
+
+        // When
+        let alias = search_documents(&index, "allotment", 50);
+        let tag = search_documents(&index, "tag:outdoors", 50);
+        let date = search_documents(&index, "date:2026-10", 50);
+
+        // Then
+        assert_eq!(ids(&alias), vec!["plan.md"]);
+        assert_eq!(alias[0].title, "Garden Plan");
+        assert_eq!(ids(&tag), vec!["plan.md"]);
+        assert_eq!(ids(&date), vec!["plan.md"]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_filter_tasks_and_show_task_lines() {
+        // Given
+        let (root, index) = build_index(
+            "search-tasks",
+            &[
+                ("open.md", "# Todo\n- [ ] buy milk\n- [x] call mom\n"),
+                ("done.md", "- [x] all done\n"),
+                ("none.md", "no tasks here\n"),
+            ],
+        );
+
+        // When
+        let any_tasks = search_documents(&index, "is:task", 50);
+        let open_tasks = search_documents(&index, "is:open-task", 50);
+
+        // Then
+        assert_eq!(ids(&any_tasks), vec!["done.md", "open.md"]);
+        assert_eq!(ids(&open_tasks), vec!["open.md"]);
+        let snippets: Vec<&str> = open_tasks[0]
+            .snippets
+            .iter()
+            .map(|snippet| snippet.text.as_str())
+            .collect();
+        assert_eq!(snippets, vec!["- [ ] buy milk"]);
+        assert_eq!(open_tasks[0].snippets[0].anchor.as_deref(), Some("todo"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_filter_by_task_metadata() {
+        // Given
+        let (root, index) = build_index(
+            "search-task-metadata",
+            &[
+                (
+                    "plan.md",
+                    "# Plan\n- [ ] ship due:2026-11-01 !high\n- [ ] write scheduled:2026-10-30\n",
+                ),
+                ("later.md", "- [ ] renew 📅 2027-01-15 !low\n"),
+            ],
+        );
+
+        // When
+        let due = search_documents(&index, "due:2026-11", 50);
+        let scheduled = search_documents(&index, "scheduled:2026", 50);
+        let low = search_documents(&index, "priority:low", 50);
+
+        // Then
+        assert_eq!(ids(&due), vec!["plan.md"]);
+        assert_eq!(due[0].snippets[0].line, 2);
+        assert_eq!(ids(&scheduled), vec!["plan.md"]);
+        assert_eq!(scheduled[0].snippets[0].line, 3);
+        assert_eq!(ids(&low), vec!["later.md"]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_return_first_matching_line_as_snippet() {
+        // Given
+        let (root, index) = build_index(
+            "search-first-line",
+            &[(
+                "doc.md",
+                "First line\nSecond contains hello world\nThird line\n",
+            )],
+        );
+
+        // When
+        let results = search_documents(&index, "hello", 50);
+
+        // Then
+        assert_eq!(results[0].snippets.len(), 1);
+        assert_eq!(results[0].snippets[0].text, "Second contains hello world");
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_be_case_insensitive() {
+        // Given
+        let (root, index) = build_index("search-case", &[("doc.md", "Title: Hello World\n")]);
+
+        // When
+        let results = search_documents(&index, "hello", 50);
+
+        // Then
+        assert_eq!(results[0].snippets[0].text, "Title: Hello World");
+        assert_eq!(highlighted(&results[0].snippets[0]), vec!["Hello"]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
     #[test]
     fn search_documents__should_find_matching_docs() {
         // Given
-        let root = create_temp_root("search");
-        std::fs::write(root.join("alpha.md"), "Alpha has the needle").expect("write");
-        std::fs::write(root.join("beta.md"), "Beta has nothing").expect("write");
-        std::fs::write(root.join("gamma.md"), "Gamma also has the needle").expect("write");
+        let (root, index) = build_index(
+            "search",
+            &[
+                ("alpha.md", "Alpha has the needle"),
+                ("beta.md", "Beta has nothing"),
+                ("gamma.md", "Gamma also has the needle"),
+            ],
+        );
 
         // When
-        let results = search_documents(&root, "needle").expect("search");
+        let results = search_documents(&index, "needle", 50);
 
         // Then
         assert_eq!(results.len(), 2);
         assert_eq!(results[0].doc_id, "alpha.md");
-        assert_eq!(results[0].snippet, "Alpha has the needle");
+        assert_eq!(results[0].snippets[0].text, "Alpha has the needle");
         assert_eq!(results[1].doc_id, "gamma.md");
 
         std::fs::remove_dir_all(&root).expect("cleanup");
@@ -97,15 +843,238 @@ mod tests {
     #[test]
     fn search_documents__should_return_empty_for_no_matches() {
         // Given
-        let root = create_temp_root("search-empty");
-        std::fs::write(root.join("doc.md"), "Nothing here").expect("write");
+        let (root, index) = build_index("search-empty", &[("doc.md", "Nothing here")]);
 
         // When
-        let results = search_documents(&root, "missing").expect("search");
+        let results = search_documents(&index, "missing", 50);
 
         // Then
         assert!(results.is_empty());
 
         std::fs::remove_dir_all(&root).expect("cleanup");
     }
+
+    #[test]
+    fn search_documents__should_require_every_word() {
+        // Given
+        let (root, index) = build_index(
+            "search-and",
+            &[("both.md", "apples and oranges"), ("one.md", "only apples")],
+        );
+
+        // When
+        let results = search_documents(&index, "oranges apples", 50);
+
+        // Then
+        let ids: Vec<&str> = results.iter().map(|r| r.doc_id.as_str()).collect();
+        assert_eq!(ids, vec!["both.md"]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_rank_heading_matches_above_body_matches() {
+        // Given
+        let (root, index) = build_index(
+            "search-rank",
+            &[
+                ("a-body.md", "# Notes\n\nSome text about gardening here.\n"),
+                (
+                    "b-heading.md",
+                    "# Notes\n\n## Gardening\n\nSome text here.\n",
+                ),
+                ("c-title.md", "# Gardening\n\nSome text here.\n"),
+            ],
+        );
+
+        // When
+        let results = search_documents(&index, "gardening", 50);
+
+        // Then
+        let ids: Vec<&str> = results.iter().map(|r| r.doc_id.as_str()).collect();
+        assert_eq!(ids, vec!["c-title.md", "b-heading.md", "a-body.md"]);
+        assert!(results[0].score > results[1].score);
+        assert!(results[1].score > results[2].score);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_rank_frequent_matches_higher() {
+        // Given
+        let (root, index) = build_index(
+            "search-tf",
+            &[
+                ("once.md", "tea is fine, coffee is better"),
+                ("twice.md", "tea, tea and more"),
+                ("other.md", "no drinks"),
+            ],
+        );
+
+        // When
+        let results = search_documents(&index, "tea", 50);
+
+        // Then
+        assert_eq!(results[0].doc_id, "twice.md");
+        assert_eq!(results[1].doc_id, "once.md");
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_use_title_from_first_heading_or_file_name() {
+        // Given
+        let (root, index) = build_index(
+            "search-title",
+            &[
+                ("notes/with-heading.md", "# Garden Plan\nseeds"),
+                ("notes/plain.md", "seeds"),
+            ],
+        );
+
+        // When
+        let results = search_documents(&index, "seeds", 50);
+
+        // Then
+        let titles: Vec<&str> = results.iter().map(|r| r.title.as_str()).collect();
+        assert!(titles.contains(&"Garden Plan"));
+        assert!(titles.contains(&"plain"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_limit_snippets_and_link_nearest_heading() {
+        // Given
+        let contents = "intro fox\n# First\nfox one\n## Second\nfox two\nfox three\nfox four\n";
+        let (root, index) = build_index("search-snippets", &[("doc.md", contents)]);
+
+        // When
+        let results = search_documents(&index, "fox", 50);
+
+        // Then
+        let snippets = &results[0].snippets;
+        assert_eq!(snippets.len(), MAX_SNIPPETS);
+        assert_eq!(snippets[0].anchor, None);
+        assert_eq!(snippets[1].anchor.as_deref(), Some("first"));
+        assert_eq!(snippets[2].anchor.as_deref(), Some("second"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_link_snippets_to_headings_text_files_and_pdf_pages() {
+        // Given
+        let (root, mut index) = build_index(
+            "search-links",
+            &[
+                ("notes/doc.md", "# Engines\nturbine notes\n"),
+                ("data/specs.yaml", "engine: turbine\n"),
+            ],
+        );
+        std::fs::write(
+            root.join("manual.pdf"),
+            crate::test_support::minimal_pdf(&["Contents", "Turbine service"]),
+        )
+        .expect("write");
+        index
+            .refresh_document(&root, "manual.pdf")
+            .expect("refresh");
+
+        // When
+        let results = search_documents(&index, "turbine", 50);
+
+        // Then
+        let urls: Vec<(String, String)> = results
+            .iter()
+            .map(|result| (result.url(), result.snippet_url(&result.snippets[0])))
+            .collect();
+        assert!(urls.contains(&(
+            "/d/notes/doc.md".to_string(),
+            "/d/notes/doc.md#engines".to_string()
+        )));
+        assert!(urls.contains(&(
+            "/view/data/specs.yaml".to_string(),
+            "/view/data/specs.yaml".to_string()
+        )));
+        assert!(urls.contains(&(
+            "/pdf/manual.pdf".to_string(),
+            "/pdf/manual.pdf#page=2".to_string()
+        )));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_report_snippet_lines_and_byte_offsets() {
+        // Given
+        let contents = "# Title\r\n\r\n   indented otter line\r\nlast\r\n";
+        let (root, index) = build_index("search-offsets", &[("doc.md", contents)]);
+
+        // When
+        let results = search_documents(&index, "otter", 50);
+
+        // Then
+        let snippet = &results[0].snippets[0];
+        assert_eq!(snippet.line, 3);
+        assert_eq!(&contents[snippet.source.clone()], "indented otter line");
+        assert_eq!(snippet.text, "indented otter line");
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_highlight_prefix_matched_words() {
+        // Given
+        let (root, index) = build_index("search-prefix", &[("doc.md", "Running runners ran")]);
+
+        // When
+        let results = search_documents(&index, "run", 50);
+
+        // Then
+        assert_eq!(
+            highlighted(&results[0].snippets[0]),
+            vec!["Running", "runners"]
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn search_documents__should_fall_back_to_substring_for_symbol_queries() {
+        // Given
+        let (root, index) = build_index(
+            "search-symbols",
+            &[("a.md", "use a -> b arrow"), ("b.md", "no arrow")],
+        );
+
+        // When
+        let results = search_documents(&index, "->", 50);
+
+        // Then
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].doc_id, "a.md");
+        assert_eq!(highlighted(&results[0].snippets[0]), vec!["->"]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn clip_snippet__should_window_long_lines_around_first_match() {
+        // Given
+        let text = format!("{}needle{}", "a ".repeat(200), " b".repeat(200));
+        let start = text.find("needle").expect("needle");
+        let highlights = vec![start..start + 6, text.len() - 1..text.len()];
+
+        // When
+        let (clipped, highlights, kept) = clip_snippet(&text, highlights);
+
+        // Then
+        assert!(clipped.starts_with('…'));
+        assert!(clipped.ends_with('…'));
+        assert!(clipped.chars().count() <= SNIPPET_MAX_CHARS + 2);
+        assert_eq!(highlights.len(), 1);
+        assert_eq!(&clipped[highlights[0].clone()], "needle");
+        assert!(clipped.contains(&text[kept]));
+    }
 }
@@ -0,0 +1,819 @@
+use super::super::DocError;
+use super::super::front_matter::{FrontMatter, parse_front_matter};
+use super::super::paths::{FileKind, collect_browsable_files, resolve_browsable_file_path};
+use super::super::rendering::{
+    HeadingAnchor, LinkTarget, OutgoingLink, heading_anchors, outgoing_links,
+};
+use super::super::tags::{TagLine, collect_tag_lines};
+use super::super::tasks::{TaskItem, collect_mentions, collect_tasks};
+use super::super::wikilinks::WikiLinkResolver;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+/// Directory (relative to the root) holding server-owned state such as the search cache.
+pub(crate) const STATE_DIR: &str = ".mindex";
+const CACHE_FILE: &str = "search-index.json";
+const CACHE_VERSION: u32 = 2;
+/// Extra weight added to a term for every occurrence in a heading.
+const HEADING_BOOST: f32 = 2.0;
+/// Extra weight added to a term for every occurrence in the document title.
+const TITLE_BOOST: f32 = 4.0;
+
+/// In-memory inverted index over all searchable files under the root: markdown
+/// documents, text files and the extracted text of PDFs.
+///
+/// Maps lowercase word tokens to the documents containing them, so a query only
+/// touches documents that share its terms instead of rereading the whole root.
+#[derive(Debug, Default)]
+pub(crate) struct SearchIndex {
+    documents: BTreeMap<String, IndexedDocument>,
+    postings: BTreeMap<String, BTreeSet<String>>,
+    total_length: u64,
+    wiki_links: Arc<WikiLinkResolver>,
+    /// Links into each document, as the linking document and the position of
+    /// the link in its `links`.
+    linked_from: HashMap<String, BTreeSet<(String, usize)>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct IndexedDocument {
+    pub(crate) kind: FileKind,
+    /// Searchable text: the file contents, or the extracted text of a PDF.
+    pub(crate) contents: String,
+    pub(crate) lowercase: String,
+    /// Front matter title of a markdown document, then its first level-one
+    /// heading, falling back to the file stem; the file name for other kinds.
+    pub(crate) title: String,
+    /// Parsed front matter; empty when the document has none.
+    pub(crate) front_matter: FrontMatter,
+    pub(crate) headings: Vec<HeadingAnchor>,
+    /// Per-term frequency, boosted for heading and title occurrences.
+    pub(crate) term_weights: HashMap<String, f32>,
+    /// Number of word tokens in the document body.
+    pub(crate) length: u32,
+    /// Front matter tags followed by inline tags, deduplicated.
+    pub(crate) tags: Vec<String>,
+    /// Lines using inline tags, for the tag pages.
+    pub(crate) tag_lines: Vec<TagLine>,
+    /// Mentioned usernames, lowercased.
+    pub(crate) mentions: BTreeSet<String>,
+    pub(crate) tasks: Vec<TaskItem>,
+    /// Links to other documents, for the link graph.
+    pub(crate) links: Vec<OutgoingLink>,
+    /// First line of each PDF page within `contents`; empty for other kinds.
+    pub(crate) page_lines: Vec<usize>,
+    fingerprint: Option<Fingerprint>,
+}
+
+/// One file as read from disk for [`SearchIndex::apply_refresh`]. Reading,
+/// which may extract PDF text, does not need the index, so callers can do it
+/// before locking the index.
+pub(crate) struct DocumentRefresh {
+    doc_id: String,
+    /// `None` when the file is gone or not searchable.
+    read: Option<(FileKind, SearchableText, Option<Fingerprint>)>,
+}
+
+impl DocumentRefresh {
+    pub(crate) fn read(root: &Path, doc_id: &str) -> std::io::Result<Self> {
+        let gone = || Self {
+            doc_id: doc_id.to_string(),
+            read: None,
+        };
+        let kind = Path::new(doc_id)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(FileKind::from_extension);
+        let (path, kind) = match (resolve_browsable_file_path(root, doc_id), kind) {
+            (Ok(_), Some(FileKind::Image) | None) => return Ok(gone()),
+            (Ok(path), Some(kind)) => (path, kind),
+            (Err(DocError::NotFound) | Err(DocError::BadPath) | Err(DocError::Conflict), _) => {
+                return Ok(gone());
+            }
+            (Err(DocError::Io(err)), _) => return Err(err),
+        };
+        match SearchableText::read(&path, kind) {
+            Ok(text) => Ok(Self {
+                doc_id: doc_id.to_string(),
+                read: Some((kind, text, Fingerprint::from_path(&path))),
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(gone()),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Index terms matched by one query term, and the documents containing any of them.
+pub(crate) struct TermMatch {
+    pub(crate) terms: Vec<String>,
+    pub(crate) doc_ids: BTreeSet<String>,
+}
+
+impl SearchIndex {
+    /// Builds the index by reading every searchable file under `root`.
+    pub(crate) fn build(root: &Path) -> std::io::Result<Self> {
+        Self::build_with_cache(root, &HashMap::new())
+    }
+
+    /// Builds the index, reusing cached contents from `.mindex/search-index.json`
+    /// for files whose size and modification time are unchanged, then rewrites the cache.
+    /// This mostly saves re-extracting text from PDFs.
+    pub(crate) fn load_cached(root: &Path) -> std::io::Result<Self> {
+        let cached = read_cache(root);
+        let index = Self::build_with_cache(root, &cached)?;
+        index.write_cache(root)?;
+        Ok(index)
+    }
+
+    fn build_with_cache(
+        root: &Path,
+        cached: &HashMap<String, CachedDocument>,
+    ) -> std::io::Result<Self> {
+        let mut index = Self::default();
+        for file in collect_browsable_files(root)? {
+            if file.kind == FileKind::Image {
+                continue;
+            }
+            let path = root.join(&file.path);
+            let fingerprint = Fingerprint::from_path(&path);
+            let reusable = cached
+                .get(&file.path)
+                .filter(|entry| fingerprint.as_ref() == Some(&entry.fingerprint));
+            let text = match reusable {
+                Some(entry) => SearchableText {
+                    contents: entry.contents.clone(),
+                    page_lines: entry.page_lines.clone(),
+                },
+                None => match SearchableText::read(&path, file.kind) {
+                    Ok(text) => text,
+                    Err(err) => {
+                        eprintln!("skipping {} while indexing: {err}", file.path);
+                        continue;
+                    }
+                },
+            };
+            index.insert(file.path, file.kind, text, fingerprint);
+        }
+        index.rebuild_links();
+        Ok(index)
+    }
+
+    /// Re-reads a single file from disk, dropping it from the index if it no
+    /// longer exists or is not searchable.
+    #[cfg(test)]
+    pub(crate) fn refresh_document(&mut self, root: &Path, doc_id: &str) -> std::io::Result<()> {
+        let refresh = DocumentRefresh::read(root, doc_id)?;
+        self.apply_refresh(refresh);
+        Ok(())
+    }
+
+    /// Applies a file read by [`DocumentRefresh::read`].
+    ///
+    /// Links are re-resolved for the refreshed document only, unless it was
+    /// added, removed or got a new first heading, which can change where other
+    /// documents' links point.
+    pub(crate) fn apply_refresh(&mut self, refresh: DocumentRefresh) {
+        let doc_id = refresh.doc_id;
+        let before = self.documents.get(&doc_id).map(link_identity);
+        self.unlink_source(&doc_id);
+        match refresh.read {
+            Some((kind, text, fingerprint)) => {
+                self.update_document(&doc_id, kind, text, fingerprint)
+            }
+            None => self.remove_document(&doc_id),
+        }
+        let after = self.documents.get(&doc_id).map(link_identity);
+        if before == after {
+            self.link_source(&doc_id);
+        } else {
+            self.rebuild_links();
+        }
+    }
+
+    fn update_document(
+        &mut self,
+        doc_id: &str,
+        kind: FileKind,
+        text: SearchableText,
+        fingerprint: Option<Fingerprint>,
+    ) {
+        self.remove_document(doc_id);
+        self.insert(doc_id.to_string(), kind, text, fingerprint);
+    }
+
+    fn remove_document(&mut self, doc_id: &str) {
+        let Some(document) = self.documents.remove(doc_id) else {
+            return;
+        };
+        self.total_length -= u64::from(document.length);
+        for term in document.term_weights.keys() {
+            if let Some(doc_ids) = self.postings.get_mut(term) {
+                doc_ids.remove(doc_id);
+                if doc_ids.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn document(&self, doc_id: &str) -> Option<&IndexedDocument> {
+        self.documents.get(doc_id)
+    }
+
+    pub(crate) fn documents(&self) -> impl Iterator<Item = (&String, &IndexedDocument)> {
+        self.documents.iter()
+    }
+
+    pub(crate) fn document_count(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub(crate) fn average_length(&self) -> f32 {
+        if self.documents.is_empty() {
+            return 0.0;
+        }
+        self.total_length as f32 / self.documents.len() as f32
+    }
+
+    /// Resolver for `[[wiki links]]` over the indexed markdown documents.
+    pub(crate) fn wiki_link_resolver(&self) -> Arc<WikiLinkResolver> {
+        Arc::clone(&self.wiki_links)
+    }
+
+    /// The indexed document a link in `source_id` points at, if any. Self-links
+    /// resolve to `None`.
+    pub(crate) fn resolve_link(&self, source_id: &str, target: &LinkTarget) -> Option<String> {
+        let target_id = match target {
+            LinkTarget::Document(target_id) => self.document(target_id).map(|_| target_id.as_str()),
+            LinkTarget::WikiNote(note) => self.wiki_links.resolve(source_id, note),
+        }?;
+        (target_id != source_id).then(|| target_id.to_string())
+    }
+
+    /// Links pointing at `doc_id` from other documents, in path and line order.
+    pub(crate) fn linked_from(
+        &self,
+        doc_id: &str,
+    ) -> impl Iterator<Item = (&String, &IndexedDocument, &OutgoingLink)> {
+        self.linked_from
+            .get(doc_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|(source_id, position)| {
+                let (source_id, document) = self.documents.get_key_value(source_id)?;
+                Some((source_id, document, document.links.get(*position)?))
+            })
+    }
+
+    fn rebuild_links(&mut self) {
+        self.wiki_links = Arc::new(WikiLinkResolver::new(
+            self.documents
+                .iter()
+                .filter(|(_, document)| document.kind == FileKind::Document)
+                .map(|(doc_id, document)| {
+                    let first_heading = document.headings.first().map(|h| h.text.as_str());
+                    (doc_id.as_str(), first_heading)
+                }),
+        ));
+        self.linked_from.clear();
+        let doc_ids: Vec<String> = self.documents.keys().cloned().collect();
+        for doc_id in doc_ids {
+            self.link_source(&doc_id);
+        }
+    }
+
+    /// Resolves the links of `source_id` into the reverse link map.
+    fn link_source(&mut self, source_id: &str) {
+        for (target_id, position) in self.resolved_links(source_id) {
+            self.linked_from
+                .entry(target_id)
+                .or_default()
+                .insert((source_id.to_string(), position));
+        }
+    }
+
+    /// Drops the links of `source_id` from the reverse link map.
+    fn unlink_source(&mut self, source_id: &str) {
+        for (target_id, position) in self.resolved_links(source_id) {
+            if let Some(sources) = self.linked_from.get_mut(&target_id) {
+                sources.remove(&(source_id.to_string(), position));
+                if sources.is_empty() {
+                    self.linked_from.remove(&target_id);
+                }
+            }
+        }
+    }
+
+    fn resolved_links(&self, source_id: &str) -> Vec<(String, usize)> {
+        let Some(document) = self.documents.get(source_id) else {
+            return Vec::new();
+        };
+        document
+            .links
+            .iter()
+            .enumerate()
+            .filter_map(|(position, link)| {
+                let target_id = self.resolve_link(source_id, &link.target)?;
+                Some((target_id, position))
+            })
+            .collect()
+    }
+
+    /// Expands `prefix` to every indexed term starting with it.
+    pub(crate) fn prefix_matches(&self, prefix: &str) -> TermMatch {
+        let mut matching = TermMatch {
+            terms: Vec::new(),
+            doc_ids: BTreeSet::new(),
+        };
+        for (term, doc_ids) in self.postings.range(prefix.to_string()..) {
+            if !term.starts_with(prefix) {
+                break;
+            }
+            matching.terms.push(term.clone());
+            matching.doc_ids.extend(doc_ids.iter().cloned());
+        }
+        matching
+    }
+
+    fn insert(
+        &mut self,
+        doc_id: String,
+        kind: FileKind,
+        text: SearchableText,
+        fingerprint: Option<Fingerprint>,
+    ) {
+        let SearchableText {
+            contents,
+            page_lines,
+        } = text;
+        let lowercase = contents.to_lowercase();
+        let is_markdown = kind == FileKind::Document;
+        let headings = if is_markdown {
+            heading_anchors(&contents)
+        } else {
+            Vec::new()
+        };
+        let front_matter = is_markdown
+            .then(|| parse_front_matter(&contents))
+            .flatten()
+            .unwrap_or_default();
+        let title = if is_markdown {
+            front_matter
+                .title
+                .clone()
+                .or_else(|| {
+                    headings
+                        .iter()
+                        .find(|heading| heading.level == 1)
+                        .map(|heading| heading.text.clone())
+                })
+                .unwrap_or_else(|| file_stem(&doc_id).to_string())
+        } else {
+            file_name(&doc_id).to_string()
+        };
+
+        let mut term_weights: HashMap<String, f32> = HashMap::new();
+        let mut length = 0u32;
+        for term in tokenize(&lowercase) {
+            *term_weights.entry(term).or_default() += 1.0;
+            length += 1;
+        }
+        for heading in &headings {
+            for term in tokenize(&heading.text) {
+                *term_weights.entry(term).or_default() += HEADING_BOOST;
+            }
+        }
+        for term in std::iter::once(&title)
+            .chain(&front_matter.aliases)
+            .flat_map(|title| tokenize(title))
+        {
+            *term_weights.entry(term).or_default() += TITLE_BOOST;
+        }
+
+        for term in term_weights.keys() {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .insert(doc_id.clone());
+        }
+        self.total_length += u64::from(length);
+        let (tags, tag_lines, mentions, tasks, links) = if is_markdown {
+            let tag_lines = collect_tag_lines(&contents);
+            let mut tags = front_matter.tags.clone();
+            for tag_line in &tag_lines {
+                if !tags.contains(&tag_line.tag) {
+                    tags.push(tag_line.tag.clone());
+                }
+            }
+            (
+                tags,
+                tag_lines,
+                collect_mentions(&contents)
+                    .into_iter()
+                    .map(|(user, _)| user.to_lowercase())
+                    .collect(),
+                collect_tasks(&contents),
+                outgoing_links(&contents, &doc_id),
+            )
+        } else {
+            (
+                Vec::new(),
+                Vec::new(),
+                BTreeSet::new(),
+                Vec::new(),
+                Vec::new(),
+            )
+        };
+        self.documents.insert(
+            doc_id,
+            IndexedDocument {
+                kind,
+                contents,
+                lowercase,
+                title,
+                front_matter,
+                headings,
+                term_weights,
+                length,
+                tags,
+                tag_lines,
+                mentions,
+                tasks,
+                links,
+                page_lines,
+                fingerprint,
+            },
+        );
+    }
+
+    fn write_cache(&self, root: &Path) -> std::io::Result<()> {
+        let documents = self
+            .documents
+            .iter()
+            .filter_map(|(doc_id, document)| {
+                let fingerprint = document.fingerprint.clone()?;
+                Some(CachedDocument {
+                    doc_id: doc_id.clone(),
+                    fingerprint,
+                    contents: document.contents.clone(),
+                    page_lines: document.page_lines.clone(),
+                })
+            })
+            .collect();
+        let cache = CacheFile {
+            version: CACHE_VERSION,
+            documents,
+        };
+        let json = serde_json::to_string(&cache).map_err(std::io::Error::other)?;
+        crate::fs::atomic_write(&state_file(root, CACHE_FILE)?, &json)
+    }
+}
+
+/// What other documents' links depend on: whether a document exists, its kind
+/// and the first heading wiki links match.
+fn link_identity(document: &IndexedDocument) -> (FileKind, Option<String>) {
+    let first_heading = document.headings.first().map(|h| h.text.clone());
+    (document.kind, first_heading)
+}
+
+/// Splits text into lowercase alphanumeric word tokens.
+pub(crate) fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn file_name(doc_id: &str) -> &str {
+    doc_id.rsplit('/').next().unwrap_or(doc_id)
+}
+
+fn file_stem(doc_id: &str) -> &str {
+    let name = file_name(doc_id);
+    name.rsplit_once('.').map_or(name, |(stem, _)| stem)
+}
+
+/// Text extracted from a file for indexing.
+struct SearchableText {
+    contents: String,
+    page_lines: Vec<usize>,
+}
+
+impl SearchableText {
+    fn read(path: &Path, kind: FileKind) -> std::io::Result<Self> {
+        let contents = match kind {
+            FileKind::Document => std::fs::read_to_string(path)?,
+            FileKind::Text => String::from_utf8_lossy(&std::fs::read(path)?).into_owned(),
+            FileKind::Pdf => {
+                let pages = extract_pdf_pages(&std::fs::read(path)?).unwrap_or_else(|err| {
+                    eprintln!("failed to extract text from {}: {err}", path.display());
+                    Vec::new()
+                });
+                return Ok(Self::from_pages(&pages));
+            }
+            FileKind::Image => String::new(),
+        };
+        Ok(Self {
+            contents,
+            page_lines: Vec::new(),
+        })
+    }
+
+    /// Joins PDF pages into one text, remembering the line each page starts on.
+    fn from_pages(pages: &[String]) -> Self {
+        let mut contents = String::new();
+        let mut page_lines = Vec::with_capacity(pages.len());
+        let mut line_count = 0;
+        for page in pages {
+            page_lines.push(line_count);
+            let page = page.trim_matches(|ch: char| ch == '\n' || ch == '\r');
+            for line in page.lines() {
+                contents.push_str(line);
+                contents.push('\n');
+                line_count += 1;
+            }
+        }
+        Self {
+            contents,
+            page_lines,
+        }
+    }
+}
+
+/// Extracts the text of each page. The extractor can panic on malformed input,
+/// so panics are turned into errors instead of taking the server down.
+fn extract_pdf_pages(bytes: &[u8]) -> Result<Vec<String>, String> {
+    match std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem_by_pages(bytes)) {
+        Ok(Ok(pages)) => Ok(pages),
+        Ok(Err(err)) => Err(err.to_string()),
+        Err(_) => Err("PDF text extractor panicked".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Fingerprint {
+    len: u64,
+    modified_secs: u64,
+    modified_nanos: u32,
+}
+
+impl Fingerprint {
+    fn from_path(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let modified = metadata
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Some(Self {
+            len: metadata.len(),
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    documents: Vec<CachedDocument>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedDocument {
+    doc_id: String,
+    fingerprint: Fingerprint,
+    contents: String,
+    #[serde(default)]
+    page_lines: Vec<usize>,
+}
+
+/// The path of `file` in the state directory. Creates the directory with a
+/// `.gitignore`, so server state is never committed with the notes.
+pub(crate) fn state_file(root: &Path, file: &str) -> std::io::Result<PathBuf> {
+    let rel_path = Path::new(STATE_DIR).join(file);
+    crate::fs::ensure_parent_dirs(root, &rel_path)?;
+    let ignore = root.join(STATE_DIR).join(".gitignore");
+    if !ignore.exists() {
+        std::fs::write(&ignore, "*\n")?;
+    }
+    Ok(root.join(rel_path))
+}
+
+fn cache_rel_path() -> PathBuf {
+    Path::new(STATE_DIR).join(CACHE_FILE)
+}
+
+fn read_cache(root: &Path) -> HashMap<String, CachedDocument> {
+    let Ok(raw) = std::fs::read_to_string(root.join(cache_rel_path())) else {
+        return HashMap::new();
+    };
+    let cache: CacheFile = match serde_json::from_str(&raw) {
+        Ok(cache) => cache,
+        Err(err) => {
+            eprintln!("ignoring unreadable search index cache: {err}");
+            return HashMap::new();
+        }
+    };
+    if cache.version != CACHE_VERSION {
+        return HashMap::new();
+    }
+    cache
+        .documents
+        .into_iter()
+        .map(|document| (document.doc_id.clone(), document))
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_temp_root;
+
+    #[test]
+    fn tokenize__should_split_on_non_alphanumeric_and_lowercase() {
+        // When
+        let tokens: Vec<String> = tokenize("Hello, World! snake_case-42").collect();
+
+        // Then
+        assert_eq!(tokens, vec!["hello", "world", "snake", "case", "42"]);
+    }
+
+    #[test]
+    fn prefix_matches__should_expand_to_all_terms_with_prefix() {
+        // Given
+        let root = create_temp_root("index-prefix");
+        std::fs::write(root.join("a.md"), "Rust programming notes").expect("write");
+        std::fs::write(root.join("b.md"), "Rusty nails").expect("write");
+        std::fs::write(root.join("c.md"), "Go").expect("write");
+        let index = SearchIndex::build(&root).expect("build index");
+
+        // When
+        let matched = index.prefix_matches("rust");
+
+        // Then
+        assert_eq!(matched.terms, vec!["rust", "rusty"]);
+        assert_eq!(
+            matched.doc_ids.into_iter().collect::<Vec<_>>(),
+            vec!["a.md", "b.md"]
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn refresh_document__should_replace_terms_of_changed_document() {
+        // Given
+        let root = create_temp_root("index-refresh");
+        std::fs::write(root.join("doc.md"), "old words").expect("write");
+        let mut index = SearchIndex::build(&root).expect("build index");
+        std::fs::write(root.join("doc.md"), "new words").expect("write");
+
+        // When
+        index.refresh_document(&root, "doc.md").expect("refresh");
+
+        // Then
+        assert!(index.prefix_matches("old").doc_ids.is_empty());
+        assert_eq!(index.prefix_matches("new").doc_ids.len(), 1);
+        assert!(!index.postings.contains_key("old"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn refresh_document__should_remove_deleted_document() {
+        // Given
+        let root = create_temp_root("index-remove");
+        std::fs::write(root.join("doc.md"), "ephemeral").expect("write");
+        let mut index = SearchIndex::build(&root).expect("build index");
+        std::fs::remove_file(root.join("doc.md")).expect("remove");
+
+        // When
+        index.refresh_document(&root, "doc.md").expect("refresh");
+
+        // Then
+        assert!(index.document("doc.md").is_none());
+        assert!(index.postings.is_empty());
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn load_cached__should_reuse_contents_of_unchanged_files() {
+        // Given
+        let root = create_temp_root("index-cache");
+        std::fs::write(root.join("doc.md"), "on disk").expect("write");
+        SearchIndex::load_cached(&root).expect("initial load");
+        let raw =
+            std::fs::read_to_string(root.join(".mindex/search-index.json")).expect("read cache");
+        let tampered = raw.replace("on disk", "from cache");
+        std::fs::write(root.join(".mindex/search-index.json"), tampered).expect("write cache");
+
+        // When
+        let index = SearchIndex::load_cached(&root).expect("cached load");
+
+        // Then
+        let document = index.document("doc.md").expect("indexed");
+        assert_eq!(document.contents, "from cache");
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn load_cached__should_reread_files_that_changed_size() {
+        // Given
+        let root = create_temp_root("index-cache-stale");
+        std::fs::write(root.join("doc.md"), "first").expect("write");
+        SearchIndex::load_cached(&root).expect("initial load");
+        std::fs::write(root.join("doc.md"), "second version").expect("write");
+
+        // When
+        let index = SearchIndex::load_cached(&root).expect("cached load");
+
+        // Then
+        let document = index.document("doc.md").expect("indexed");
+        assert_eq!(document.contents, "second version");
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn build__should_index_text_files_and_pdf_pages() {
+        // Given
+        let root = create_temp_root("index-files");
+        std::fs::write(root.join("config.toml"), "name = \"zephyr\"\n").expect("write");
+        std::fs::write(
+            root.join("manual.pdf"),
+            crate::test_support::minimal_pdf(&["Intro page", "Gearbox maintenance"]),
+        )
+        .expect("write");
+        std::fs::write(root.join("photo.png"), b"not an image").expect("write");
+
+        // When
+        let index = SearchIndex::build(&root).expect("build index");
+
+        // Then
+        let config = index.document("config.toml").expect("text file indexed");
+        assert_eq!(config.kind, FileKind::Text);
+        assert_eq!(config.title, "config.toml");
+        let manual = index.document("manual.pdf").expect("pdf indexed");
+        assert_eq!(manual.kind, FileKind::Pdf);
+        assert_eq!(manual.contents, "Intro page\nGearbox maintenance\n");
+        assert_eq!(manual.page_lines, vec![0, 1]);
+        assert!(index.document("photo.png").is_none());
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn build__should_index_unreadable_pdfs_as_empty() {
+        // Given
+        let root = create_temp_root("index-bad-pdf");
+        std::fs::write(root.join("broken.pdf"), b"%PDF-1.4 garbage").expect("write");
+
+        // When
+        let index = SearchIndex::build(&root).expect("build index");
+
+        // Then
+        let broken = index.document("broken.pdf").expect("pdf indexed");
+        assert!(broken.contents.is_empty());
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn build__should_skip_documents_that_are_not_utf8() {
+        // Given
+        let root = create_temp_root("index-bad-utf8");
+        std::fs::write(root.join("latin1.md"), b"caf\xe9 notes").expect("write");
+        std::fs::write(root.join("good.md"), "Good notes").expect("write");
+
+        // When
+        let index = SearchIndex::build(&root).expect("build index");
+
+        // Then
+        assert!(index.document("latin1.md").is_none());
+        assert!(index.document("good.md").is_some());
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn refresh_document__should_index_new_text_files() {
+        // Given
+        let root = create_temp_root("index-refresh-text");
+        let mut index = SearchIndex::build(&root).expect("build index");
+        std::fs::write(root.join("data.json"), r#"{"city": "Lisbon"}"#).expect("write");
+
+        // When
+        index.refresh_document(&root, "data.json").expect("refresh");
+
+        // Then
+        assert!(index.prefix_matches("lisbon").doc_ids.contains("data.json"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+}
@@ -0,0 +1,340 @@
+use super::super::tasks::TaskPriority;
+
+/// Parsed search query.
+///
+/// Grammar (whitespace separated, case-insensitive except for `OR`):
+///
+/// - `word` — documents containing a word starting with `word`
+/// - `"some phrase"` — documents containing the exact phrase
+/// - `-clause` — excludes documents matching `clause`
+/// - words without letters or digits (e.g. `->`) match as substrings
+/// - `a OR b` — either clause; binds tighter than the implicit AND between clauses
+/// - `path:notes/` — document is in the folder (or is the file)
+/// - `ext:md` — file extension
+/// - `tag:foo` — document is tagged `#foo` or a nested tag such as `#foo/bar`
+/// - `is:task`, `is:open-task` — document has (open) task items
+/// - `mentions:@alice` — document mentions `@alice`
+/// - `date:2026-10` — a front matter date (`date`, `created`, `updated`,
+///   `modified`) starts with the prefix
+/// - `due:2026-11`, `scheduled:2026-10` — a task's due or scheduled date
+///   starts with the prefix
+/// - `priority:high`, `priority:low` — a task has the priority
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum QueryNode {
+    Term(String),
+    Phrase(String),
+    Filter(QueryFilter),
+    Not(Box<QueryNode>),
+    Or(Vec<QueryNode>),
+    And(Vec<QueryNode>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum QueryFilter {
+    Path(String),
+    Ext(String),
+    Tag(String),
+    IsTask,
+    IsOpenTask,
+    Mentions(String),
+    Date(String),
+    Due(String),
+    Scheduled(String),
+    Priority(TaskPriority),
+}
+
+pub(crate) fn parse_query(input: &str) -> QueryNode {
+    let mut groups: Vec<Vec<QueryNode>> = Vec::new();
+    let mut pending_or = false;
+    for token in lex(input) {
+        match token {
+            Token::Or => pending_or = !groups.is_empty(),
+            Token::Clause(clause) => {
+                match groups.last_mut() {
+                    Some(group) if pending_or => group.push(clause),
+                    _ => groups.push(vec![clause]),
+                }
+                pending_or = false;
+            }
+        }
+    }
+
+    let mut clauses: Vec<QueryNode> = groups
+        .into_iter()
+        .map(|mut group| {
+            if group.len() == 1 {
+                group.remove(0)
+            } else {
+                QueryNode::Or(group)
+            }
+        })
+        .collect();
+    if clauses.len() == 1 {
+        clauses.remove(0)
+    } else {
+        QueryNode::And(clauses)
+    }
+}
+
+enum Token {
+    Or,
+    Clause(QueryNode),
+}
+
+fn lex(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(_, ch)) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        // `-` only negates when it prefixes a word or phrase, so `->` stays literal.
+        let mut lookahead = chars.clone();
+        lookahead.next();
+        let negated = ch == '-'
+            && lookahead
+                .peek()
+                .is_some_and(|&(_, next)| next.is_alphanumeric() || next == '"');
+        if negated {
+            chars.next();
+        }
+
+        let body_start = chars.peek().map_or(input.len(), |&(idx, _)| idx);
+        let mut in_quotes = false;
+        let mut end = input.len();
+        while let Some(&(idx, ch)) = chars.peek() {
+            if ch == '"' {
+                in_quotes = !in_quotes;
+            } else if ch.is_whitespace() && !in_quotes {
+                end = idx;
+                break;
+            }
+            chars.next();
+        }
+        let raw = &input[body_start..end];
+
+        if !negated && raw == "OR" {
+            tokens.push(Token::Or);
+            continue;
+        }
+        let Some(clause) = parse_clause(raw) else {
+            continue;
+        };
+        let clause = if negated {
+            QueryNode::Not(Box::new(clause))
+        } else {
+            clause
+        };
+        tokens.push(Token::Clause(clause));
+    }
+    tokens
+}
+
+fn parse_clause(raw: &str) -> Option<QueryNode> {
+    if let Some(quoted) = raw.strip_prefix('"') {
+        let phrase = quoted.strip_suffix('"').unwrap_or(quoted).trim();
+        if phrase.is_empty() {
+            return None;
+        }
+        return Some(QueryNode::Phrase(phrase.to_lowercase()));
+    }
+
+    if let Some((key, value)) = raw.split_once(':') {
+        let value = unquote(value);
+        if !value.is_empty()
+            && let Some(filter) = parse_filter(&key.to_ascii_lowercase(), value)
+        {
+            return Some(QueryNode::Filter(filter));
+        }
+    }
+
+    let term = unquote(raw);
+    if term.is_empty() {
+        return None;
+    }
+    Some(QueryNode::Term(term.to_lowercase()))
+}
+
+fn parse_filter(key: &str, value: &str) -> Option<QueryFilter> {
+    match key {
+        "path" => Some(QueryFilter::Path(value.trim_start_matches('/').to_string())),
+        "ext" => Some(QueryFilter::Ext(
+            value.trim_start_matches('.').to_ascii_lowercase(),
+        )),
+        "tag" => Some(QueryFilter::Tag(
+            value.trim_start_matches('#').to_ascii_lowercase(),
+        )),
+        "is" => match value.to_ascii_lowercase().as_str() {
+            "task" => Some(QueryFilter::IsTask),
+            "open-task" => Some(QueryFilter::IsOpenTask),
+            _ => None,
+        },
+        "mentions" => {
+            let user = value.trim_start_matches('@');
+            (!user.is_empty()).then(|| QueryFilter::Mentions(user.to_lowercase()))
+        }
+        "date" => Some(QueryFilter::Date(value.to_string())),
+        "due" => Some(QueryFilter::Due(value.to_string())),
+        "scheduled" => Some(QueryFilter::Scheduled(value.to_string())),
+        "priority" => match value.trim_start_matches('!').to_ascii_lowercase().as_str() {
+            "high" => Some(QueryFilter::Priority(TaskPriority::High)),
+            "low" => Some(QueryFilter::Priority(TaskPriority::Low)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.strip_prefix('"').unwrap_or(value);
+    value.strip_suffix('"').unwrap_or(value)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn term(text: &str) -> QueryNode {
+        QueryNode::Term(text.to_string())
+    }
+
+    #[test]
+    fn parse_query__should_and_plain_terms() {
+        // When
+        let query = parse_query("Garden  Plan");
+
+        // Then
+        assert_eq!(query, QueryNode::And(vec![term("garden"), term("plan")]));
+    }
+
+    #[test]
+    fn parse_query__should_parse_phrases_and_exclusions() {
+        // When
+        let query = parse_query(r#""Weekly Review" -draft -"old notes""#);
+
+        // Then
+        assert_eq!(
+            query,
+            QueryNode::And(vec![
+                QueryNode::Phrase("weekly review".to_string()),
+                QueryNode::Not(Box::new(term("draft"))),
+                QueryNode::Not(Box::new(QueryNode::Phrase("old notes".to_string()))),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_query__should_group_or_before_and() {
+        // When
+        let query = parse_query("cats OR dogs food");
+
+        // Then
+        assert_eq!(
+            query,
+            QueryNode::And(vec![
+                QueryNode::Or(vec![term("cats"), term("dogs")]),
+                term("food"),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_query__should_treat_lowercase_or_and_dangling_or_as_terms_or_noise() {
+        // When
+        let lowercase = parse_query("cats or dogs");
+        let dangling = parse_query("OR cats OR");
+
+        // Then
+        assert_eq!(
+            lowercase,
+            QueryNode::And(vec![term("cats"), term("or"), term("dogs")])
+        );
+        assert_eq!(dangling, term("cats"));
+    }
+
+    #[test]
+    fn parse_query__should_parse_filters() {
+        // When
+        let query = parse_query(
+            r#"path:notes/ ext:.MD tag:#Garden is:task is:open-task mentions:@alice path:"my docs/" date:2026-10"#,
+        );
+
+        // Then
+        assert_eq!(
+            query,
+            QueryNode::And(vec![
+                QueryNode::Filter(QueryFilter::Path("notes/".to_string())),
+                QueryNode::Filter(QueryFilter::Ext("md".to_string())),
+                QueryNode::Filter(QueryFilter::Tag("garden".to_string())),
+                QueryNode::Filter(QueryFilter::IsTask),
+                QueryNode::Filter(QueryFilter::IsOpenTask),
+                QueryNode::Filter(QueryFilter::Mentions("alice".to_string())),
+                QueryNode::Filter(QueryFilter::Path("my docs/".to_string())),
+                QueryNode::Filter(QueryFilter::Date("2026-10".to_string())),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_query__should_parse_task_metadata_filters() {
+        // When
+        let query = parse_query("due:2026-11 scheduled:2026-10-30 priority:!HIGH priority:urgent");
+
+        // Then
+        assert_eq!(
+            query,
+            QueryNode::And(vec![
+                QueryNode::Filter(QueryFilter::Due("2026-11".to_string())),
+                QueryNode::Filter(QueryFilter::Scheduled("2026-10-30".to_string())),
+                QueryNode::Filter(QueryFilter::Priority(TaskPriority::High)),
+                term("priority:urgent"),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_query__should_keep_unknown_prefixes_as_terms() {
+        // When
+        let query = parse_query("is:weird http://example.com -tag:done");
+
+        // Then
+        assert_eq!(
+            query,
+            QueryNode::And(vec![
+                term("is:weird"),
+                term("http://example.com"),
+                QueryNode::Not(Box::new(QueryNode::Filter(QueryFilter::Tag(
+                    "done".to_string()
+                )))),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_query__should_keep_dashes_that_do_not_prefix_words() {
+        // When
+        let query = parse_query("-> - -x");
+
+        // Then
+        assert_eq!(
+            query,
+            QueryNode::And(vec![
+                term("->"),
+                term("-"),
+                QueryNode::Not(Box::new(term("x"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_query__should_return_empty_and_for_blank_input() {
+        // When
+        let query = parse_query("   ");
+
+        // Then
+        assert_eq!(query, QueryNode::And(Vec::new()));
+    }
+}
@@ -0,0 +1,301 @@
+use super::front_matter::blank_front_matter;
+use super::is_fence_line;
+use super::paths::FileKind;
+use super::search::SearchIndex;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// An inline tag and the line it appears on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TagLine {
+    pub(crate) tag: String,
+    /// Zero-based line index in the source markdown.
+    pub(crate) line: usize,
+    /// The trimmed source line containing the tag.
+    pub(crate) context: String,
+}
+
+/// A tag and the number of documents using it.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct TagCount {
+    pub(crate) tag: String,
+    pub(crate) documents: usize,
+}
+
+/// A document using a tag, with the lines it appears on.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct TaggedDocument {
+    pub(crate) doc_id: String,
+    pub(crate) title: String,
+    /// Whether the front matter lists the tag.
+    pub(crate) in_front_matter: bool,
+    pub(crate) lines: Vec<TagLine>,
+}
+
+/// Collect inline `#tag` and `#nested/tag` references outside fenced code
+/// blocks, with their lines, once per tag and line.
+///
+/// Tags are lowercased. A `#` only starts a tag at the beginning of a line or
+/// after a non-tag character, and the tag must contain at least one letter, so
+/// headings (`# Title`), issue numbers (`#42`) and URL fragments are not
+/// picked up. Front matter is skipped; its tags are in `FrontMatter::tags`.
+pub(crate) fn collect_tag_lines(contents: &str) -> Vec<TagLine> {
+    let contents = blank_front_matter(contents);
+    let mut tag_lines: Vec<TagLine> = Vec::new();
+    let mut in_fence = false;
+
+    for (line_idx, line) in contents.lines().enumerate() {
+        if is_fence_line(line) {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let line_start = tag_lines.len();
+        for (_, tag) in tag_spans(line, None) {
+            if !tag_lines[line_start..].iter().any(|seen| seen.tag == tag) {
+                tag_lines.push(TagLine {
+                    tag,
+                    line: line_idx,
+                    context: line.trim().to_string(),
+                });
+            }
+        }
+    }
+
+    tag_lines
+}
+
+/// Finds the tags in `text` as byte ranges covering the `#` and the tag name,
+/// with the lowercased tag. `previous` is the byte right before `text` when
+/// it continues earlier text.
+pub(crate) fn tag_spans(text: &str, previous: Option<u8>) -> Vec<(Range<usize>, String)> {
+    let bytes = text.as_bytes();
+    let mut spans = Vec::new();
+    let mut idx = 0usize;
+
+    while idx < bytes.len() {
+        let boundary = if idx == 0 {
+            previous.is_none_or(is_tag_boundary)
+        } else {
+            is_tag_boundary(bytes[idx - 1])
+        };
+        if bytes[idx] == b'#' && boundary {
+            let start = idx + 1;
+            let mut end = start;
+            while end < bytes.len() && is_tag_char(bytes[end]) {
+                end += 1;
+            }
+            let tag = text[start..end].trim_end_matches('/');
+            if tag.bytes().any(|byte| byte.is_ascii_alphabetic()) && !tag.starts_with('/') {
+                spans.push((idx..start + tag.len(), tag.to_ascii_lowercase()));
+            }
+            idx = end.max(start);
+            continue;
+        }
+        idx += 1;
+    }
+
+    spans
+}
+
+/// Counts the documents using each tag, sorted by tag.
+pub(crate) fn tag_counts(index: &SearchIndex) -> Vec<TagCount> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for (_, document) in index.documents() {
+        for tag in &document.tags {
+            *counts.entry(tag).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(tag, documents)| TagCount {
+            tag: tag.to_string(),
+            documents,
+        })
+        .collect()
+}
+
+/// Lists the documents using `tag` or a tag nested below it, in path order.
+pub(crate) fn tagged_documents(index: &SearchIndex, tag: &str) -> Vec<TaggedDocument> {
+    let tag = normalize_tag(tag);
+    let matches = |candidate: &str| tag_matches(candidate, &tag);
+
+    let mut tagged = Vec::new();
+    for (doc_id, document) in index.documents() {
+        if document.kind != FileKind::Document || !document.tags.iter().any(|t| matches(t)) {
+            continue;
+        }
+        tagged.push(TaggedDocument {
+            doc_id: doc_id.clone(),
+            title: document.title.clone(),
+            in_front_matter: document.front_matter.tags.iter().any(|t| matches(t)),
+            lines: document
+                .tag_lines
+                .iter()
+                .filter(|tag_line| matches(&tag_line.tag))
+                .cloned()
+                .collect(),
+        });
+    }
+    tagged
+}
+
+/// Lowercases a tag as typed in a URL or filter, without `#` or surrounding `/`.
+pub(crate) fn normalize_tag(tag: &str) -> String {
+    tag.trim()
+        .trim_start_matches('#')
+        .trim_matches('/')
+        .to_lowercase()
+}
+
+/// Whether `candidate` is the normalized `tag` or nested below it.
+pub(super) fn tag_matches(candidate: &str, tag: &str) -> bool {
+    candidate == tag
+        || candidate
+            .strip_prefix(tag)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn is_tag_boundary(previous: u8) -> bool {
+    previous.is_ascii_whitespace() || matches!(previous, b'(' | b'[' | b',')
+}
+
+fn is_tag_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-' | b'/')
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_temp_root;
+
+    fn collect_inline_tags(contents: &str) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag_line in collect_tag_lines(contents) {
+            if !tags.contains(&tag_line.tag) {
+                tags.push(tag_line.tag);
+            }
+        }
+        tags
+    }
+
+    #[test]
+    fn collect_inline_tags__should_find_simple_and_nested_tags() {
+        // Given
+        let contents = "Planning #Garden and #projects/house-build, again #garden.\n";
+
+        // When
+        let tags = collect_inline_tags(contents);
+
+        // Then
+        assert_eq!(tags, vec!["garden", "projects/house-build"]);
+    }
+
+    #[test]
+    fn collect_inline_tags__should_skip_headings_numbers_fragments_and_fences() {
+        // Given
+        let contents = "\
+# Heading
+Issue #42 and [link](doc.md#section)
+```
+#fenced
+```
+";
+
+        // When
+        let tags = collect_inline_tags(contents);
+
+        // Then
+        assert!(tags.is_empty());
+    }
+
+    #[test]
+    fn collect_tag_lines__should_report_lines_once_per_tag_and_skip_front_matter() {
+        // Given
+        let contents = "---\ntitle: \"#not-inline\"\n---\n#todo buy seeds #todo\n\n  - call #Vet\n";
+
+        // When
+        let tag_lines = collect_tag_lines(contents);
+
+        // Then
+        assert_eq!(
+            tag_lines,
+            vec![
+                TagLine {
+                    tag: "todo".to_string(),
+                    line: 3,
+                    context: "#todo buy seeds #todo".to_string(),
+                },
+                TagLine {
+                    tag: "vet".to_string(),
+                    line: 5,
+                    context: "- call #Vet".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn tag_spans__should_cover_hash_and_name_and_respect_the_boundary() {
+        assert_eq!(
+            tag_spans("#a/b/ and x#no", None),
+            vec![(0..4, "a/b".to_string())]
+        );
+        assert!(tag_spans("#cont", Some(b'x')).is_empty());
+    }
+
+    #[test]
+    fn tagged_documents__should_include_nested_tags_and_front_matter() {
+        // Given
+        let root = create_temp_root("tags-tagged");
+        std::fs::write(root.join("a.md"), "# Alpha\nSee #projects/house\n").expect("write");
+        std::fs::write(root.join("b.md"), "---\ntags: [projects]\n---\nBody\n").expect("write");
+        std::fs::write(root.join("c.md"), "#projectsx and #other\n").expect("write");
+        let index = SearchIndex::build(&root).expect("build index");
+
+        // When
+        let counts = tag_counts(&index);
+        let tagged = tagged_documents(&index, "Projects");
+
+        // Then
+        assert_eq!(
+            counts
+                .iter()
+                .map(|count| (count.tag.as_str(), count.documents))
+                .collect::<Vec<_>>(),
+            vec![
+                ("other", 1),
+                ("projects", 1),
+                ("projects/house", 1),
+                ("projectsx", 1)
+            ]
+        );
+        assert_eq!(
+            tagged,
+            vec![
+                TaggedDocument {
+                    doc_id: "a.md".to_string(),
+                    title: "Alpha".to_string(),
+                    in_front_matter: false,
+                    lines: vec![TagLine {
+                        tag: "projects/house".to_string(),
+                        line: 1,
+                        context: "See #projects/house".to_string(),
+                    }],
+                },
+                TaggedDocument {
+                    doc_id: "b.md".to_string(),
+                    title: "b".to_string(),
+                    in_front_matter: true,
+                    lines: Vec::new(),
+                },
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+}
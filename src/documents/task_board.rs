@@ -0,0 +1,370 @@
+use super::paths::FileKind;
+use super::search::SearchIndex;
+use super::tags::{normalize_tag, tag_matches, tag_spans};
+use super::tasks::{TaskItem, collect_mentions};
+use std::collections::BTreeSet;
+
+/// Which tasks to show on the task dashboard.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct TaskFilter {
+    /// Only tasks in documents below this folder.
+    pub(crate) folder: Option<String>,
+    /// Only tasks mentioning this user.
+    pub(crate) assignee: Option<String>,
+    pub(crate) status: TaskStatus,
+    /// Only tasks tagged with this tag or one nested below it, on the task
+    /// line or on the document.
+    pub(crate) tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum TaskStatus {
+    #[default]
+    Open,
+    Done,
+    All,
+}
+
+impl TaskStatus {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "open" => Some(TaskStatus::Open),
+            "done" => Some(TaskStatus::Done),
+            "all" => Some(TaskStatus::All),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            TaskStatus::Open => "open",
+            TaskStatus::Done => "done",
+            TaskStatus::All => "all",
+        }
+    }
+
+    fn includes(self, checked: bool) -> bool {
+        match self {
+            TaskStatus::Open => !checked,
+            TaskStatus::Done => checked,
+            TaskStatus::All => true,
+        }
+    }
+}
+
+/// A task that passes the filter, with the document it is in.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct BoardTask {
+    pub(crate) doc_id: String,
+    pub(crate) title: String,
+    pub(crate) item: TaskItem,
+    /// Mentioned users, lowercased.
+    pub(crate) assignees: Vec<String>,
+    /// Tags on the task line.
+    pub(crate) tags: Vec<String>,
+}
+
+/// Collects the task list items of every markdown document, soonest due
+/// first. Tasks without a due date follow in path and line order.
+///
+/// Task indexes are the ones `toggle_task_item` expects for the document.
+pub(crate) fn collect_board_tasks(index: &SearchIndex, filter: &TaskFilter) -> Vec<BoardTask> {
+    let folder = filter
+        .folder
+        .as_deref()
+        .map(|folder| folder.trim().trim_matches('/'))
+        .filter(|folder| !folder.is_empty());
+    let assignee = filter
+        .assignee
+        .as_deref()
+        .map(|assignee| assignee.trim().trim_start_matches('@').to_lowercase())
+        .filter(|assignee| !assignee.is_empty());
+    let tag = filter
+        .tag
+        .as_deref()
+        .map(normalize_tag)
+        .filter(|tag| !tag.is_empty());
+
+    let mut board = Vec::new();
+    for (doc_id, document) in index.documents() {
+        if document.kind != FileKind::Document || document.tasks.is_empty() {
+            continue;
+        }
+        if folder.is_some_and(|folder| {
+            !doc_id
+                .strip_prefix(folder)
+                .is_some_and(|rest| rest.starts_with('/'))
+        }) {
+            continue;
+        }
+        let document_tagged = tag.as_deref().is_none_or(|tag| {
+            document
+                .tags
+                .iter()
+                .any(|candidate| tag_matches(candidate, tag))
+        });
+
+        let tasks = document
+            .tasks
+            .iter()
+            .filter(|item| filter.status.includes(item.checked))
+            .map(|item| board_task(doc_id, &document.title, item.clone()))
+            .filter(|task| {
+                assignee
+                    .as_ref()
+                    .is_none_or(|assignee| task.assignees.contains(assignee))
+            })
+            .filter(|task| {
+                document_tagged
+                    || tag.as_deref().is_some_and(|tag| {
+                        task.tags
+                            .iter()
+                            .any(|candidate| tag_matches(candidate, tag))
+                    })
+            });
+        board.extend(tasks);
+    }
+    board.sort_by_key(|task| (task.item.metadata.due.is_none(), task.item.metadata.due));
+    board
+}
+
+/// Values offered as suggestions in the dashboard filters.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct TaskFilterOptions {
+    pub(crate) folders: Vec<String>,
+    pub(crate) assignees: Vec<String>,
+    pub(crate) tags: Vec<String>,
+}
+
+/// Folders, assignees and tags used by any task, each sorted.
+pub(crate) fn task_filter_options(index: &SearchIndex) -> TaskFilterOptions {
+    let all = TaskFilter {
+        status: TaskStatus::All,
+        ..TaskFilter::default()
+    };
+    let mut folders = BTreeSet::new();
+    let mut assignees = BTreeSet::new();
+    let mut tags = BTreeSet::new();
+    for task in collect_board_tasks(index, &all) {
+        let mut parts: Vec<&str> = task.doc_id.split('/').collect();
+        parts.pop();
+        for depth in 1..=parts.len() {
+            folders.insert(parts[..depth].join("/"));
+        }
+        if let Some(indexed) = index.document(&task.doc_id) {
+            tags.extend(indexed.tags.iter().cloned());
+        }
+        assignees.extend(task.assignees);
+        tags.extend(task.tags);
+    }
+    TaskFilterOptions {
+        folders: folders.into_iter().collect(),
+        assignees: assignees.into_iter().collect(),
+        tags: tags.into_iter().collect(),
+    }
+}
+
+fn board_task(doc_id: &str, title: &str, item: TaskItem) -> BoardTask {
+    let mut assignees: Vec<String> = Vec::new();
+    for (user, _) in collect_mentions(&item.text) {
+        let user = user.to_lowercase();
+        if !assignees.contains(&user) {
+            assignees.push(user);
+        }
+    }
+    let mut tags: Vec<String> = Vec::new();
+    for (_, tag) in tag_spans(&item.text, None) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    BoardTask {
+        doc_id: doc_id.to_string(),
+        title: title.to_string(),
+        item,
+        assignees,
+        tags,
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::test_support::build_index;
+
+    fn summary(board: &[BoardTask]) -> Vec<(&str, usize, &str)> {
+        board
+            .iter()
+            .map(|task| {
+                (
+                    task.doc_id.as_str(),
+                    task.item.index,
+                    task.item.text.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn collect_board_tasks__should_default_to_open_tasks_across_documents() {
+        // Given
+        let (root, index) = build_index(
+            "task-board-open",
+            &[
+                ("home.md", "- [x] done\n- [ ] sweep @Ana\n"),
+                (
+                    "work/plan.md",
+                    "```\n- [ ] fenced\n```\n- [ ] draft #report\n",
+                ),
+                ("notes.txt", "- [ ] not markdown\n"),
+            ],
+        );
+
+        // When
+        let board = collect_board_tasks(&index, &TaskFilter::default());
+
+        // Then
+        assert_eq!(
+            summary(&board),
+            vec![
+                ("home.md", 1, "sweep @Ana"),
+                ("work/plan.md", 0, "draft #report")
+            ]
+        );
+        assert_eq!(board[0].assignees, vec!["ana"]);
+        assert_eq!(board[1].tags, vec!["report"]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn collect_board_tasks__should_sort_by_due_date_then_path_and_line() {
+        // Given
+        let (root, index) = build_index(
+            "task-board-due",
+            &[
+                ("a.md", "- [ ] undated\n- [ ] later due:2026-12-01\n"),
+                ("b.md", "- [ ] sooner 📅 2026-11-01\n- [ ] also undated\n"),
+            ],
+        );
+
+        // When
+        let board = collect_board_tasks(&index, &TaskFilter::default());
+
+        // Then
+        assert_eq!(
+            summary(&board),
+            vec![
+                ("b.md", 0, "sooner 📅 2026-11-01"),
+                ("a.md", 1, "later due:2026-12-01"),
+                ("a.md", 0, "undated"),
+                ("b.md", 1, "also undated"),
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn task_filter_options__should_list_folders_assignees_and_tags() {
+        // Given
+        let (root, index) = build_index(
+            "task-board-options",
+            &[
+                ("a/b/plan.md", "#plan\n- [x] ship @ben #q4\n"),
+                ("a/other.md", "- [ ] call @ana\n"),
+                ("c/untasked.md", "#idle @zed\n"),
+            ],
+        );
+
+        // When
+        let options = task_filter_options(&index);
+
+        // Then
+        assert_eq!(
+            options,
+            TaskFilterOptions {
+                folders: vec!["a".to_string(), "a/b".to_string()],
+                assignees: vec!["ana".to_string(), "ben".to_string()],
+                tags: vec!["plan".to_string(), "q4".to_string()],
+            }
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn collect_board_tasks__should_filter_by_folder_assignee_status_and_tag() {
+        // Given
+        let (root, index) = build_index(
+            "task-board-filters",
+            &[
+                ("home.md", "- [ ] sweep @ana #chores\n- [x] cook @ana\n"),
+                (
+                    "work/plan.md",
+                    "---\ntags: [projects/q4]\n---\n- [ ] draft @ben\n- [x] review @ana\n",
+                ),
+                ("workshop.md", "- [ ] sand @ana\n"),
+            ],
+        );
+
+        // When
+        let in_folder = collect_board_tasks(
+            &index,
+            &TaskFilter {
+                folder: Some("/work/".to_string()),
+                status: TaskStatus::All,
+                ..TaskFilter::default()
+            },
+        );
+        let done_for_ana = collect_board_tasks(
+            &index,
+            &TaskFilter {
+                assignee: Some("@Ana".to_string()),
+                status: TaskStatus::Done,
+                ..TaskFilter::default()
+            },
+        );
+        let by_task_tag = collect_board_tasks(
+            &index,
+            &TaskFilter {
+                tag: Some("#Chores".to_string()),
+                ..TaskFilter::default()
+            },
+        );
+        let by_document_tag = collect_board_tasks(
+            &index,
+            &TaskFilter {
+                tag: Some("projects".to_string()),
+                ..TaskFilter::default()
+            },
+        );
+
+        // Then
+        assert_eq!(
+            summary(&in_folder),
+            vec![
+                ("work/plan.md", 0, "draft @ben"),
+                ("work/plan.md", 1, "review @ana")
+            ]
+        );
+        assert_eq!(
+            summary(&done_for_ana),
+            vec![
+                ("home.md", 1, "cook @ana"),
+                ("work/plan.md", 1, "review @ana")
+            ]
+        );
+        assert_eq!(
+            summary(&by_task_tag),
+            vec![("home.md", 0, "sweep @ana #chores")]
+        );
+        assert_eq!(
+            summary(&by_document_tag),
+            vec![("work/plan.md", 0, "draft @ben")]
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+}
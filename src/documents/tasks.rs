@@ -1,5 +1,9 @@
+use super::front_matter::parse_front_matter;
+use super::magent::find_magent_regions;
 use super::{detect_line_ending, is_fence_line, split_line_ending};
 use std::collections::HashSet;
+use std::ops::{Range, RangeInclusive};
+use time::{Date, Month};
 
 pub(crate) fn collect_mentions(contents: &str) -> Vec<(String, String)> {
     let mut mentions = Vec::new();
@@ -26,14 +30,115 @@ pub(crate) fn collect_mentions(contents: &str) -> Vec<(String, String)> {
     mentions
 }
 
+/// A task list item, numbered the same way `toggle_task_item` counts them.
+///
+/// Tasks in the front matter and in `<magent-response>` blocks are not
+/// counted, since the document view does not render them as checkboxes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TaskItem {
+    pub(crate) index: usize,
+    /// Zero-based line index in the document.
+    pub(crate) line: usize,
+    pub(crate) checked: bool,
+    /// The task text, including any metadata tokens.
+    pub(crate) text: String,
+    pub(crate) metadata: TaskMetadata,
+}
+
+impl TaskItem {
+    /// The task text without its metadata tokens.
+    pub(crate) fn label(&self) -> String {
+        let mut label = String::with_capacity(self.text.len());
+        let mut copied = 0;
+        for token in task_metadata_tokens(&self.text) {
+            label.push_str(&self.text[copied..token.range.start]);
+            copied = token.range.end;
+        }
+        label.push_str(&self.text[copied..]);
+        label.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Inline task metadata: `due:2026-11-01` or `📅 2026-11-01`,
+/// `scheduled:2026-10-30`, and `!high` or `!low`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct TaskMetadata {
+    pub(crate) due: Option<Date>,
+    pub(crate) scheduled: Option<Date>,
+    pub(crate) priority: Option<TaskPriority>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TaskPriority {
+    High,
+    Low,
+}
+
+impl TaskPriority {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            TaskPriority::High => "high",
+            TaskPriority::Low => "low",
+        }
+    }
+}
+
+/// A metadata token in a task's text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct MetadataToken {
+    /// Byte range of the whole token, e.g. `📅 2026-11-01`.
+    pub(super) range: Range<usize>,
+    pub(super) kind: MetadataKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MetadataKind {
+    Due(Date),
+    Scheduled(Date),
+    Priority(TaskPriority),
+}
+
+pub(crate) fn collect_tasks(contents: &str) -> Vec<TaskItem> {
+    let mut tasks = Vec::new();
+    let mut in_fence = false;
+    let hidden = hidden_line_ranges(contents);
+
+    for (line_idx, line) in contents.lines().enumerate() {
+        if is_hidden_line(&hidden, line_idx) {
+            continue;
+        }
+        if is_fence_line(line) {
+            in_fence = !in_fence;
+            continue;
+        }
+
+        if !in_fence && let Some(parts) = parse_task_line(line) {
+            tasks.push(TaskItem {
+                index: tasks.len(),
+                line: line_idx,
+                checked: parts.checked,
+                text: parts.suffix.trim().to_string(),
+                metadata: parts.metadata,
+            });
+        }
+    }
+
+    tasks
+}
+
 pub(crate) fn toggle_task_item(contents: &str, task_index: usize, checked: bool) -> Option<String> {
     let mut output = String::with_capacity(contents.len());
     let mut in_fence = false;
     let mut current = 0usize;
     let mut updated = false;
+    let hidden = hidden_line_ranges(contents);
 
-    for segment in contents.split_inclusive('\n') {
+    for (line_idx, segment) in contents.split_inclusive('\n').enumerate() {
         let (line, ending) = split_line_ending(segment);
+        if is_hidden_line(&hidden, line_idx) {
+            output.push_str(segment);
+            continue;
+        }
         if is_fence_line(line) {
             in_fence = !in_fence;
             output.push_str(line);
@@ -149,10 +254,28 @@ pub(crate) fn add_task_item_in_list(contents: &str, list_index: usize, text: &st
     output
 }
 
+/// Line ranges the document view leaves out: the front matter and
+/// `<magent-response>` blocks.
+fn hidden_line_ranges(contents: &str) -> Vec<RangeInclusive<usize>> {
+    let mut ranges: Vec<RangeInclusive<usize>> = find_magent_regions(contents)
+        .into_iter()
+        .map(|region| region.start_line..=region.end_line)
+        .collect();
+    if let Some(front_matter) = parse_front_matter(contents) {
+        ranges.push(0..=front_matter.line_count.saturating_sub(1));
+    }
+    ranges
+}
+
+fn is_hidden_line(hidden: &[RangeInclusive<usize>], line: usize) -> bool {
+    hidden.iter().any(|range| range.contains(&line))
+}
+
 pub(super) struct TaskLineParts<'a> {
     pub(super) prefix: &'a str,
     pub(super) suffix: &'a str,
     pub(super) checked: bool,
+    pub(super) metadata: TaskMetadata,
 }
 
 pub(super) fn parse_task_line(line: &str) -> Option<TaskLineParts<'_>> {
@@ -196,13 +319,119 @@ pub(super) fn parse_task_line(line: &str) -> Option<TaskLineParts<'_>> {
         return None;
     }
 
+    let suffix = &line[after..];
     Some(TaskLineParts {
         prefix: &line[..j],
-        suffix: &line[after..],
+        suffix,
         checked: status != b' ',
+        metadata: parse_task_metadata(suffix),
     })
 }
 
+/// Reads the metadata tokens of a task; the first of each kind wins.
+fn parse_task_metadata(text: &str) -> TaskMetadata {
+    let mut metadata = TaskMetadata::default();
+    for token in task_metadata_tokens(text) {
+        match token.kind {
+            MetadataKind::Due(date) => {
+                metadata.due.get_or_insert(date);
+            }
+            MetadataKind::Scheduled(date) => {
+                metadata.scheduled.get_or_insert(date);
+            }
+            MetadataKind::Priority(priority) => {
+                metadata.priority.get_or_insert(priority);
+            }
+        }
+    }
+    metadata
+}
+
+/// Finds the whitespace separated metadata tokens in a task's text, skipping
+/// inline code spans.
+pub(super) fn task_metadata_tokens(text: &str) -> Vec<MetadataToken> {
+    let mut tokens = Vec::new();
+    let mut words = words_outside_code(text).into_iter().peekable();
+    while let Some((start, word)) = words.next() {
+        let token = if let Some(date) = word.strip_prefix("due:") {
+            parse_date(date).map(|date| (start + word.len(), MetadataKind::Due(date)))
+        } else if let Some(date) = word.strip_prefix("scheduled:") {
+            parse_date(date).map(|date| (start + word.len(), MetadataKind::Scheduled(date)))
+        } else if word == "📅" {
+            words
+                .next_if(|(_, next)| parse_date(next).is_some())
+                .and_then(|(next_start, next)| {
+                    Some((
+                        next_start + next.len(),
+                        MetadataKind::Due(parse_date(next)?),
+                    ))
+                })
+        } else if word.eq_ignore_ascii_case("!high") {
+            Some((
+                start + word.len(),
+                MetadataKind::Priority(TaskPriority::High),
+            ))
+        } else if word.eq_ignore_ascii_case("!low") {
+            Some((
+                start + word.len(),
+                MetadataKind::Priority(TaskPriority::Low),
+            ))
+        } else {
+            None
+        };
+        if let Some((end, kind)) = token {
+            tokens.push(MetadataToken {
+                range: start..end,
+                kind,
+            });
+        }
+    }
+    tokens
+}
+
+/// Whitespace separated words with their byte offsets, leaving out words
+/// touching a backtick code span.
+fn words_outside_code(text: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut in_code = false;
+    let mut word_start: Option<usize> = None;
+    let mut word_has_code = false;
+    for (idx, ch) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        if ch.is_whitespace() {
+            if let Some(start) = word_start.take()
+                && !in_code
+                && !word_has_code
+            {
+                words.push((start, &text[start..idx]));
+            }
+            word_has_code = in_code;
+            continue;
+        }
+        if word_start.is_none() {
+            word_start = Some(idx);
+        }
+        if ch == '`' {
+            in_code = !in_code;
+            word_has_code = true;
+        }
+    }
+    words
+}
+
+/// Parses a `YYYY-MM-DD` date.
+fn parse_date(value: &str) -> Option<Date> {
+    let mut parts = value.split('-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return None;
+    }
+    let month = Month::try_from(month.parse::<u8>().ok()?).ok()?;
+    Date::from_calendar_date(year.parse().ok()?, month, day.parse().ok()?).ok()
+}
+
 pub(super) fn is_task_list_marker(line: &str) -> bool {
     line.trim() == "+"
 }
@@ -296,6 +525,78 @@ Edge: email@example.com and @not+valid and @ok-name.
         );
     }
 
+    #[test]
+    fn collect_tasks__should_number_tasks_and_skip_fences() {
+        // Given
+        let contents = "\
+- [ ] one
+```
+- [x] fenced
+```
+* [x] two
+";
+
+        // When
+        let tasks = collect_tasks(contents);
+
+        // Then
+        assert_eq!(
+            tasks,
+            vec![
+                TaskItem {
+                    index: 0,
+                    line: 0,
+                    checked: false,
+                    text: "one".to_string(),
+                    metadata: TaskMetadata::default(),
+                },
+                TaskItem {
+                    index: 1,
+                    line: 4,
+                    checked: true,
+                    text: "two".to_string(),
+                    metadata: TaskMetadata::default(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn collect_tasks__should_parse_dates_and_priorities() {
+        // Given
+        let contents = "\
+- [ ] Ship report due:2026-11-01 !HIGH scheduled:2026-10-30
+- [ ] Call back 📅 2026-10-20 !low
+- [ ] Not metadata: due:2026-13-01 `due:2026-11-02` !highest due:soon
+";
+
+        // When
+        let tasks = collect_tasks(contents);
+
+        // Then
+        let date = |year, month, day| Date::from_calendar_date(year, month, day).ok();
+        assert_eq!(
+            tasks[0].metadata,
+            TaskMetadata {
+                due: date(2026, Month::November, 1),
+                scheduled: date(2026, Month::October, 30),
+                priority: Some(TaskPriority::High),
+            }
+        );
+        assert_eq!(tasks[0].label(), "Ship report");
+        assert_eq!(
+            tasks[1].metadata,
+            TaskMetadata {
+                due: date(2026, Month::October, 20),
+                scheduled: None,
+                priority: Some(TaskPriority::Low),
+            }
+        );
+        assert_eq!(tasks[1].label(), "Call back");
+        assert_eq!(tasks[2].metadata, TaskMetadata::default());
+        assert_eq!(tasks[2].label(), tasks[2].text);
+    }
+
     #[test]
     fn toggle_task_item__should_update_target() {
         // Given
@@ -331,6 +632,33 @@ Edge: email@example.com and @not+valid and @ok-name.
         assert!(updated.contains("- [x] yes"));
     }
 
+    #[test]
+    fn toggle_task_item__should_number_tasks_like_the_rendered_view() {
+        // Given
+        let contents = "\
+---
+title: Chores
+---
+<magent-response>
+- [ ] agent suggestion
+</magent-response>
+- [ ] first
+- [ ] second
+";
+
+        // When
+        let updated = toggle_task_item(contents, 1, true).expect("updated");
+        let tasks = collect_tasks(contents);
+
+        // Then
+        assert!(updated.contains("- [ ] agent suggestion\n"));
+        assert!(updated.contains("- [ ] first\n- [x] second\n"));
+        assert_eq!(
+            tasks.iter().map(|task| task.line).collect::<Vec<_>>(),
+            vec![6, 7]
+        );
+    }
+
     #[test]
     fn toggle_task_item__should_return_none_for_missing_index() {
         // Given
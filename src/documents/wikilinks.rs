@@ -0,0 +1,226 @@
+use super::rendering::heading_slug;
+use std::collections::HashMap;
+
+/// Resolves Obsidian-style `[[Note]]` targets against the documents under the root.
+///
+/// A target matches a document by file stem first and by its first heading
+/// second, both case-insensitively. Targets containing `/` match a document
+/// path (without `.md`) relative to the root.
+#[derive(Debug, Default)]
+pub(crate) struct WikiLinkResolver {
+    by_path: HashMap<String, String>,
+    by_stem: HashMap<String, Vec<String>>,
+    by_heading: HashMap<String, Vec<String>>,
+}
+
+/// Where a wiki link points once resolved.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum WikiLinkDestination {
+    /// URL of the existing document, including any heading fragment.
+    Resolved(String),
+    /// Suggested path for creating the missing note.
+    Missing(String),
+}
+
+impl WikiLinkResolver {
+    /// Builds a resolver from `(doc_id, first heading)` pairs.
+    pub(crate) fn new<'a>(documents: impl IntoIterator<Item = (&'a str, Option<&'a str>)>) -> Self {
+        let mut resolver = Self::default();
+        for (doc_id, first_heading) in documents {
+            let Some(without_ext) = strip_md_extension(doc_id) else {
+                continue;
+            };
+            let stem = without_ext.rsplit('/').next().unwrap_or(without_ext);
+            resolver
+                .by_path
+                .insert(without_ext.to_lowercase(), doc_id.to_string());
+            resolver
+                .by_stem
+                .entry(stem.to_lowercase())
+                .or_default()
+                .push(doc_id.to_string());
+            if let Some(heading) = first_heading.map(str::trim).filter(|h| !h.is_empty()) {
+                resolver
+                    .by_heading
+                    .entry(heading.to_lowercase())
+                    .or_default()
+                    .push(doc_id.to_string());
+            }
+        }
+        // Prefer shallower documents when a name is ambiguous, then sort by path.
+        for candidates in resolver
+            .by_stem
+            .values_mut()
+            .chain(resolver.by_heading.values_mut())
+        {
+            candidates.sort_by(|a, b| {
+                a.matches('/')
+                    .count()
+                    .cmp(&b.matches('/').count())
+                    .then_with(|| a.cmp(b))
+            });
+        }
+        resolver
+    }
+
+    /// Finds the document `note` refers to when linked from `from_doc_id`.
+    ///
+    /// Among several documents with the same name, one in the linking
+    /// document's folder wins.
+    pub(crate) fn resolve(&self, from_doc_id: &str, note: &str) -> Option<&str> {
+        let note = note.trim();
+        let note = strip_md_extension(note).unwrap_or(note).to_lowercase();
+        if note.is_empty() {
+            return None;
+        }
+        if note.contains('/') {
+            return self
+                .by_path
+                .get(note.trim_start_matches('/'))
+                .map(String::as_str);
+        }
+        let from_dir = parent_dir(from_doc_id);
+        self.by_stem
+            .get(&note)
+            .or_else(|| self.by_heading.get(&note))
+            .and_then(|candidates| {
+                candidates
+                    .iter()
+                    .find(|doc_id| parent_dir(doc_id) == from_dir)
+                    .or_else(|| candidates.first())
+            })
+            .map(String::as_str)
+    }
+
+    /// Resolves a wiki link target such as `Note`, `Note#Heading` or `#Heading`.
+    pub(crate) fn destination(&self, from_doc_id: &str, target: &str) -> WikiLinkDestination {
+        let (note, heading) = match target.split_once('#') {
+            Some((note, heading)) => (note.trim(), Some(heading.trim())),
+            None => (target.trim(), None),
+        };
+        let fragment = heading
+            .filter(|heading| !heading.is_empty())
+            .map(|heading| format!("#{}", heading_slug(heading)))
+            .unwrap_or_default();
+
+        if note.is_empty() {
+            return WikiLinkDestination::Resolved(format!("/d/{from_doc_id}{fragment}"));
+        }
+        if let Some(doc_id) = self.resolve(from_doc_id, note) {
+            return WikiLinkDestination::Resolved(format!("/d/{doc_id}{fragment}"));
+        }
+
+        let file_name = format!("{}.md", strip_md_extension(note).unwrap_or(note));
+        let dir = parent_dir(from_doc_id);
+        let path = if note.contains('/') || dir.is_empty() {
+            file_name.trim_start_matches('/').to_string()
+        } else {
+            format!("{dir}/{file_name}")
+        };
+        WikiLinkDestination::Missing(path)
+    }
+}
+
+fn strip_md_extension(name: &str) -> Option<&str> {
+    let split = name.len().checked_sub(3)?;
+    let (stem, ext) = (name.get(..split)?, name.get(split..)?);
+    ext.eq_ignore_ascii_case(".md").then_some(stem)
+}
+
+fn parent_dir(doc_id: &str) -> &str {
+    doc_id.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn resolver() -> WikiLinkResolver {
+        WikiLinkResolver::new([
+            ("Inbox.md", Some("Inbox")),
+            ("projects/garden.md", Some("Garden Plan")),
+            ("projects/Meeting Notes.md", None),
+            ("archive/Meeting Notes.md", None),
+            ("archive/old/todo.md", Some("Todo")),
+            ("todo.md", None),
+        ])
+    }
+
+    #[test]
+    fn resolve__should_prefer_file_stem_over_heading() {
+        // Given
+        let resolver = WikiLinkResolver::new([("a.md", Some("Garden")), ("garden.md", None)]);
+
+        // When
+        let resolved = resolver.resolve("x.md", "Garden");
+
+        // Then
+        assert_eq!(resolved, Some("garden.md"));
+    }
+
+    #[test]
+    fn resolve__should_fall_back_to_first_heading_case_insensitively() {
+        // Given
+        let resolver = resolver();
+
+        // When
+        let resolved = resolver.resolve("Inbox.md", "garden plan");
+
+        // Then
+        assert_eq!(resolved, Some("projects/garden.md"));
+    }
+
+    #[test]
+    fn resolve__should_prefer_same_folder_then_shallowest_match() {
+        // Given
+        let resolver = resolver();
+
+        // When
+        let same_folder = resolver.resolve("archive/index.md", "Meeting Notes");
+        let shallowest = resolver.resolve("projects/x.md", "todo");
+
+        // Then
+        assert_eq!(same_folder, Some("archive/Meeting Notes.md"));
+        assert_eq!(shallowest, Some("todo.md"));
+    }
+
+    #[test]
+    fn resolve__should_match_paths_and_md_extensions() {
+        // Given
+        let resolver = resolver();
+
+        // When
+        let by_path = resolver.resolve("Inbox.md", "archive/old/todo");
+        let with_ext = resolver.resolve("Inbox.md", "inbox.md");
+
+        // Then
+        assert_eq!(by_path, Some("archive/old/todo.md"));
+        assert_eq!(with_ext, Some("Inbox.md"));
+    }
+
+    #[test]
+    fn destination__should_add_heading_fragments_and_suggest_missing_paths() {
+        // Given
+        let resolver = resolver();
+
+        // When
+        let heading = resolver.destination("Inbox.md", "Garden Plan#Next Steps");
+        let same_doc = resolver.destination("projects/garden.md", "#Soil");
+        let missing = resolver.destination("projects/garden.md", "Seed List");
+
+        // Then
+        assert_eq!(
+            heading,
+            WikiLinkDestination::Resolved("/d/projects/garden.md#next-steps".to_string())
+        );
+        assert_eq!(
+            same_doc,
+            WikiLinkDestination::Resolved("/d/projects/garden.md#soil".to_string())
+        );
+        assert_eq!(
+            missing,
+            WikiLinkDestination::Missing("projects/Seed List.md".to_string())
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Word-level diff used to preview magent edits.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiffKind {
+    Same,
+    Removed,
+    Added,
+}
+
+/// A run of text that is unchanged, only in the old text, or only in the new.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiffSpan {
+    pub(crate) kind: DiffKind,
+    pub(crate) text: String,
+}
+
+/// Above this many comparison cells the changed middle is shown as one
+/// removal and one addition instead of being diffed word by word.
+const MAX_TABLE_CELLS: usize = 1 << 20;
+
+/// Diffs `old` against `new` by words, whitespace runs and punctuation.
+///
+/// Concatenating the `Same` and `Removed` spans gives `old`, and the `Same`
+/// and `Added` spans give `new`. Within a change, removals come before
+/// additions, and a lone whitespace run between two changes is folded into
+/// them so the result reads as phrases rather than single words.
+pub(crate) fn word_diff(old: &str, new: &str) -> Vec<DiffSpan> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+
+    let prefix = old_tokens
+        .iter()
+        .zip(&new_tokens)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_tokens[prefix..]
+        .iter()
+        .rev()
+        .zip(new_tokens[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old_tokens[prefix..old_tokens.len() - suffix];
+    let new_middle = &new_tokens[prefix..new_tokens.len() - suffix];
+
+    let mut ops: Vec<(DiffKind, &str)> = Vec::new();
+    ops.extend(old_tokens[..prefix].iter().map(|t| (DiffKind::Same, *t)));
+    diff_tokens(old_middle, new_middle, &mut ops);
+    ops.extend(
+        old_tokens[old_tokens.len() - suffix..]
+            .iter()
+            .map(|t| (DiffKind::Same, *t)),
+    );
+    group(&ops)
+}
+
+/// Splits text into runs of word characters, runs of whitespace and single
+/// other characters.
+fn tokenize(text: &str) -> Vec<&str> {
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        let kind = class(c);
+        let joins_next = kind != 2 && chars.peek().is_some_and(|(_, next)| class(*next) == kind);
+        if !joins_next {
+            let end = idx + c.len_utf8();
+            tokens.push(&text[start..end]);
+            start = end;
+        }
+    }
+    tokens
+}
+
+/// Longest common subsequence walk over two token slices.
+fn diff_tokens<'a>(old: &[&'a str], new: &[&'a str], ops: &mut Vec<(DiffKind, &'a str)>) {
+    let (n, m) = (old.len(), new.len());
+    if n == 0 || m == 0 || (n + 1) * (m + 1) > MAX_TABLE_CELLS {
+        ops.extend(old.iter().map(|t| (DiffKind::Removed, *t)));
+        ops.extend(new.iter().map(|t| (DiffKind::Added, *t)));
+        return;
+    }
+
+    // lcs[i * (m + 1) + j] is the LCS length of old[i..] and new[j..].
+    let width = m + 1;
+    let mut lcs = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            ops.push((DiffKind::Same, old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            ops.push((DiffKind::Removed, old[i]));
+            i += 1;
+        } else {
+            ops.push((DiffKind::Added, new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|t| (DiffKind::Removed, *t)));
+    ops.extend(new[j..].iter().map(|t| (DiffKind::Added, *t)));
+}
+
+enum Group {
+    Same(String),
+    Change { removed: String, added: String },
+}
+
+fn group(ops: &[(DiffKind, &str)]) -> Vec<DiffSpan> {
+    let mut groups: Vec<Group> = Vec::new();
+    for (kind, token) in ops {
+        match (kind, groups.last_mut()) {
+            (DiffKind::Same, Some(Group::Same(text))) => text.push_str(token),
+            (DiffKind::Same, _) => groups.push(Group::Same(token.to_string())),
+            (DiffKind::Removed, Some(Group::Change { removed, .. })) => removed.push_str(token),
+            (DiffKind::Added, Some(Group::Change { added, .. })) => added.push_str(token),
+            (DiffKind::Removed, _) => groups.push(Group::Change {
+                removed: token.to_string(),
+                added: String::new(),
+            }),
+            (DiffKind::Added, _) => groups.push(Group::Change {
+                removed: String::new(),
+                added: token.to_string(),
+            }),
+        }
+    }
+
+    // Fold `change, whitespace, change` into one change.
+    let mut folded: Vec<Group> = Vec::new();
+    for next in groups {
+        let foldable = matches!(
+            folded.as_slice(),
+            [.., Group::Change { .. }, Group::Same(space)] if space.trim().is_empty()
+        ) && matches!(next, Group::Change { .. });
+        if !foldable {
+            folded.push(next);
+            continue;
+        }
+        let Some(Group::Same(space)) = folded.pop() else {
+            unreachable!("checked above");
+        };
+        if let (
+            Some(Group::Change { removed, added }),
+            Group::Change {
+                removed: next_removed,
+                added: next_added,
+            },
+        ) = (folded.last_mut(), next)
+        {
+            removed.push_str(&space);
+            removed.push_str(&next_removed);
+            added.push_str(&space);
+            added.push_str(&next_added);
+        }
+    }
+
+    let mut spans = Vec::new();
+    for group in folded {
+        match group {
+            Group::Same(text) => spans.push(DiffSpan {
+                kind: DiffKind::Same,
+                text,
+            }),
+            Group::Change { removed, added } => {
+                if !removed.is_empty() {
+                    spans.push(DiffSpan {
+                        kind: DiffKind::Removed,
+                        text: removed,
+                    });
+                }
+                if !added.is_empty() {
+                    spans.push(DiffSpan {
+                        kind: DiffKind::Added,
+                        text: added,
+                    });
+                }
+            }
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn summary(spans: &[DiffSpan]) -> Vec<(DiffKind, &str)> {
+        spans
+            .iter()
+            .map(|span| (span.kind, span.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn word_diff__should_mark_changed_words_only() {
+        // When
+        let spans = word_diff(
+            "The quick brown fox jumps.",
+            "The quick red fox leaps high.",
+        );
+
+        // Then
+        assert_eq!(
+            summary(&spans),
+            vec![
+                (DiffKind::Same, "The quick "),
+                (DiffKind::Removed, "brown"),
+                (DiffKind::Added, "red"),
+                (DiffKind::Same, " fox "),
+                (DiffKind::Removed, "jumps"),
+                (DiffKind::Added, "leaps high"),
+                (DiffKind::Same, "."),
+            ]
+        );
+    }
+
+    #[test]
+    fn word_diff__should_fold_whitespace_between_changes() {
+        // When
+        let spans = word_diff("one two three", "uno dos three");
+
+        // Then
+        assert_eq!(
+            summary(&spans),
+            vec![
+                (DiffKind::Removed, "one two"),
+                (DiffKind::Added, "uno dos"),
+                (DiffKind::Same, " three"),
+            ]
+        );
+    }
+
+    #[test]
+    fn word_diff__should_rebuild_both_texts() {
+        // Given
+        let old = "Fix htps://rust-lang.org\nand ünïcode words";
+        let new = "Fix https://rust-lang.org\n\nand unicode words!";
+
+        // When
+        let spans = word_diff(old, new);
+
+        // Then
+        let side = |skip: DiffKind| -> String {
+            spans
+                .iter()
+                .filter(|span| span.kind != skip)
+                .map(|span| span.text.as_str())
+                .collect()
+        };
+        assert_eq!(side(DiffKind::Added), old);
+        assert_eq!(side(DiffKind::Removed), new);
+        assert!(spans.contains(&DiffSpan {
+            kind: DiffKind::Removed,
+            text: "htps".to_string(),
+        }));
+    }
+}
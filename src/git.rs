@@ -1,3 +1,5 @@
+use crate::documents::STATE_DIR;
+
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output, Stdio};
@@ -430,7 +432,9 @@ fn ssh_command(git_dir: &Path) -> String {
 
 fn git_add_all(root: &Path) -> Result<(), GitError> {
     let mut cmd = git_command(root)?;
-    cmd.args(["add", "-A", "--", "."]);
+    // Server state may predate its `.gitignore`, so never stage it.
+    let exclude_state = format!(":(exclude){STATE_DIR}");
+    cmd.args(["add", "-A", "--", ".", &exclude_state]);
     run_command_checked("git add", cmd, None)?;
     Ok(())
 }
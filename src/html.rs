@@ -16,6 +16,45 @@ pub(crate) fn escape(s: &str) -> String {
     escaped
 }
 
+/// Percent-encode a value for use in a URL query string. `/` is kept as-is so
+/// paths stay readable.
+pub(crate) fn encode_query_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~' | b'/') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Decode `%XX` escapes, e.g. in link destinations. Invalid escapes are kept
+/// as-is and invalid UTF-8 is replaced.
+pub(crate) fn decode_percent(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let escaped = (bytes[idx] == b'%')
+            .then(|| value.get(idx + 1..idx + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                idx += 3;
+            }
+            None => {
+                decoded.push(bytes[idx]);
+                idx += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod tests {
@@ -30,4 +69,20 @@ mod tests {
         assert_eq!(escape("'"), "&#x27;");
         assert_eq!(escape("a < b & c > d"), "a &lt; b &amp; c &gt; d");
     }
+
+    #[test]
+    fn encode_query_value__should_encode_reserved_and_non_ascii_bytes() {
+        assert_eq!(
+            encode_query_value("notes/Seed List.md"),
+            "notes/Seed%20List.md"
+        );
+        assert_eq!(encode_query_value("a&b=c#d"), "a%26b%3Dc%23d");
+        assert_eq!(encode_query_value("é"), "%C3%A9");
+    }
+
+    #[test]
+    fn decode_percent__should_decode_escapes_and_keep_invalid_ones() {
+        assert_eq!(decode_percent("Seed%20List.md"), "Seed List.md");
+        assert_eq!(decode_percent("%C3%A9%zz%"), "é%zz%");
+    }
 }
@@ -13,6 +13,7 @@ mod ports;
 mod types;
 mod uploads;
 
+mod agent;
 mod app;
 mod assets;
 mod git;
@@ -36,6 +37,27 @@ pub fn generate_vapid_credentials() -> Result<VapidCredentials, web_push::WebPus
     })
 }
 
+pub struct BrokenLink {
+    pub doc_id: String,
+    /// One-based line number of the link.
+    pub line: usize,
+    pub target: String,
+    pub problem: &'static str,
+}
+
+pub fn check_links(root: &std::path::Path) -> std::io::Result<Vec<BrokenLink>> {
+    let links = documents::check_links(root)?;
+    Ok(links
+        .into_iter()
+        .map(|link| BrokenLink {
+            doc_id: link.doc_id,
+            line: link.line + 1,
+            target: link.target,
+            problem: link.problem.describe(),
+        })
+        .collect())
+}
+
 pub async fn serve(addr: SocketAddr, config: config::AppConfig) {
     let listener = tokio::net::TcpListener::bind(addr)
         .await
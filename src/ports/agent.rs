@@ -0,0 +1,12 @@
+use crate::types::agent::AgentRequest;
+
+pub trait AgentBackend: Clone + Send + Sync + 'static {
+    type Error: std::fmt::Display + Send + Sync + 'static;
+    type Fut<'a>: Future<Output = Result<String, Self::Error>> + Send + 'a
+    where
+        Self: 'a;
+
+    /// Answers a directive with the markdown to write into its
+    /// `<magent-response>` block.
+    fn respond<'a>(&'a self, request: &'a AgentRequest) -> Self::Fut<'a>;
+}
@@ -1,10 +1,25 @@
-use crate::types::directives::Subscription;
+use crate::types::directives::Channel;
 
+/// Sends notifications to one kind of target: a web push subscription, a
+/// URL or an email address, or any of them for a sender covering every channel.
 pub trait PushSender: Clone + Send + Sync + 'static {
+    type Target: ?Sized + Sync;
     type Error: std::fmt::Display + Send + Sync + 'static;
     type Fut<'a>: Future<Output = Result<(), Self::Error>> + Send + 'a
     where
         Self: 'a;
 
-    fn send<'a>(&'a self, subscription: &'a Subscription, message: &'a str) -> Self::Fut<'a>;
+    fn send<'a>(&'a self, target: &'a Self::Target, message: &'a str) -> Self::Fut<'a>;
+
+    /// Whether `error` means the subscription is gone for good, e.g. the push
+    /// service answered 404 or 410, so it should not be tried again.
+    fn is_expired(_error: &Self::Error) -> bool {
+        false
+    }
+
+    /// Whether this sender can deliver over `channel` at all. Recipients whose
+    /// channels it cannot reach are skipped without a delivery record.
+    fn has_channel(&self, _channel: Channel) -> bool {
+        true
+    }
 }
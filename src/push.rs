@@ -1,127 +1,173 @@
-use crate::adapters::{TokioTimeProvider, WebPushSender};
+use crate::adapters::{
+    ChannelSender, EmailSender, NtfySender, TokioTimeProvider, WebPushSender, WebhookSender,
+};
 use crate::config;
 use crate::ports::push::PushSender;
-use crate::types::directives::DirectiveRegistries;
+use crate::ports::time::TimeProvider;
+use crate::types::directives::{DeliveryTarget, DirectiveRegistries, Notification};
 
+pub(crate) mod history;
+mod prune;
+pub(crate) mod recurrence;
 mod scheduler;
+pub(crate) mod time_zone;
 pub(crate) mod vapid;
 
 use std::sync::Arc;
 use std::sync::Mutex;
 
-use scheduler::PushScheduler;
+use history::DeliveryLog;
+use prune::SubscriptionPruner;
+use scheduler::{Delivery, PushScheduler};
+use time::OffsetDateTime;
+
 pub(crate) use scheduler::ScheduledNotificationHandle;
 pub(crate) use vapid::{VapidConfigStatus, load_vapid_config};
 
+/// The scheduler with every configured channel, built once at startup so
+/// scheduled notifications keep working whichever channels users switch to.
+pub(crate) type Scheduler = PushScheduler<TokioTimeProvider, ChannelSender>;
+
 pub fn maybe_start_scheduler(
-    config: &config::AppConfig,
+    scheduler: Option<&Scheduler>,
     registries: Arc<DirectiveRegistries>,
     handles: Arc<Mutex<Vec<ScheduledNotificationHandle>>>,
 ) {
-    let vapid = match load_vapid_config(config) {
-        VapidConfigStatus::Ready(vapid) => vapid,
+    let Some(scheduler) = scheduler else {
+        return;
+    };
+    let mut guard = handles.lock().expect("push handles lock");
+    scheduler.reload(registries, &mut guard);
+}
+
+/// Returns `None` when no sender could be set up for any channel.
+pub(crate) fn build_scheduler(config: &config::AppConfig, log: &DeliveryLog) -> Option<Scheduler> {
+    let sender = build_sender(config)?;
+    Some(PushScheduler::new(
+        TokioTimeProvider,
+        sender,
+        config.task_reminder_lead,
+        log.clone(),
+        SubscriptionPruner::new(&config.root),
+    ))
+}
+
+/// The senders for every channel: web push and email when the server
+/// settings configure them, webhook and ntfy always, so users can switch to
+/// them without a restart. Recipients who only use channels without a sender
+/// are skipped at delivery. Returns `None` when no sender could be set up.
+fn build_sender(config: &config::AppConfig) -> Option<ChannelSender> {
+    let webpush = match load_vapid_config(config) {
+        VapidConfigStatus::Ready(vapid) => match WebPushSender::new(vapid) {
+            Ok(sender) => Some(sender),
+            Err(err) => {
+                eprintln!("web push disabled: failed to init web-push ({err})");
+                None
+            }
+        },
         VapidConfigStatus::Incomplete => {
-            eprintln!("push notifications disabled: incomplete VAPID configuration");
-            return;
-        }
-        VapidConfigStatus::Missing => {
-            return;
+            eprintln!("web push disabled: incomplete VAPID configuration");
+            None
         }
+        VapidConfigStatus::Missing => None,
     };
 
-    let sender = match WebPushSender::new(vapid) {
-        Ok(sender) => sender,
-        Err(err) => {
-            eprintln!("push notifications disabled: failed to init web-push ({err})");
-            return;
+    let email = match (&config.smtp_url, &config.smtp_from) {
+        (Some(url), Some(from)) => match EmailSender::new(url, from) {
+            Ok(sender) => Some(sender),
+            Err(err) => {
+                eprintln!("email notifications disabled: {err}");
+                None
+            }
+        },
+        (None, None) => None,
+        _ => {
+            eprintln!("email notifications disabled: set both --smtp-url and --smtp-from");
+            None
         }
     };
 
-    let scheduler = PushScheduler::new(TokioTimeProvider, sender);
-    let scheduled = scheduler.spawn_all(registries);
-    let mut guard = handles.lock().expect("push handles lock");
-    guard.extend(scheduled);
+    let webhook = WebhookSender::new()
+        .map_err(|err| eprintln!("webhook notifications disabled: {err}"))
+        .ok();
+    let ntfy = NtfySender::new()
+        .map_err(|err| eprintln!("ntfy notifications disabled: {err}"))
+        .ok();
+
+    let sender = ChannelSender {
+        webpush,
+        webhook,
+        ntfy,
+        email,
+    };
+    (!sender.is_empty()).then_some(sender)
 }
 
 pub async fn send_mentions(
-    config: &config::AppConfig,
+    scheduler: Option<&Scheduler>,
     registries: &DirectiveRegistries,
     doc_id: &str,
     mentions: &[(String, String)],
 ) {
-    if mentions.is_empty() {
+    let Some(scheduler) = scheduler else {
         return;
-    }
-
-    let vapid = match load_vapid_config(config) {
-        VapidConfigStatus::Ready(vapid) => vapid,
-        VapidConfigStatus::Incomplete => {
-            eprintln!("push notifications disabled: incomplete VAPID configuration");
-            return;
-        }
-        VapidConfigStatus::Missing => {
-            return;
-        }
-    };
-
-    let sender = match WebPushSender::new(vapid) {
-        Ok(sender) => sender,
-        Err(err) => {
-            eprintln!("push notifications disabled: failed to init web-push ({err})");
-            return;
-        }
     };
-
-    send_mentions_with_sender(sender, registries, doc_id, mentions).await;
+    send_mentions_with_scheduler(scheduler, registries, doc_id, mentions).await;
 }
 
-async fn send_mentions_with_sender<S: PushSender>(
-    sender: S,
+async fn send_mentions_with_scheduler<T: TimeProvider, S: PushSender<Target = DeliveryTarget>>(
+    scheduler: &PushScheduler<T, S>,
     registries: &DirectiveRegistries,
     doc_id: &str,
     mentions: &[(String, String)],
 ) {
+    let now = OffsetDateTime::now_utc();
+    let notification_id = format!("mention:{doc_id}");
     for (recipient, message) in mentions {
-        let subscriptions = match registries.subscriptions.get(recipient) {
-            Some(subscriptions) => subscriptions,
-            None => {
-                eprintln!(
-                    "push delivery warning: no subscriptions for '{}' ({})",
-                    recipient, doc_id
-                );
-                continue;
-            }
+        let notification = Notification {
+            to: vec![recipient.clone()],
+            at: now,
+            message: message.clone(),
+            doc_id: doc_id.to_string(),
+            line: 0,
+            repeat: None,
+            until: None,
+            tz: None,
+            pending_local: None,
         };
-
-        for subscription in subscriptions {
-            if let Err(err) = sender.send(subscription, message).await {
-                eprintln!(
-                    "push delivery error: {} (user {}, doc {})",
-                    err, recipient, doc_id
-                );
-            }
-        }
+        let delivery = Delivery {
+            notification_id: &notification_id,
+            notification: &notification,
+            scheduled_for: now,
+        };
+        scheduler.deliver(registries, delivery, now).await;
     }
 }
 
+/// Updates the scheduled notifications after the registries were reloaded.
+/// Only notifications that were added, changed or removed are touched.
 pub fn restart_scheduler(
-    config: &config::AppConfig,
+    scheduler: Option<&Scheduler>,
     registries: Arc<DirectiveRegistries>,
     handles: Arc<Mutex<Vec<ScheduledNotificationHandle>>>,
 ) {
-    {
-        let mut guard = handles.lock().expect("push handles lock");
-        for handle in guard.drain(..) {
-            handle.abort();
+    let mut guard = handles.lock().expect("push handles lock");
+    match scheduler {
+        Some(scheduler) => scheduler.reload(registries, &mut guard),
+        None => {
+            for handle in guard.drain(..) {
+                handle.abort();
+            }
         }
     }
-    maybe_start_scheduler(config, registries, handles);
 }
 
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod tests {
     use super::*;
+    use crate::push::history::DeliveryOutcome;
+    use crate::test_support::create_temp_root;
     use crate::types::directives::Subscription;
     use std::future::Future;
     use std::pin::Pin;
@@ -143,15 +189,16 @@ mod tests {
     }
 
     impl PushSender for TestSender {
+        type Target = DeliveryTarget;
         type Error = TestSendError;
         type Fut<'a>
             = Pin<Box<dyn Future<Output = Result<(), Self::Error>> + Send + 'a>>
         where
             Self: 'a;
 
-        fn send<'a>(&'a self, subscription: &'a Subscription, message: &'a str) -> Self::Fut<'a> {
+        fn send<'a>(&'a self, target: &'a DeliveryTarget, message: &'a str) -> Self::Fut<'a> {
             let sent = Arc::clone(&self.sent);
-            let endpoint = subscription.endpoint.clone();
+            let endpoint = target.endpoint().to_string();
             let message = message.to_string();
             Box::pin(async move {
                 sent.lock().expect("sent lock").push((endpoint, message));
@@ -161,7 +208,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn send_mentions_with_sender__should_send_for_each_line() {
+    async fn send_mentions_with_scheduler__should_send_for_each_line() {
         // Given
         let mut registries = DirectiveRegistries::default();
         registries.subscriptions.insert(
@@ -170,16 +217,29 @@ mod tests {
                 endpoint: "https://push.example/123".to_string(),
                 p256dh: "p256".to_string(),
                 auth: "auth".to_string(),
+                doc_id: "devices.md".to_string(),
             }],
         );
         let mentions = vec![
             ("marten".to_string(), "First line".to_string()),
             ("marten".to_string(), "Second line".to_string()),
+            ("ana".to_string(), "Third line".to_string()),
         ];
         let sender = TestSender::default();
+        let root = create_temp_root("send-mentions");
+        let log = DeliveryLog::new(&root);
+        let pruner = SubscriptionPruner::new(&root);
+
+        let scheduler = PushScheduler::new(
+            TokioTimeProvider,
+            sender.clone(),
+            time::Duration::ZERO,
+            log.clone(),
+            pruner,
+        );
 
         // When
-        send_mentions_with_sender(sender.clone(), &registries, "note.md", &mentions).await;
+        send_mentions_with_scheduler(&scheduler, &registries, "note.md", &mentions).await;
 
         // Then
         let sent = sender.sent.lock().expect("sent lock").clone();
@@ -187,5 +247,66 @@ mod tests {
         assert_eq!(sent[0].0, "https://push.example/123");
         assert_eq!(sent[0].1, "First line");
         assert_eq!(sent[1].1, "Second line");
+        let outcomes: Vec<(String, String, DeliveryOutcome)> = log
+            .records()
+            .into_iter()
+            .map(|record| (record.notification_id, record.recipient, record.outcome))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                (
+                    "mention:note.md".to_string(),
+                    "marten".to_string(),
+                    DeliveryOutcome::Delivered
+                ),
+                (
+                    "mention:note.md".to_string(),
+                    "marten".to_string(),
+                    DeliveryOutcome::Delivered
+                ),
+                (
+                    "mention:note.md".to_string(),
+                    "ana".to_string(),
+                    DeliveryOutcome::NoSubscription
+                ),
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn send_mentions__should_not_record_channels_without_a_sender() {
+        // Given
+        let root = create_temp_root("unconfigured-channels");
+        let config = config::AppConfig {
+            root: root.clone(),
+            ..config::AppConfig::default()
+        };
+        let log = DeliveryLog::new(&root);
+        let scheduler = build_scheduler(&config, &log).expect("scheduler");
+        let mut registries = DirectiveRegistries::default();
+        registries.subscriptions.insert(
+            "marten".to_string(),
+            vec![Subscription {
+                endpoint: "https://push.example/123".to_string(),
+                p256dh: "p256".to_string(),
+                auth: "auth".to_string(),
+                doc_id: "devices.md".to_string(),
+            }],
+        );
+        let mentions = vec![
+            ("marten".to_string(), "Subscribed".to_string()),
+            ("ana".to_string(), "Not subscribed".to_string()),
+        ];
+
+        // When
+        send_mentions(Some(&scheduler), &registries, "note.md", &mentions).await;
+
+        // Then
+        assert!(log.records().is_empty());
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
     }
 }
@@ -0,0 +1,342 @@
+use crate::documents::{STATE_DIR, state_file};
+use crate::types::directives::Notification;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use time::OffsetDateTime;
+
+const HISTORY_FILE: &str = "push-history.jsonl";
+
+/// One delivery attempt to one recipient, or the lack of one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DeliveryRecord {
+    /// See [`notification_id`]; mentions use `mention:<doc id>`.
+    pub(crate) notification_id: String,
+    pub(crate) doc_id: String,
+    /// When the notification was due.
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) scheduled_for: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) attempted_at: OffsetDateTime,
+    pub(crate) recipient: String,
+    pub(crate) endpoint: Option<String>,
+    pub(crate) outcome: DeliveryOutcome,
+    pub(crate) error: Option<String>,
+    pub(crate) message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum DeliveryOutcome {
+    Delivered,
+    Failed,
+    /// The push service no longer knows the endpoint, so its subscription
+    /// was pruned.
+    Expired,
+    /// The recipient has no `/subscription` block.
+    NoSubscription,
+}
+
+impl DeliveryOutcome {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            DeliveryOutcome::Delivered => "delivered",
+            DeliveryOutcome::Failed => "failed",
+            DeliveryOutcome::Expired => "expired",
+            DeliveryOutcome::NoSubscription => "no subscription",
+        }
+    }
+}
+
+/// Once the log is larger than this, it is renamed to `push-history.1.jsonl`,
+/// replacing the previous one, and a new log is started.
+const MAX_LOG_BYTES: u64 = 1 << 20;
+const ROTATED_FILE: &str = "push-history.1.jsonl";
+
+/// Append-only JSON lines log of push deliveries in `.mindex/push-history.jsonl`.
+/// Clones share the same log.
+#[derive(Debug, Clone)]
+pub(crate) struct DeliveryLog {
+    root: PathBuf,
+    max_bytes: u64,
+    handled: Arc<Mutex<Option<Handled>>>,
+}
+
+/// The occurrences in the log, read once and then kept up to date by
+/// [`DeliveryLog::append`].
+#[derive(Debug)]
+struct Handled {
+    occurrences: HashSet<(String, OffsetDateTime)>,
+    /// When the log was rotated away, the oldest due time still on record.
+    /// Anything due before it may have been dropped and counts as handled.
+    horizon: Option<OffsetDateTime>,
+}
+
+impl DeliveryLog {
+    pub(crate) fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            max_bytes: MAX_LOG_BYTES,
+            handled: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_max_bytes(root: &Path, max_bytes: u64) -> Self {
+        Self {
+            max_bytes,
+            ..Self::new(root)
+        }
+    }
+
+    fn path(&self, file: &str) -> PathBuf {
+        self.root.join(STATE_DIR).join(file)
+    }
+
+    /// Appends a record. Failures are logged and otherwise ignored so a
+    /// read-only state directory never stops deliveries.
+    pub(crate) fn append(&self, record: &DeliveryRecord) {
+        let mut handled = self.handled.lock().expect("delivery log lock");
+        if let Some(handled) = handled.as_mut() {
+            handled
+                .occurrences
+                .insert((record.notification_id.clone(), record.scheduled_for));
+        }
+        if let Err(err) = self.try_append(record) {
+            eprintln!("failed to record push delivery: {err}");
+        }
+    }
+
+    fn try_append(&self, record: &DeliveryRecord) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(record).map_err(std::io::Error::other)?;
+        line.push('\n');
+        let path = state_file(&self.root, HISTORY_FILE)?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        file.write_all(line.as_bytes())?;
+        if file.metadata()?.len() > self.max_bytes {
+            std::fs::rename(&path, self.path(ROTATED_FILE))?;
+        }
+        Ok(())
+    }
+
+    /// Every record, oldest first. Lines that do not parse are skipped.
+    pub(crate) fn records(&self) -> Vec<DeliveryRecord> {
+        let _guard = self.handled.lock().expect("delivery log lock");
+        self.read_records()
+    }
+
+    fn read_records(&self) -> Vec<DeliveryRecord> {
+        [ROTATED_FILE, HISTORY_FILE]
+            .into_iter()
+            .filter_map(|file| std::fs::read_to_string(self.path(file)).ok())
+            .flat_map(|raw| {
+                raw.lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect::<Vec<DeliveryRecord>>()
+            })
+            .collect()
+    }
+
+    /// Up to `limit` records attempted before `before`, newest first.
+    /// Records attempted at the same moment as the last one are included
+    /// too, so paging with its `attempted_at` skips none of them.
+    pub(crate) fn newest(
+        &self,
+        limit: usize,
+        before: Option<OffsetDateTime>,
+    ) -> Vec<DeliveryRecord> {
+        let mut records: Vec<DeliveryRecord> = self
+            .records()
+            .into_iter()
+            .rev()
+            .filter(|record| before.is_none_or(|before| record.attempted_at < before))
+            .collect();
+        let mut end = limit.min(records.len());
+        while end > 0
+            && end < records.len()
+            && records[end].attempted_at == records[end - 1].attempted_at
+        {
+            end += 1;
+        }
+        records.truncate(end);
+        records
+    }
+
+    /// Whether the occurrence of `notification_id` due at `scheduled_for`
+    /// was already handled.
+    pub(crate) fn is_handled(&self, notification_id: &str, scheduled_for: OffsetDateTime) -> bool {
+        let mut handled = self.handled.lock().expect("delivery log lock");
+        let handled = handled.get_or_insert_with(|| self.load_handled());
+        handled
+            .horizon
+            .is_some_and(|horizon| scheduled_for < horizon)
+            || handled
+                .occurrences
+                .contains(&(notification_id.to_string(), scheduled_for))
+    }
+
+    fn load_handled(&self) -> Handled {
+        let records = self.read_records();
+        let rotated = self.path(ROTATED_FILE).exists();
+        Handled {
+            horizon: rotated
+                .then(|| records.iter().map(|record| record.scheduled_for).min())
+                .flatten(),
+            occurrences: records
+                .into_iter()
+                .map(|record| (record.notification_id, record.scheduled_for))
+                .collect(),
+        }
+    }
+}
+
+/// A stable id for a notification: its document and a hash of its contents,
+/// so editing a `/notify` block gives it a new id.
+pub(crate) fn notification_id(notification: &Notification) -> String {
+    format!(
+        "{}#{:016x}",
+        notification.doc_id,
+        content_hash(notification)
+    )
+}
+
+/// A hash of what a notification sends, to whom and when.
+pub(super) fn content_hash(notification: &Notification) -> u64 {
+    let contents = format!(
+        "{:?}|{}|{}|{:?}|{:?}|{:?}",
+        notification.to,
+        notification.at.unix_timestamp(),
+        notification.message,
+        notification.repeat,
+        notification.until.map(OffsetDateTime::unix_timestamp),
+        notification.tz,
+    );
+    fnv1a(contents.as_bytes())
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_temp_root;
+    use time::format_description::well_known::Rfc3339;
+
+    #[test]
+    fn delivery_log__should_append_and_read_records() {
+        // Given
+        let root = create_temp_root("push-history");
+        let log = DeliveryLog::new(&root);
+        let at = OffsetDateTime::parse("2026-10-19T09:00:00Z", &Rfc3339).expect("parse");
+        let record = DeliveryRecord {
+            notification_id: "note.md#1".to_string(),
+            doc_id: "note.md".to_string(),
+            scheduled_for: at,
+            attempted_at: at,
+            recipient: "marten".to_string(),
+            endpoint: None,
+            outcome: DeliveryOutcome::NoSubscription,
+            error: None,
+            message: "Hello".to_string(),
+        };
+
+        // When
+        log.append(&record);
+        log.append(&DeliveryRecord {
+            outcome: DeliveryOutcome::Failed,
+            error: Some("gone".to_string()),
+            ..record.clone()
+        });
+        let reopened = DeliveryLog::new(&root);
+
+        // Then
+        let records = reopened.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0], record);
+        assert_eq!(records[1].error.as_deref(), Some("gone"));
+        assert!(reopened.is_handled("note.md#1", at));
+        assert!(!reopened.is_handled("note.md#2", at));
+        assert_eq!(
+            std::fs::read_to_string(root.join(".mindex/.gitignore")).expect("gitignore"),
+            "*\n"
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    fn record_at(minutes: i64) -> DeliveryRecord {
+        let at = OffsetDateTime::parse("2026-10-19T09:00:00Z", &Rfc3339).expect("parse")
+            + time::Duration::minutes(minutes);
+        DeliveryRecord {
+            notification_id: format!("note.md#{minutes}"),
+            doc_id: "note.md".to_string(),
+            scheduled_for: at,
+            attempted_at: at,
+            recipient: "marten".to_string(),
+            endpoint: None,
+            outcome: DeliveryOutcome::Delivered,
+            error: None,
+            message: "Hello".to_string(),
+        }
+    }
+
+    #[test]
+    fn delivery_log__should_rotate_and_treat_dropped_occurrences_as_handled() {
+        // Given
+        let root = create_temp_root("push-history-rotate");
+        let log = DeliveryLog::with_max_bytes(&root, 600);
+
+        // When
+        for minutes in 0..6 {
+            log.append(&record_at(minutes));
+        }
+        let reopened = DeliveryLog::new(&root);
+
+        // Then
+        let records = reopened.records();
+        assert!(records.len() < 6);
+        assert_eq!(records.last(), Some(&record_at(5)));
+        assert!(root.join(".mindex/push-history.1.jsonl").exists());
+        let oldest = records[0].scheduled_for;
+        assert!(reopened.is_handled("note.md#0", record_at(0).scheduled_for));
+        assert!(!reopened.is_handled("other.md#1", oldest + time::Duration::hours(1)));
+        assert!(log.is_handled("note.md#0", record_at(0).scheduled_for));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn newest__should_page_backwards_without_splitting_a_moment() {
+        // Given
+        let root = create_temp_root("push-history-page");
+        let log = DeliveryLog::new(&root);
+        for minutes in [0, 1, 1, 2] {
+            log.append(&record_at(minutes));
+        }
+
+        // When
+        let first = log.newest(2, None);
+        let second = log.newest(2, first.last().map(|record| record.attempted_at));
+
+        // Then
+        let minutes = |records: &[DeliveryRecord]| -> Vec<String> {
+            records.iter().map(|r| r.notification_id.clone()).collect()
+        };
+        assert_eq!(minutes(&first), vec!["note.md#2", "note.md#1", "note.md#1"]);
+        assert_eq!(minutes(&second), vec!["note.md#0"]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+}
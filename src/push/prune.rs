@@ -0,0 +1,111 @@
+use crate::directives::remove_subscription_block;
+use crate::fs::atomic_write;
+use crate::types::directives::Subscription;
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use time::OffsetDateTime;
+
+type RewriteHook = Box<dyn Fn(&str) + Send + Sync>;
+
+/// Removes `/subscription` blocks whose endpoint the push service reported
+/// as gone, so later deliveries stop trying them.
+#[derive(Clone)]
+pub(crate) struct SubscriptionPruner {
+    root: PathBuf,
+    /// Endpoints pruned since the server started. The registries list them
+    /// until the next reload, and a failed block removal keeps them listed,
+    /// so they are skipped.
+    pruned: Arc<Mutex<HashSet<String>>>,
+    /// Documents rewritten since the last [`SubscriptionPruner::finish`].
+    rewritten: Arc<Mutex<BTreeSet<String>>>,
+    on_rewritten: Arc<OnceLock<RewriteHook>>,
+}
+
+impl std::fmt::Debug for SubscriptionPruner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubscriptionPruner")
+            .field("root", &self.root)
+            .field("pruned", &self.pruned)
+            .field("rewritten", &self.rewritten)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SubscriptionPruner {
+    pub(crate) fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            pruned: Arc::new(Mutex::new(HashSet::new())),
+            rewritten: Arc::new(Mutex::new(BTreeSet::new())),
+            on_rewritten: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Sets what [`SubscriptionPruner::finish`] calls with each rewritten
+    /// document, so the server can refresh its view of it. Only the first
+    /// hook is kept.
+    pub(crate) fn on_rewritten(&self, hook: impl Fn(&str) + Send + Sync + 'static) {
+        if self.on_rewritten.set(Box::new(hook)).is_err() {
+            eprintln!("subscription pruner already has a rewrite hook");
+        }
+    }
+
+    /// Passes the documents rewritten so far to the hook. Called once a
+    /// delivery is done, since refreshing the registries may restart the
+    /// notification that was being delivered.
+    pub(crate) fn finish(&self) {
+        let rewritten = std::mem::take(&mut *self.rewritten.lock().expect("rewritten lock"));
+        let Some(hook) = self.on_rewritten.get() else {
+            return;
+        };
+        for doc_id in &rewritten {
+            hook(doc_id);
+        }
+    }
+
+    pub(crate) fn is_pruned(&self, endpoint: &str) -> bool {
+        self.pruned.lock().expect("pruned lock").contains(endpoint)
+    }
+
+    /// Replaces the subscription's block in its source document with a
+    /// comment and returns a description of what was done.
+    pub(crate) fn prune(&self, user: &str, subscription: &Subscription) -> String {
+        self.pruned
+            .lock()
+            .expect("pruned lock")
+            .insert(subscription.endpoint.clone());
+        if subscription.doc_id.is_empty() {
+            return "subscription is not from a document".to_string();
+        }
+        let path = self.root.join(&subscription.doc_id);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                return format!("failed to read {}: {err}", subscription.doc_id);
+            }
+        };
+        let note = format!(
+            "<!-- expired push subscription for {user} removed on {} -->",
+            OffsetDateTime::now_utc().date()
+        );
+        let Some(updated) = remove_subscription_block(&contents, &subscription.endpoint, &note)
+        else {
+            return format!("no /subscription block found in {}", subscription.doc_id);
+        };
+        match atomic_write(&path, &updated) {
+            Ok(()) => {
+                self.rewritten
+                    .lock()
+                    .expect("rewritten lock")
+                    .insert(subscription.doc_id.clone());
+                format!(
+                    "removed its /subscription block from {}",
+                    subscription.doc_id
+                )
+            }
+            Err(err) => format!("failed to update {}: {err}", subscription.doc_id),
+        }
+    }
+}
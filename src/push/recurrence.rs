@@ -0,0 +1,754 @@
+use super::time_zone::{from_local, parse_zone, to_local};
+use crate::types::directives::{Notification, Repeat};
+
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, Weekday};
+
+/// How far past the search start occurrences are looked for. Eight years
+/// covers rules that only match on February 29.
+const SEARCH_DAYS: i64 = 8 * 366;
+
+/// A parsed `every`, `cron` or `rrule` value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Schedule {
+    Rule(RecurrenceRule),
+    Cron(CronSchedule),
+}
+
+/// Parses the repeat value of a `/notify` block.
+pub(crate) fn parse_repeat(repeat: &Repeat) -> Result<Schedule, String> {
+    match repeat {
+        Repeat::Every(value) => parse_every(value).map(Schedule::Rule),
+        Repeat::Cron(value) => CronSchedule::parse(value).map(Schedule::Cron),
+        Repeat::Rrule(value) => RecurrenceRule::parse(value).map(Schedule::Rule),
+    }
+}
+
+/// The first time `notification` goes out after `after`, if any.
+///
+/// A one-off notification only occurs at `at`. Repeating ones occur on their
+/// schedule from `at` on, up to `until`, evaluated on the wall clock of the
+/// notification's time zone (UTC without one).
+pub(crate) fn next_occurrence(
+    notification: &Notification,
+    after: OffsetDateTime,
+) -> Option<OffsetDateTime> {
+    let next = match &notification.repeat {
+        None => Some(notification.at).filter(|at| *at > after),
+        Some(repeat) => {
+            let schedule = parse_repeat(repeat).ok()?;
+            let zone = notification
+                .tz
+                .as_deref()
+                .and_then(|tz| parse_zone(tz).ok());
+            let zone = zone.as_ref();
+            let local = schedule.next_after(
+                to_local(notification.at, zone),
+                to_local(after, zone),
+                zone,
+            )?;
+            from_local(local, zone)
+        }
+    }?;
+    match notification.until {
+        Some(until) if next > until => None,
+        _ => Some(next),
+    }
+}
+
+impl Schedule {
+    /// The first occurrence at or after `start` and strictly after `after`,
+    /// both wall clock times in `zone`.
+    pub(crate) fn next_after(
+        &self,
+        start: PrimitiveDateTime,
+        after: PrimitiveDateTime,
+        zone: Option<&jiff::tz::TimeZone>,
+    ) -> Option<PrimitiveDateTime> {
+        match self {
+            Schedule::Rule(rule) => rule.next_after(start, after, zone),
+            Schedule::Cron(cron) => cron.next_after(start.max(after), after),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Frequency {
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// The supported subset of an RFC 5545 `RRULE`: `FREQ` (hourly to yearly),
+/// `INTERVAL`, `COUNT`, `UNTIL`, `BYMONTH`, `BYMONTHDAY`, `BYDAY` without
+/// ordinals, `BYHOUR` and `BYMINUTE`. The rule starts at the notification's
+/// `at`, which plays the part of `DTSTART`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RecurrenceRule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<RuleUntil>,
+    by_month: Vec<Month>,
+    by_month_day: Vec<i8>,
+    by_day: Vec<Weekday>,
+    by_hour: Vec<u8>,
+    by_minute: Vec<u8>,
+}
+
+/// The end of an `RRULE`. `UNTIL=...Z` is an instant, while a date-only
+/// `UNTIL` covers that whole day on the notification's wall clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RuleUntil {
+    Instant(OffsetDateTime),
+    Day(Date),
+}
+
+impl RuleUntil {
+    fn to_local(self, zone: Option<&jiff::tz::TimeZone>) -> PrimitiveDateTime {
+        match self {
+            RuleUntil::Instant(at) => to_local(at, zone),
+            RuleUntil::Day(date) => {
+                PrimitiveDateTime::new(date, Time::from_hms(23, 59, 59).expect("valid time"))
+            }
+        }
+    }
+}
+
+impl RecurrenceRule {
+    fn new(frequency: Frequency) -> Self {
+        Self {
+            frequency,
+            interval: 1,
+            count: None,
+            until: None,
+            by_month: Vec::new(),
+            by_month_day: Vec::new(),
+            by_day: Vec::new(),
+            by_hour: Vec::new(),
+            by_minute: Vec::new(),
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let value = value
+            .strip_prefix("RRULE:")
+            .or_else(|| value.strip_prefix("rrule:"))
+            .unwrap_or(value);
+        let mut frequency = None;
+        let mut rule = RecurrenceRule::new(Frequency::Daily);
+
+        for part in value.split(';').filter(|part| !part.trim().is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected NAME=VALUE, got '{part}'"))?;
+            let value = value.trim();
+            match name.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "HOURLY" => Frequency::Hourly,
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(format!("unsupported FREQ '{value}'")),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = parse_number(value, "INTERVAL", 1, i64::from(u32::MAX))?;
+                }
+                "COUNT" => rule.count = Some(parse_number(value, "COUNT", 1, i64::from(u32::MAX))?),
+                "UNTIL" => rule.until = Some(parse_rrule_until(value)?),
+                "BYMONTH" => {
+                    rule.by_month = parse_list(value, |item| {
+                        let month: u8 = parse_number(item, "BYMONTH", 1, 12)?;
+                        Month::try_from(month).map_err(|err| err.to_string())
+                    })?
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day = parse_list(value, |item| {
+                        let day: i8 = parse_number(item, "BYMONTHDAY", -31, 31)?;
+                        if day == 0 {
+                            return Err("BYMONTHDAY cannot be 0".to_string());
+                        }
+                        Ok(day)
+                    })?
+                }
+                "BYDAY" => {
+                    rule.by_day = parse_list(value, |item| {
+                        parse_weekday(item)
+                            .ok_or_else(|| format!("unsupported BYDAY value '{item}'"))
+                    })?
+                }
+                "BYHOUR" => {
+                    rule.by_hour = parse_list(value, |item| parse_number(item, "BYHOUR", 0, 23))?
+                }
+                "BYMINUTE" => {
+                    rule.by_minute =
+                        parse_list(value, |item| parse_number(item, "BYMINUTE", 0, 59))?
+                }
+                "WKST" if value.eq_ignore_ascii_case("MO") => {}
+                other => return Err(format!("unsupported rule part '{other}'")),
+            }
+        }
+
+        rule.frequency = frequency.ok_or("FREQ is required")?;
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err("COUNT and UNTIL cannot both be set".to_string());
+        }
+        Ok(rule)
+    }
+
+    fn next_after(
+        &self,
+        start: PrimitiveDateTime,
+        after: PrimitiveDateTime,
+        zone: Option<&jiff::tz::TimeZone>,
+    ) -> Option<PrimitiveDateTime> {
+        let until = self.until.map(|until| until.to_local(zone));
+        // Occurrences are counted from the start, so COUNT rules walk from there.
+        let mut day = if self.count.is_some() {
+            start.date()
+        } else {
+            start.date().max(after.date())
+        };
+        let last_day = after.date().max(start.date()) + time::Duration::days(SEARCH_DAYS);
+        let mut seen = 0u32;
+
+        while day <= last_day {
+            if self.matches_day(start.date(), day) {
+                for time in self.times_on(start, day) {
+                    let occurrence = PrimitiveDateTime::new(day, time);
+                    if occurrence < start {
+                        continue;
+                    }
+                    if until.is_some_and(|until| occurrence > until) {
+                        return None;
+                    }
+                    seen += 1;
+                    if self.count.is_some_and(|count| seen > count) {
+                        return None;
+                    }
+                    if occurrence > after {
+                        return Some(occurrence);
+                    }
+                }
+            }
+            day = day.next_day()?;
+        }
+        None
+    }
+
+    fn matches_day(&self, start: Date, day: Date) -> bool {
+        let interval = i64::from(self.interval);
+        let aligned = match self.frequency {
+            Frequency::Hourly => true,
+            Frequency::Daily => (day - start).whole_days() % interval == 0,
+            Frequency::Weekly => {
+                (week_start(day) - week_start(start)).whole_weeks() % interval == 0
+            }
+            Frequency::Monthly => months_between(start, day) % interval == 0,
+            Frequency::Yearly => i64::from(day.year() - start.year()) % interval == 0,
+        };
+        if !aligned {
+            return false;
+        }
+
+        let month_ok = if !self.by_month.is_empty() {
+            self.by_month.contains(&day.month())
+        } else if self.frequency == Frequency::Yearly
+            && self.by_month_day.is_empty()
+            && self.by_day.is_empty()
+        {
+            day.month() == start.month()
+        } else {
+            true
+        };
+        let month_day_ok = if !self.by_month_day.is_empty() {
+            let last = i16::from(day.month().length(day.year()));
+            self.by_month_day.iter().any(|&wanted| {
+                let wanted = i16::from(wanted);
+                let wanted = if wanted < 0 {
+                    last + 1 + wanted
+                } else {
+                    wanted
+                };
+                wanted == i16::from(day.day())
+            })
+        } else if matches!(self.frequency, Frequency::Monthly | Frequency::Yearly)
+            && self.by_day.is_empty()
+        {
+            day.day() == start.day()
+        } else {
+            true
+        };
+        let weekday_ok = if !self.by_day.is_empty() {
+            self.by_day.contains(&day.weekday())
+        } else if self.frequency == Frequency::Weekly {
+            day.weekday() == start.weekday()
+        } else {
+            true
+        };
+        month_ok && month_day_ok && weekday_ok
+    }
+
+    fn times_on(&self, start: PrimitiveDateTime, day: Date) -> Vec<Time> {
+        let hours: Vec<u8> = if self.frequency == Frequency::Hourly {
+            let interval = i64::from(self.interval);
+            let days = (day - start.date()).whole_days();
+            (0..24u8)
+                .filter(|hour| self.by_hour.is_empty() || self.by_hour.contains(hour))
+                .filter(|hour| {
+                    let hours = days * 24 + i64::from(*hour) - i64::from(start.hour());
+                    hours.rem_euclid(interval) == 0
+                })
+                .collect()
+        } else if self.by_hour.is_empty() {
+            vec![start.hour()]
+        } else {
+            self.by_hour.clone()
+        };
+        let minutes = if self.by_minute.is_empty() {
+            vec![start.minute()]
+        } else {
+            self.by_minute.clone()
+        };
+
+        let mut times: Vec<Time> = hours
+            .iter()
+            .flat_map(|&hour| {
+                minutes
+                    .iter()
+                    .filter_map(move |&minute| Time::from_hms(hour, minute, start.second()).ok())
+            })
+            .collect();
+        times.sort();
+        times.dedup();
+        times
+    }
+}
+
+/// The rule for an `every` value.
+fn parse_every(value: &str) -> Result<RecurrenceRule, String> {
+    let rule = match value.trim().to_ascii_lowercase().as_str() {
+        "hourly" => RecurrenceRule::new(Frequency::Hourly),
+        "daily" => RecurrenceRule::new(Frequency::Daily),
+        "weekdays" => RecurrenceRule {
+            by_day: vec![
+                Weekday::Monday,
+                Weekday::Tuesday,
+                Weekday::Wednesday,
+                Weekday::Thursday,
+                Weekday::Friday,
+            ],
+            ..RecurrenceRule::new(Frequency::Weekly)
+        },
+        "weekly" => RecurrenceRule::new(Frequency::Weekly),
+        "monthly" => RecurrenceRule::new(Frequency::Monthly),
+        "yearly" => RecurrenceRule::new(Frequency::Yearly),
+        _ => {
+            return Err(format!(
+                "'{value}' is not hourly, daily, weekdays, weekly, monthly or yearly"
+            ));
+        }
+    };
+    Ok(rule)
+}
+
+fn parse_rrule_until(value: &str) -> Result<RuleUntil, String> {
+    let invalid = || format!("invalid UNTIL '{value}'; expected YYYYMMDD or YYYYMMDDTHHMMSSZ");
+    let digits = |range: std::ops::Range<usize>| -> Result<u32, String> {
+        value
+            .get(range)
+            .filter(|part| part.bytes().all(|byte| byte.is_ascii_digit()))
+            .and_then(|part| part.parse().ok())
+            .ok_or_else(invalid)
+    };
+    let year = i32::try_from(digits(0..4)?).map_err(|_| invalid())?;
+    let month = Month::try_from(digits(4..6)? as u8).map_err(|_| invalid())?;
+    let date = Date::from_calendar_date(year, month, digits(6..8)? as u8).map_err(|_| invalid())?;
+    match value.len() {
+        8 => Ok(RuleUntil::Day(date)),
+        16 if &value[8..9] == "T" && value.ends_with('Z') => {
+            let time = Time::from_hms(
+                digits(9..11)? as u8,
+                digits(11..13)? as u8,
+                digits(13..15)? as u8,
+            )
+            .map_err(|_| invalid())?;
+            Ok(RuleUntil::Instant(
+                PrimitiveDateTime::new(date, time).assume_utc(),
+            ))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// A five-field cron expression (`minute hour day-of-month month
+/// day-of-week`) or one of `@hourly`, `@daily`, `@weekly`, `@monthly` and
+/// `@yearly`. Fields accept `*`, lists, ranges, `/` steps, and month and
+/// weekday names. When both day fields are restricted either may match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    /// Bit 0 is Sunday.
+    days_of_week: u8,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+impl CronSchedule {
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let expanded = match value.to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            _ => value,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!(
+                "expected 5 fields (minute hour day month weekday), got {}",
+                fields.len()
+            ));
+        };
+
+        let minutes = parse_cron_field(minute, "minute", 0, 59, &[])?;
+        let hours = parse_cron_field(hour, "hour", 0, 23, &[])?;
+        let days_of_month = parse_cron_field(day_of_month, "day of month", 1, 31, &[])?;
+        let months = parse_cron_field(month, "month", 1, 12, &MONTH_NAMES)?;
+        // 7 is Sunday too.
+        let mut days_of_week = parse_cron_field(day_of_week, "day of week", 0, 7, &WEEKDAY_NAMES)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes,
+            hours: hours as u32,
+            days_of_month: days_of_month as u32,
+            months: months as u16,
+            days_of_week: days_of_week as u8,
+            day_of_month_restricted: !day_of_month.starts_with('*'),
+            day_of_week_restricted: !day_of_week.starts_with('*'),
+        })
+    }
+
+    /// The first match at or after `from` and strictly after `after`.
+    fn next_after(
+        &self,
+        from: PrimitiveDateTime,
+        after: PrimitiveDateTime,
+    ) -> Option<PrimitiveDateTime> {
+        let mut day = from.date();
+        let last_day = day + time::Duration::days(SEARCH_DAYS);
+        while day <= last_day {
+            if self.matches_day(day) {
+                for hour in (0..24u8).filter(|hour| self.hours & (1 << hour) != 0) {
+                    for minute in (0..60u8).filter(|minute| self.minutes & (1 << minute) != 0) {
+                        let Ok(time) = Time::from_hms(hour, minute, 0) else {
+                            continue;
+                        };
+                        let candidate = PrimitiveDateTime::new(day, time);
+                        if candidate >= from && candidate > after {
+                            return Some(candidate);
+                        }
+                    }
+                }
+            }
+            day = day.next_day()?;
+        }
+        None
+    }
+
+    fn matches_day(&self, day: Date) -> bool {
+        if self.months & (1 << u8::from(day.month())) == 0 {
+            return false;
+        }
+        let day_of_month = self.days_of_month & (1 << day.day()) != 0;
+        let day_of_week = self.days_of_week & (1 << day.weekday().number_days_from_sunday()) != 0;
+        if self.day_of_month_restricted && self.day_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+/// Parses one cron field into a bit set of the allowed values.
+fn parse_cron_field(
+    field: &str,
+    what: &str,
+    min: u8,
+    max: u8,
+    names: &[&str],
+) -> Result<u64, String> {
+    let value_of = |raw: &str| -> Result<u8, String> {
+        if let Some(idx) = names.iter().position(|name| name.eq_ignore_ascii_case(raw)) {
+            // Month names start at 1, weekday names at 0.
+            return Ok(idx as u8 + min);
+        }
+        raw.parse::<u8>()
+            .ok()
+            .filter(|value| (min..=max).contains(value))
+            .ok_or_else(|| format!("invalid {what} '{raw}'"))
+    };
+
+    let mut bits = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u8 = step
+                    .parse()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("invalid {what} step '{step}'"))?;
+                (range, step)
+            }
+            None => (item, 1),
+        };
+        let (first, last) = if range == "*" {
+            (min, max)
+        } else if let Some((first, last)) = range.split_once('-') {
+            (value_of(first)?, value_of(last)?)
+        } else {
+            let first = value_of(range)?;
+            // `5/15` runs from 5 to the end of the range.
+            (first, if item.contains('/') { max } else { first })
+        };
+        if first > last {
+            return Err(format!("invalid {what} range '{range}'"));
+        }
+        for value in (first..=last).step_by(usize::from(step)) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_number<T: TryFrom<i64> + Copy>(
+    value: &str,
+    what: &str,
+    min: i64,
+    max: i64,
+) -> Result<T, String> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|number| (min..=max).contains(number))
+        .and_then(|number| T::try_from(number).ok())
+        .ok_or_else(|| format!("invalid {what} '{value}'"))
+}
+
+fn parse_list<T>(value: &str, parse: impl Fn(&str) -> Result<T, String>) -> Result<Vec<T>, String> {
+    value.split(',').map(|item| parse(item.trim())).collect()
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value.to_ascii_uppercase().as_str() {
+        "MO" => Some(Weekday::Monday),
+        "TU" => Some(Weekday::Tuesday),
+        "WE" => Some(Weekday::Wednesday),
+        "TH" => Some(Weekday::Thursday),
+        "FR" => Some(Weekday::Friday),
+        "SA" => Some(Weekday::Saturday),
+        "SU" => Some(Weekday::Sunday),
+        _ => None,
+    }
+}
+
+fn week_start(day: Date) -> Date {
+    day - time::Duration::days(i64::from(day.weekday().number_days_from_monday()))
+}
+
+fn months_between(start: Date, day: Date) -> i64 {
+    i64::from(day.year() - start.year()) * 12 + i64::from(u8::from(day.month()))
+        - i64::from(u8::from(start.month()))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use time::format_description::well_known::Rfc3339;
+
+    fn utc(value: &str) -> OffsetDateTime {
+        OffsetDateTime::parse(value, &Rfc3339).expect("parse time")
+    }
+
+    fn notification(at: &str, repeat: Repeat) -> Notification {
+        Notification {
+            to: vec!["marten".to_string()],
+            at: utc(at),
+            message: "Hello".to_string(),
+            doc_id: "note.md".to_string(),
+            line: 1,
+            repeat: Some(repeat),
+            until: None,
+            tz: None,
+            pending_local: None,
+        }
+    }
+
+    fn occurrences(notification: &Notification, after: &str, limit: usize) -> Vec<String> {
+        let mut after = utc(after);
+        let mut found = Vec::new();
+        while found.len() < limit {
+            let Some(next) = next_occurrence(notification, after) else {
+                break;
+            };
+            found.push(next.format(&Rfc3339).expect("format"));
+            after = next;
+        }
+        found
+    }
+
+    #[test]
+    fn next_occurrence__should_repeat_every_interval_from_at() {
+        // Given
+        let weekly = notification("2026-10-05T09:30:00Z", Repeat::Every("weekly".into()));
+        let weekdays = notification("2026-10-16T09:00:00Z", Repeat::Every("weekdays".into()));
+
+        // Then
+        assert_eq!(
+            occurrences(&weekly, "2026-10-01T00:00:00Z", 2),
+            vec!["2026-10-05T09:30:00Z", "2026-10-12T09:30:00Z"]
+        );
+        assert_eq!(
+            occurrences(&weekly, "2026-10-12T09:30:00Z", 1),
+            vec!["2026-10-19T09:30:00Z"]
+        );
+        assert_eq!(
+            occurrences(&weekdays, "2026-10-16T09:00:00Z", 2),
+            vec!["2026-10-19T09:00:00Z", "2026-10-20T09:00:00Z"]
+        );
+    }
+
+    #[test]
+    fn next_occurrence__should_follow_rrule_parts_and_stop_after_count() {
+        // Given
+        let biweekly = notification(
+            "2026-10-05T08:00:00Z",
+            Repeat::Rrule("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;BYHOUR=8,17;COUNT=5".into()),
+        );
+        let month_end = notification(
+            "2026-01-31T18:00:00Z",
+            Repeat::Rrule("FREQ=MONTHLY;BYMONTHDAY=-1".into()),
+        );
+
+        // Then
+        assert_eq!(
+            occurrences(&biweekly, "2026-01-01T00:00:00Z", 10),
+            vec![
+                "2026-10-05T08:00:00Z",
+                "2026-10-05T17:00:00Z",
+                "2026-10-08T08:00:00Z",
+                "2026-10-08T17:00:00Z",
+                "2026-10-19T08:00:00Z",
+            ]
+        );
+        assert_eq!(
+            occurrences(&month_end, "2026-02-01T00:00:00Z", 2),
+            vec!["2026-02-28T18:00:00Z", "2026-03-31T18:00:00Z"]
+        );
+    }
+
+    #[test]
+    fn next_occurrence__should_match_cron_fields() {
+        // Given
+        let standup = notification("2026-10-14T00:00:00Z", Repeat::Cron("0 9 * * MON".into()));
+        let either_day = notification(
+            "2026-10-01T00:00:00Z",
+            Repeat::Cron("*/30 6 13 * FRI".into()),
+        );
+
+        // Then
+        assert_eq!(
+            occurrences(&standup, "2026-10-01T00:00:00Z", 2),
+            vec!["2026-10-19T09:00:00Z", "2026-10-26T09:00:00Z"]
+        );
+        assert_eq!(
+            occurrences(&either_day, "2026-10-09T06:00:00Z", 3),
+            vec![
+                "2026-10-09T06:30:00Z",
+                "2026-10-13T06:00:00Z",
+                "2026-10-13T06:30:00Z",
+            ]
+        );
+    }
+
+    #[test]
+    fn next_occurrence__should_keep_the_local_time_across_daylight_saving() {
+        // Given
+        let mut daily = notification("2026-10-23T07:00:00Z", Repeat::Every("daily".into()));
+        daily.tz = Some("Europe/Berlin".to_string());
+
+        // Then
+        assert_eq!(
+            occurrences(&daily, "2026-10-24T08:00:00Z", 2),
+            vec!["2026-10-25T08:00:00Z", "2026-10-26T08:00:00Z"]
+        );
+    }
+
+    #[test]
+    fn next_occurrence__should_stop_at_until() {
+        // Given
+        let mut daily = notification("2026-10-01T09:00:00Z", Repeat::Every("daily".into()));
+        daily.until = Some(utc("2026-10-02T09:00:00Z"));
+
+        // Then
+        assert_eq!(
+            occurrences(&daily, "2026-09-01T00:00:00Z", 5),
+            vec!["2026-10-01T09:00:00Z", "2026-10-02T09:00:00Z"]
+        );
+    }
+
+    #[test]
+    fn next_occurrence__should_read_rrule_until_as_utc_unless_date_only() {
+        // Given
+        let mut instant = notification(
+            "2026-10-01T07:00:00Z",
+            Repeat::Rrule("FREQ=DAILY;UNTIL=20261002T073000Z".into()),
+        );
+        instant.tz = Some("Europe/Berlin".to_string());
+        let mut day = notification(
+            "2026-10-01T21:00:00Z",
+            Repeat::Rrule("FREQ=DAILY;UNTIL=20261002".into()),
+        );
+        day.tz = Some("Europe/Berlin".to_string());
+
+        // Then
+        assert_eq!(
+            occurrences(&instant, "2026-09-01T00:00:00Z", 5),
+            vec!["2026-10-01T07:00:00Z", "2026-10-02T07:00:00Z"]
+        );
+        assert_eq!(
+            occurrences(&day, "2026-09-01T00:00:00Z", 5),
+            vec!["2026-10-01T21:00:00Z", "2026-10-02T21:00:00Z"]
+        );
+    }
+
+    #[test]
+    fn parse_repeat__should_reject_unsupported_values() {
+        assert!(parse_repeat(&Repeat::Every("fortnightly".into())).is_err());
+        assert!(parse_repeat(&Repeat::Cron("0 9 * *".into())).is_err());
+        assert!(parse_repeat(&Repeat::Cron("0 9 * * 5-1".into())).is_err());
+        assert!(parse_repeat(&Repeat::Rrule("FREQ=SECONDLY".into())).is_err());
+        assert!(parse_repeat(&Repeat::Rrule("FREQ=MONTHLY;BYDAY=1MO".into())).is_err());
+        assert!(parse_repeat(&Repeat::Rrule("INTERVAL=2".into())).is_err());
+    }
+}
@@ -1,15 +1,28 @@
+use super::history::{DeliveryLog, DeliveryOutcome, DeliveryRecord, content_hash, notification_id};
+use super::prune::SubscriptionPruner;
+use super::recurrence::next_occurrence;
+use super::time_zone::{from_local, group_by_zone, parse_zone};
 use crate::ports::push::PushSender;
 use crate::ports::time::TimeProvider;
-use crate::types::directives::{DirectiveRegistries, Notification};
+use crate::types::directives::{
+    Channel, DeliveryTarget, DirectiveRegistries, Notification, TaskReminder,
+};
 
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use time::OffsetDateTime;
 use tokio::task::JoinHandle;
 
 pub(crate) struct ScheduledNotificationHandle {
+    /// See [`notification_id`].
+    pub(crate) id: String,
+    /// See [`schedule_key`].
+    key: String,
     pub(crate) notification: Notification,
     pub(crate) scheduled_at: OffsetDateTime,
+    /// The registries the task looks subscriptions up in, replaced on reload.
+    registries: Arc<Mutex<Arc<DirectiveRegistries>>>,
     handle: JoinHandle<()>,
 }
 
@@ -28,48 +41,200 @@ impl ScheduledNotificationHandle {
     }
 }
 
+/// Hours after midnight, in the assignee's time zone, at which a task is due
+/// on its due date.
+const TASK_DUE_HOUR: i64 = 9;
+
 #[derive(Debug, Clone)]
 pub(crate) struct PushScheduler<T, S> {
     time: T,
     sender: S,
+    /// How long before a task is due its reminder goes out.
+    task_reminder_lead: time::Duration,
+    log: DeliveryLog,
+    pruner: SubscriptionPruner,
 }
 
 impl<T, S> PushScheduler<T, S>
 where
     T: TimeProvider,
-    S: PushSender,
+    S: PushSender<Target = DeliveryTarget>,
 {
-    pub(crate) fn new(time: T, sender: S) -> Self {
-        Self { time, sender }
+    pub(crate) fn new(
+        time: T,
+        sender: S,
+        task_reminder_lead: time::Duration,
+        log: DeliveryLog,
+        pruner: SubscriptionPruner,
+    ) -> Self {
+        Self {
+            time,
+            sender,
+            task_reminder_lead,
+            log,
+            pruner,
+        }
     }
 
+    /// Spawns a task per `/notify` block and per due task reminder.
+    #[cfg(test)]
     pub(crate) fn spawn_all(
         &self,
         registries: Arc<DirectiveRegistries>,
     ) -> Vec<ScheduledNotificationHandle> {
-        registries
+        let mut handles = Vec::new();
+        self.reload(registries, &mut handles);
+        handles
+    }
+
+    /// Brings `handles` in line with `registries`: tasks whose notification
+    /// is gone or changed are aborted, new notifications get a task, and the
+    /// rest keep running against the new registries. Notifications are
+    /// matched by [`schedule_key`], so a task that is firing while its block
+    /// is unchanged is left alone.
+    ///
+    /// Past one-off notifications go out right away unless the delivery log
+    /// shows they were already handled. Task reminders whose time has passed
+    /// are skipped, so old tasks do not all remind at once.
+    pub(crate) fn reload(
+        &self,
+        registries: Arc<DirectiveRegistries>,
+        handles: &mut Vec<ScheduledNotificationHandle>,
+    ) {
+        let now = self.time.now();
+        let reminders: Vec<Notification> = registries
+            .task_reminders
+            .iter()
+            .flat_map(|reminder| {
+                group_by_zone(&reminder.to, &registries.users)
+                    .into_iter()
+                    .filter_map(|(tz, to)| {
+                        task_reminder_notification(reminder, self.task_reminder_lead, tz, to)
+                    })
+            })
+            .collect();
+        let current: Vec<(String, &Notification, bool)> = registries
             .notifications
             .iter()
-            .map(|notification| {
-                let time = self.time.clone();
-                let sender = self.sender.clone();
-                let registries = Arc::clone(&registries);
-                let notification = notification.clone();
-                let scheduled_at = time.now();
-                let notification_for_task = notification.clone();
-                let handle = tokio::spawn(async move {
-                    run_notification(time, sender, registries, notification_for_task).await;
-                });
-                ScheduledNotificationHandle {
-                    notification,
-                    scheduled_at,
-                    handle,
-                }
+            .map(|notification| (notification, false))
+            .chain(reminders.iter().map(|reminder| (reminder, true)))
+            .map(|(notification, is_reminder)| {
+                (schedule_key(notification), notification, is_reminder)
             })
-            .collect()
+            .collect();
+
+        let current_keys: HashSet<&str> = current.iter().map(|(key, _, _)| key.as_str()).collect();
+        handles.retain(|handle| {
+            if current_keys.contains(handle.key.as_str()) {
+                *handle.registries.lock().expect("registries lock") = Arc::clone(&registries);
+                true
+            } else {
+                handle.abort();
+                false
+            }
+        });
+
+        let running: HashSet<String> = handles.iter().map(|handle| handle.key.clone()).collect();
+        for (key, notification, is_reminder) in current {
+            if running.contains(&key) {
+                continue;
+            }
+            let id = notification_id(notification);
+            let pending = notification.repeat.is_some()
+                || notification.at > now
+                || (!is_reminder && !self.log.is_handled(&id, notification.at));
+            if pending {
+                handles.push(self.spawn(key, id, notification.clone(), &registries));
+            }
+        }
+    }
+
+    /// Calls `hook` with each document an expired subscription was removed
+    /// from, after the delivery that removed it.
+    pub(crate) fn on_pruned(&self, hook: impl Fn(&str) + Send + Sync + 'static) {
+        self.pruner.on_rewritten(hook);
+    }
+
+    /// Sends an occurrence right away, as [`deliver`] does.
+    pub(super) async fn deliver(
+        &self,
+        registries: &DirectiveRegistries,
+        delivery: Delivery<'_>,
+        attempted_at: OffsetDateTime,
+    ) {
+        deliver(
+            &self.sender,
+            registries,
+            &self.log,
+            &self.pruner,
+            delivery,
+            attempted_at,
+        )
+        .await;
+    }
+
+    fn spawn(
+        &self,
+        key: String,
+        id: String,
+        notification: Notification,
+        registries: &Arc<DirectiveRegistries>,
+    ) -> ScheduledNotificationHandle {
+        let registries = Arc::new(Mutex::new(Arc::clone(registries)));
+        let handle = tokio::spawn(run_notification(
+            self.time.clone(),
+            self.sender.clone(),
+            Arc::clone(&registries),
+            self.log.clone(),
+            self.pruner.clone(),
+            id.clone(),
+            notification.clone(),
+        ));
+        ScheduledNotificationHandle {
+            id,
+            key,
+            notification,
+            scheduled_at: self.time.now(),
+            registries,
+            handle,
+        }
     }
 }
 
+/// Identifies a notification across reloads by its document, block line and
+/// contents. Editing a block, or moving it to another line, changes its key.
+fn schedule_key(notification: &Notification) -> String {
+    format!(
+        "{}:{}#{:016x}",
+        notification.doc_id,
+        notification.line,
+        content_hash(notification)
+    )
+}
+
+/// The notification reminding `to`, who share the time zone `tz`, `lead`
+/// before the task is due.
+fn task_reminder_notification(
+    reminder: &TaskReminder,
+    lead: time::Duration,
+    tz: Option<String>,
+    to: Vec<String>,
+) -> Option<Notification> {
+    let zone = tz.as_deref().and_then(|tz| parse_zone(tz).ok());
+    let due_at = reminder.due.midnight() + time::Duration::hours(TASK_DUE_HOUR);
+    Some(Notification {
+        to,
+        at: from_local(due_at - lead, zone.as_ref())?,
+        message: reminder.message.clone(),
+        doc_id: reminder.doc_id.clone(),
+        line: reminder.line,
+        repeat: None,
+        until: None,
+        tz,
+        pending_local: None,
+    })
+}
+
 fn compute_delay<T: TimeProvider>(time: &T, at: OffsetDateTime) -> Option<Duration> {
     let now = time.now();
     let delay = at - now;
@@ -83,47 +248,200 @@ fn compute_delay<T: TimeProvider>(time: &T, at: OffsetDateTime) -> Option<Durati
     }
 }
 
+/// Sends `notification` when it is due. Repeating notifications then wait
+/// for their next occurrence until the schedule runs out; occurrences missed
+/// while the server was down or asleep are not sent.
 async fn run_notification<T, S>(
     time: T,
     sender: S,
-    registries: Arc<DirectiveRegistries>,
+    registries: Arc<Mutex<Arc<DirectiveRegistries>>>,
+    log: DeliveryLog,
+    pruner: SubscriptionPruner,
+    id: String,
     notification: Notification,
 ) where
     T: TimeProvider,
-    S: PushSender,
+    S: PushSender<Target = DeliveryTarget>,
 {
-    if let Some(delay) = compute_delay(&time, notification.at) {
-        time.sleep(delay).await;
+    let mut next = match notification.repeat {
+        None => Some(notification.at),
+        Some(_) => next_occurrence(&notification, time.now()),
+    };
+    while let Some(at) = next {
+        if let Some(delay) = compute_delay(&time, at) {
+            time.sleep(delay).await;
+        }
+        let delivery = Delivery {
+            notification_id: &id,
+            notification: &notification,
+            scheduled_for: at,
+        };
+        let current = Arc::clone(&registries.lock().expect("registries lock"));
+        deliver(&sender, &current, &log, &pruner, delivery, time.now()).await;
+        if notification.repeat.is_none() {
+            break;
+        }
+        next = next_occurrence(&notification, time.now().max(at));
     }
+}
+
+/// One occurrence of a notification.
+pub(super) struct Delivery<'a> {
+    pub(super) notification_id: &'a str,
+    pub(super) notification: &'a Notification,
+    pub(super) scheduled_for: OffsetDateTime,
+}
+
+/// Sends an occurrence to every delivery target of its recipients and records
+/// the outcome per recipient and endpoint. Subscriptions the push service
+/// reports as gone are pruned and skipped from then on.
+pub(super) async fn deliver<S: PushSender<Target = DeliveryTarget>>(
+    sender: &S,
+    registries: &DirectiveRegistries,
+    log: &DeliveryLog,
+    pruner: &SubscriptionPruner,
+    delivery: Delivery<'_>,
+    attempted_at: OffsetDateTime,
+) {
+    let notification = delivery.notification;
+    let record = |recipient: &str,
+                  endpoint: Option<&str>,
+                  outcome: DeliveryOutcome,
+                  error: Option<String>| DeliveryRecord {
+        notification_id: delivery.notification_id.to_string(),
+        doc_id: notification.doc_id.clone(),
+        scheduled_for: delivery.scheduled_for,
+        attempted_at,
+        recipient: recipient.to_string(),
+        endpoint: endpoint.map(str::to_string),
+        outcome,
+        error,
+        message: notification.message.clone(),
+    };
 
     for recipient in &notification.to {
-        let subscriptions = match registries.subscriptions.get(recipient) {
-            Some(subscriptions) => subscriptions,
-            None => {
-                eprintln!(
-                    "push delivery warning: no subscriptions for '{}' ({})",
-                    recipient, notification.doc_id
-                );
+        let channels: Vec<Channel> = recipient_channels(registries, recipient)
+            .iter()
+            .copied()
+            .filter(|&channel| sender.has_channel(channel))
+            .collect();
+        if channels.is_empty() {
+            // Nothing on this server can reach the recipient, e.g. web push
+            // without VAPID keys; that is not a failed delivery.
+            continue;
+        }
+        let targets = recipient_targets(registries, recipient, &channels);
+        if targets.is_empty() {
+            eprintln!(
+                "push delivery warning: no subscriptions for '{}' ({})",
+                recipient, notification.doc_id
+            );
+            log.append(&record(
+                recipient,
+                None,
+                DeliveryOutcome::NoSubscription,
+                None,
+            ));
+            continue;
+        }
+
+        for target in &targets {
+            if let DeliveryTarget::WebPush(subscription) = target
+                && pruner.is_pruned(&subscription.endpoint)
+            {
                 continue;
             }
-        };
+            let endpoint = Some(target.endpoint());
+            match (sender.send(target, &notification.message).await, target) {
+                (Ok(()), _) => log.append(&record(
+                    recipient,
+                    endpoint,
+                    DeliveryOutcome::Delivered,
+                    None,
+                )),
+                (Err(err), DeliveryTarget::WebPush(subscription)) if S::is_expired(&err) => {
+                    let action = pruner.prune(recipient, subscription);
+                    eprintln!(
+                        "push subscription expired: {} (user {}, doc {}); {}",
+                        err, recipient, subscription.doc_id, action
+                    );
+                    log.append(&record(
+                        recipient,
+                        endpoint,
+                        DeliveryOutcome::Expired,
+                        Some(format!("{err}; {action}")),
+                    ));
+                }
+                (Err(err), _) => {
+                    eprintln!(
+                        "push delivery error: {} (user {}, doc {})",
+                        err, recipient, notification.doc_id
+                    );
+                    log.append(&record(
+                        recipient,
+                        endpoint,
+                        DeliveryOutcome::Failed,
+                        Some(err.to_string()),
+                    ));
+                }
+            }
+        }
+    }
+    pruner.finish();
+}
 
-        for subscription in subscriptions {
-            if let Err(err) = sender.send(subscription, &notification.message).await {
-                eprintln!(
-                    "push delivery error: {} (user {}, doc {})",
-                    err, recipient, notification.doc_id
-                );
+/// The channels `recipient` selected in their `/user` block, web push when
+/// they did not choose any.
+fn recipient_channels<'a>(registries: &'a DirectiveRegistries, recipient: &str) -> &'a [Channel] {
+    registries
+        .users
+        .get(recipient)
+        .map(|user| user.channels.as_slice())
+        .filter(|channels| !channels.is_empty())
+        .unwrap_or(&[Channel::Webpush])
+}
+
+/// Where notifications for `recipient` go on each of `channels`, in order.
+fn recipient_targets(
+    registries: &DirectiveRegistries,
+    recipient: &str,
+    channels: &[Channel],
+) -> Vec<DeliveryTarget> {
+    let user = registries.users.get(recipient);
+    let mut targets = Vec::new();
+    for &channel in channels {
+        match channel {
+            Channel::Webpush => {
+                if let Some(subscriptions) = registries.subscriptions.get(recipient) {
+                    targets.extend(subscriptions.iter().cloned().map(DeliveryTarget::WebPush));
+                }
+            }
+            Channel::Webhook => {
+                if let Some(url) = user.and_then(|user| user.webhook.clone()) {
+                    targets.push(DeliveryTarget::Webhook(url));
+                }
+            }
+            Channel::Ntfy => {
+                if let Some(url) = user.and_then(|user| user.ntfy.clone()) {
+                    targets.push(DeliveryTarget::Ntfy(url));
+                }
+            }
+            Channel::Email => {
+                if let Some(user) = user {
+                    targets.push(DeliveryTarget::Email(user.email.clone()));
+                }
             }
         }
     }
+    targets
 }
 
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod tests {
     use super::*;
-    use crate::types::directives::Subscription;
+    use crate::test_support::create_temp_root;
+    use crate::types::directives::{Subscription, User};
     use std::future::Future;
     use std::pin::Pin;
     use std::sync::Mutex;
@@ -207,22 +525,33 @@ mod tests {
     #[derive(Clone, Default)]
     struct TestSender {
         sent: Arc<Mutex<Vec<(String, String)>>>,
+        /// Endpoints that fail as if the push service answered 410 Gone.
+        gone: Arc<Mutex<Vec<String>>>,
     }
 
     impl PushSender for TestSender {
+        type Target = DeliveryTarget;
         type Error = TestSendError;
         type Fut<'a>
             = std::future::Ready<Result<(), Self::Error>>
         where
             Self: 'a;
 
-        fn send<'a>(&'a self, subscription: &'a Subscription, message: &'a str) -> Self::Fut<'a> {
+        fn send<'a>(&'a self, target: &'a DeliveryTarget, message: &'a str) -> Self::Fut<'a> {
+            let endpoint = target.endpoint().to_string();
+            if self.gone.lock().expect("gone lock").contains(&endpoint) {
+                return std::future::ready(Err(TestSendError));
+            }
             self.sent
                 .lock()
                 .expect("sent lock")
-                .push((subscription.endpoint.clone(), message.to_string()));
+                .push((endpoint, message.to_string()));
             std::future::ready(Ok(()))
         }
+
+        fn is_expired(_error: &Self::Error) -> bool {
+            true
+        }
     }
 
     #[test]
@@ -269,6 +598,7 @@ mod tests {
     #[tokio::test]
     async fn scheduler__should_wait_and_send() {
         // Given
+        let root = create_temp_root("scheduler-wait");
         let now = OffsetDateTime::parse("2025-01-12T09:30:00Z", &Rfc3339).expect("parse now");
         let time = TestTime::new(now);
         let sender = TestSender::default();
@@ -277,6 +607,11 @@ mod tests {
             at: now + time::Duration::seconds(30),
             message: "Hello".to_string(),
             doc_id: "note.md".to_string(),
+            line: 1,
+            repeat: None,
+            until: None,
+            tz: None,
+            pending_local: None,
         };
         let mut registries = DirectiveRegistries::default();
         registries.subscriptions.insert(
@@ -285,12 +620,19 @@ mod tests {
                 endpoint: "https://push.example/123".to_string(),
                 p256dh: "p256".to_string(),
                 auth: "auth".to_string(),
+                doc_id: "devices.md".to_string(),
             }],
         );
         registries.notifications.push(notification);
 
         // When
-        let scheduler = PushScheduler::new(time.clone(), sender.clone());
+        let scheduler = PushScheduler::new(
+            time.clone(),
+            sender.clone(),
+            time::Duration::ZERO,
+            DeliveryLog::new(&root),
+            SubscriptionPruner::new(&root),
+        );
         let handles = scheduler.spawn_all(Arc::new(registries));
         tokio::task::yield_now().await;
 
@@ -313,11 +655,14 @@ mod tests {
         assert_eq!(sent.len(), 1);
         assert_eq!(sent[0].0, "https://push.example/123");
         assert_eq!(sent[0].1, "Hello");
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
     }
 
     #[tokio::test]
     async fn scheduler__should_send_immediately_for_past_notification() {
         // Given
+        let root = create_temp_root("scheduler-past");
         let now = OffsetDateTime::parse("2025-01-12T09:30:00Z", &Rfc3339).expect("parse now");
         let time = TestTime::new(now);
         let sender = TestSender::default();
@@ -326,6 +671,11 @@ mod tests {
             at: now - time::Duration::seconds(5),
             message: "Immediate".to_string(),
             doc_id: "note.md".to_string(),
+            line: 1,
+            repeat: None,
+            until: None,
+            tz: None,
+            pending_local: None,
         };
         let mut registries = DirectiveRegistries::default();
         registries.subscriptions.insert(
@@ -334,12 +684,19 @@ mod tests {
                 endpoint: "https://push.example/123".to_string(),
                 p256dh: "p256".to_string(),
                 auth: "auth".to_string(),
+                doc_id: "devices.md".to_string(),
             }],
         );
         registries.notifications.push(notification);
 
         // When
-        let scheduler = PushScheduler::new(time.clone(), sender.clone());
+        let scheduler = PushScheduler::new(
+            time.clone(),
+            sender.clone(),
+            time::Duration::ZERO,
+            DeliveryLog::new(&root),
+            SubscriptionPruner::new(&root),
+        );
         let handles = scheduler.spawn_all(Arc::new(registries));
         for handle in handles {
             handle.join().await.expect("join handle");
@@ -350,5 +707,516 @@ mod tests {
         let sent = sender.sent.lock().expect("sent lock");
         assert_eq!(sent.len(), 1);
         assert_eq!(sent[0].1, "Immediate");
+        let records = DeliveryLog::new(&root).records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].outcome, DeliveryOutcome::Delivered);
+        assert_eq!(
+            records[0].endpoint.as_deref(),
+            Some("https://push.example/123")
+        );
+        assert_eq!(records[0].scheduled_for, now - time::Duration::seconds(5));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn scheduler__should_not_resend_past_notification_after_restart() {
+        // Given
+        let root = create_temp_root("scheduler-restart");
+        let now = OffsetDateTime::parse("2025-01-12T09:30:00Z", &Rfc3339).expect("parse now");
+        let time = TestTime::new(now);
+        let sender = TestSender::default();
+        let mut registries = DirectiveRegistries::default();
+        registries.notifications.push(Notification {
+            to: vec!["marten".to_string()],
+            at: now - time::Duration::hours(1),
+            message: "Once".to_string(),
+            doc_id: "note.md".to_string(),
+            line: 1,
+            repeat: None,
+            until: None,
+            tz: None,
+            pending_local: None,
+        });
+        let registries = Arc::new(registries);
+        let first = PushScheduler::new(
+            time.clone(),
+            sender.clone(),
+            time::Duration::ZERO,
+            DeliveryLog::new(&root),
+            SubscriptionPruner::new(&root),
+        );
+        for handle in first.spawn_all(Arc::clone(&registries)) {
+            handle.join().await.expect("join handle");
+        }
+
+        // When
+        let restarted = PushScheduler::new(
+            time.clone(),
+            sender.clone(),
+            time::Duration::ZERO,
+            DeliveryLog::new(&root),
+            SubscriptionPruner::new(&root),
+        );
+        let handles = restarted.spawn_all(registries);
+
+        // Then
+        assert!(handles.is_empty());
+        let records = DeliveryLog::new(&root).records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].outcome, DeliveryOutcome::NoSubscription);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn scheduler__should_prune_subscriptions_whose_endpoint_is_gone() {
+        // Given
+        let root = create_temp_root("scheduler-prune");
+        let block = |endpoint: &str| {
+            format!(
+                "/subscription\n```toml\nuser = \"marten\"\nendpoint = \"{endpoint}\"\np256dh = \"p\"\nauth = \"a\"\n```\n"
+            )
+        };
+        std::fs::write(
+            root.join("devices.md"),
+            format!(
+                "{}\n{}",
+                block("https://push.example/old"),
+                block("https://push.example/new")
+            ),
+        )
+        .expect("write devices.md");
+        let now = OffsetDateTime::parse("2025-01-12T09:30:00Z", &Rfc3339).expect("parse now");
+        let time = TestTime::new(now);
+        let sender = TestSender::default();
+        sender
+            .gone
+            .lock()
+            .expect("gone lock")
+            .push("https://push.example/old".to_string());
+        let loaded = DirectiveRegistries::load(&root).expect("load registries");
+        let with_message = |message: &str| {
+            let mut registries = loaded.clone();
+            registries.notifications.push(Notification {
+                to: vec!["marten".to_string()],
+                at: now,
+                message: message.to_string(),
+                doc_id: "note.md".to_string(),
+                line: 1,
+                repeat: None,
+                until: None,
+                tz: None,
+                pending_local: None,
+            });
+            Arc::new(registries)
+        };
+        let scheduler = PushScheduler::new(
+            time.clone(),
+            sender.clone(),
+            time::Duration::ZERO,
+            DeliveryLog::new(&root),
+            SubscriptionPruner::new(&root),
+        );
+        let rewritten = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&rewritten);
+        scheduler.on_pruned(move |doc_id| {
+            recorded
+                .lock()
+                .expect("rewritten lock")
+                .push(doc_id.to_string());
+        });
+
+        // When
+        for message in ["First", "Second"] {
+            for handle in scheduler.spawn_all(with_message(message)) {
+                handle.join().await.expect("join handle");
+            }
+        }
+
+        // Then
+        let sent = sender.sent.lock().expect("sent lock").clone();
+        assert_eq!(
+            sent,
+            vec![
+                ("https://push.example/new".to_string(), "First".to_string()),
+                ("https://push.example/new".to_string(), "Second".to_string()),
+            ]
+        );
+        let expired: Vec<DeliveryRecord> = DeliveryLog::new(&root)
+            .records()
+            .into_iter()
+            .filter(|record| record.outcome == DeliveryOutcome::Expired)
+            .collect();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(
+            expired[0].error.as_deref(),
+            Some("test send error; removed its /subscription block from devices.md")
+        );
+        let reloaded = DirectiveRegistries::load(&root).expect("reload registries");
+        let endpoints: Vec<&str> = reloaded.subscriptions["marten"]
+            .iter()
+            .map(|subscription| subscription.endpoint.as_str())
+            .collect();
+        assert_eq!(endpoints, vec!["https://push.example/new"]);
+        let devices = std::fs::read_to_string(root.join("devices.md")).expect("read devices.md");
+        assert!(devices.starts_with("<!-- expired push subscription for marten removed on "));
+        assert_eq!(
+            *rewritten.lock().expect("rewritten lock"),
+            vec!["devices.md"]
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn reload__should_only_replace_added_changed_and_removed_notifications() {
+        // Given
+        let root = create_temp_root("scheduler-reload");
+        let now = OffsetDateTime::parse("2026-10-19T08:00:00Z", &Rfc3339).expect("parse now");
+        let time = TestTime::new(now);
+        let sender = TestSender::default();
+        let notification = |line: usize, minutes: i64, message: &str| Notification {
+            to: vec!["marten".to_string()],
+            at: now + time::Duration::minutes(minutes),
+            message: message.to_string(),
+            doc_id: "note.md".to_string(),
+            line,
+            repeat: None,
+            until: None,
+            tz: None,
+            pending_local: None,
+        };
+        let registries = |endpoint: &str, notifications: Vec<Notification>| {
+            let mut registries = DirectiveRegistries::default();
+            registries.subscriptions.insert(
+                "marten".to_string(),
+                vec![Subscription {
+                    endpoint: endpoint.to_string(),
+                    p256dh: "p256".to_string(),
+                    auth: "auth".to_string(),
+                    doc_id: "devices.md".to_string(),
+                }],
+            );
+            registries.notifications = notifications;
+            Arc::new(registries)
+        };
+        let scheduler = PushScheduler::new(
+            time.clone(),
+            sender.clone(),
+            time::Duration::ZERO,
+            DeliveryLog::new(&root),
+            SubscriptionPruner::new(&root),
+        );
+        let mut handles = scheduler.spawn_all(registries(
+            "https://push.example/old",
+            vec![
+                notification(2, 10, "Keep"),
+                notification(8, 20, "Change"),
+                notification(14, 30, "Remove"),
+            ],
+        ));
+        while time.sleep_durations().len() < 3 {
+            tokio::task::yield_now().await;
+        }
+
+        // When
+        scheduler.reload(
+            registries(
+                "https://push.example/new",
+                vec![
+                    notification(2, 10, "Keep"),
+                    notification(8, 20, "Changed"),
+                    notification(20, 40, "Added"),
+                ],
+            ),
+            &mut handles,
+        );
+        while time.sleep_durations().len() < 5 {
+            tokio::task::yield_now().await;
+        }
+
+        // Then
+        let messages: Vec<&str> = handles
+            .iter()
+            .map(|handle| handle.notification.message.as_str())
+            .collect();
+        assert_eq!(messages, vec!["Keep", "Changed", "Added"]);
+        assert_eq!(
+            time.sleep_durations(),
+            [10, 20, 30, 20, 40]
+                .iter()
+                .map(|minutes| Duration::from_secs(minutes * 60))
+                .collect::<Vec<_>>()
+        );
+
+        // When
+        time.trigger_all();
+        for handle in handles {
+            handle.join().await.expect("join handle");
+        }
+
+        // Then
+        let mut sent = sender.sent.lock().expect("sent lock").clone();
+        sent.sort();
+        assert_eq!(
+            sent,
+            vec![
+                ("https://push.example/new".to_string(), "Added".to_string()),
+                (
+                    "https://push.example/new".to_string(),
+                    "Changed".to_string()
+                ),
+                ("https://push.example/new".to_string(), "Keep".to_string()),
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn deliver__should_use_the_channels_of_the_user() {
+        // Given
+        let root = create_temp_root("scheduler-channels");
+        let sender = TestSender::default();
+        let mut registries = DirectiveRegistries::default();
+        registries.subscriptions.insert(
+            "marten".to_string(),
+            vec![Subscription {
+                endpoint: "https://push.example/123".to_string(),
+                p256dh: "p256".to_string(),
+                auth: "auth".to_string(),
+                doc_id: "devices.md".to_string(),
+            }],
+        );
+        registries.users.insert(
+            "marten".to_string(),
+            User {
+                name: "marten".to_string(),
+                display_name: None,
+                email: "marten@example.com".to_string(),
+                password_hash: "hash".to_string(),
+                tz: None,
+                channels: vec![Channel::Email, Channel::Webhook, Channel::Webpush],
+                webhook: Some("https://hooks.example/marten".to_string()),
+                ntfy: None,
+            },
+        );
+        let now = OffsetDateTime::parse("2026-10-19T09:00:00Z", &Rfc3339).expect("parse now");
+        let notification = Notification {
+            to: vec!["marten".to_string()],
+            at: now,
+            message: "Hello".to_string(),
+            doc_id: "note.md".to_string(),
+            line: 1,
+            repeat: None,
+            until: None,
+            tz: None,
+            pending_local: None,
+        };
+        let delivery = Delivery {
+            notification_id: "note.md#1",
+            notification: &notification,
+            scheduled_for: now,
+        };
+
+        // When
+        deliver(
+            &sender,
+            &registries,
+            &DeliveryLog::new(&root),
+            &SubscriptionPruner::new(&root),
+            delivery,
+            now,
+        )
+        .await;
+
+        // Then
+        let endpoints: Vec<String> = sender
+            .sent
+            .lock()
+            .expect("sent lock")
+            .iter()
+            .map(|(endpoint, _)| endpoint.clone())
+            .collect();
+        assert_eq!(
+            endpoints,
+            vec![
+                "marten@example.com",
+                "https://hooks.example/marten",
+                "https://push.example/123"
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn scheduler__should_remind_lead_time_before_a_task_is_due() {
+        // Given
+        let root = create_temp_root("scheduler-reminders");
+        let now = OffsetDateTime::parse("2026-10-30T12:00:00Z", &Rfc3339).expect("parse now");
+        let time = TestTime::new(now);
+        let sender = TestSender::default();
+        let reminder = |due: &str, message: &str| TaskReminder {
+            to: vec!["marten".to_string(), "ana".to_string()],
+            due: time::Date::parse(due, &time::format_description::well_known::Iso8601::DATE)
+                .expect("parse due"),
+            message: message.to_string(),
+            doc_id: "chores.md".to_string(),
+            task_index: 0,
+            line: 1,
+        };
+        let mut registries = DirectiveRegistries::default();
+        registries.subscriptions.insert(
+            "marten".to_string(),
+            vec![Subscription {
+                endpoint: "https://push.example/123".to_string(),
+                p256dh: "p256".to_string(),
+                auth: "auth".to_string(),
+                doc_id: "devices.md".to_string(),
+            }],
+        );
+        registries.users.insert(
+            "ana".to_string(),
+            User {
+                name: "ana".to_string(),
+                display_name: None,
+                email: "ana@example.com".to_string(),
+                password_hash: "hash".to_string(),
+                tz: Some("Europe/Berlin".to_string()),
+                channels: Vec::new(),
+                webhook: None,
+                ntfy: None,
+            },
+        );
+        registries
+            .task_reminders
+            .push(reminder("2026-11-01", "Due 2026-11-01: pay rent @marten"));
+        registries
+            .task_reminders
+            .push(reminder("2026-10-31", "Due 2026-10-31: already reminded"));
+
+        // When
+        let scheduler = PushScheduler::new(
+            time.clone(),
+            sender.clone(),
+            time::Duration::days(1),
+            DeliveryLog::new(&root),
+            SubscriptionPruner::new(&root),
+        );
+        let handles = scheduler.spawn_all(Arc::new(registries));
+        tokio::task::yield_now().await;
+
+        // Then
+        let scheduled: Vec<(Vec<String>, OffsetDateTime)> = handles
+            .iter()
+            .map(|handle| (handle.notification.to.clone(), handle.notification.at))
+            .collect();
+        assert_eq!(
+            scheduled,
+            vec![
+                (
+                    vec!["marten".to_string()],
+                    OffsetDateTime::parse("2026-10-31T09:00:00Z", &Rfc3339).expect("parse")
+                ),
+                (
+                    vec!["ana".to_string()],
+                    OffsetDateTime::parse("2026-10-31T08:00:00Z", &Rfc3339).expect("parse")
+                ),
+            ]
+        );
+        let mut durations = time.sleep_durations();
+        durations.sort();
+        assert_eq!(
+            durations,
+            vec![
+                Duration::from_secs(20 * 60 * 60),
+                Duration::from_secs(21 * 60 * 60)
+            ]
+        );
+
+        // When
+        time.trigger_all();
+        for handle in handles {
+            handle.join().await.expect("join handle");
+        }
+
+        // Then
+        let sent = sender.sent.lock().expect("sent lock");
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].1, "Due 2026-11-01: pay rent @marten");
+        drop(sent);
+        let ana_records: Vec<DeliveryOutcome> = DeliveryLog::new(&root)
+            .records()
+            .into_iter()
+            .filter(|record| record.recipient == "ana")
+            .map(|record| record.outcome)
+            .collect();
+        assert_eq!(ana_records, vec![DeliveryOutcome::NoSubscription]);
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[tokio::test]
+    async fn scheduler__should_reschedule_repeating_notification_until_it_ends() {
+        // Given
+        let root = create_temp_root("scheduler-repeat");
+        let now = OffsetDateTime::parse("2026-10-19T08:00:00Z", &Rfc3339).expect("parse now");
+        let time = TestTime::new(now);
+        let sender = TestSender::default();
+        let mut registries = DirectiveRegistries::default();
+        registries.subscriptions.insert(
+            "marten".to_string(),
+            vec![Subscription {
+                endpoint: "https://push.example/123".to_string(),
+                p256dh: "p256".to_string(),
+                auth: "auth".to_string(),
+                doc_id: "devices.md".to_string(),
+            }],
+        );
+        registries.notifications.push(Notification {
+            to: vec!["marten".to_string()],
+            at: OffsetDateTime::parse("2026-10-01T09:00:00Z", &Rfc3339).expect("parse at"),
+            message: "Stand-up".to_string(),
+            doc_id: "note.md".to_string(),
+            line: 1,
+            repeat: Some(crate::types::directives::Repeat::Every("daily".to_string())),
+            until: Some(OffsetDateTime::parse("2026-10-20T12:00:00Z", &Rfc3339).expect("until")),
+            tz: None,
+            pending_local: None,
+        });
+
+        // When
+        let scheduler = PushScheduler::new(
+            time.clone(),
+            sender.clone(),
+            time::Duration::ZERO,
+            DeliveryLog::new(&root),
+            SubscriptionPruner::new(&root),
+        );
+        let mut handles = scheduler.spawn_all(Arc::new(registries));
+        let handle = handles.pop().expect("handle");
+        for expected_sleeps in 1..=2 {
+            while time.sleep_durations().len() < expected_sleeps {
+                tokio::task::yield_now().await;
+            }
+            time.trigger_all();
+        }
+        handle.join().await.expect("join handle");
+
+        // Then
+        assert_eq!(
+            time.sleep_durations(),
+            vec![
+                Duration::from_secs(60 * 60),
+                Duration::from_secs(25 * 60 * 60)
+            ]
+        );
+        let sent = sender.sent.lock().expect("sent lock");
+        assert_eq!(sent.len(), 2);
+        assert!(sent.iter().all(|(_, message)| message == "Stand-up"));
+
+        std::fs::remove_dir_all(&root).expect("cleanup");
     }
 }
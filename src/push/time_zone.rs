@@ -0,0 +1,128 @@
+use crate::types::directives::User;
+
+use std::collections::HashMap;
+use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+/// Looks up an IANA time zone name in the bundled database.
+pub(crate) fn parse_zone(name: &str) -> Result<jiff::tz::TimeZone, String> {
+    jiff::tz::TimeZone::get(name.trim()).map_err(|_| format!("unknown time zone '{name}'"))
+}
+
+/// The wall clock time in `zone` at `at`; UTC when there is no zone.
+pub(crate) fn to_local(at: OffsetDateTime, zone: Option<&jiff::tz::TimeZone>) -> PrimitiveDateTime {
+    let local = at.to_offset(offset_at(at, zone));
+    PrimitiveDateTime::new(local.date(), local.time())
+}
+
+/// The instant a wall clock time in `zone` refers to; UTC when there is no
+/// zone. Times skipped by a transition move forward and repeated times take
+/// the earlier offset.
+pub(crate) fn from_local(
+    local: PrimitiveDateTime,
+    zone: Option<&jiff::tz::TimeZone>,
+) -> Option<OffsetDateTime> {
+    let Some(zone) = zone else {
+        return Some(local.assume_utc());
+    };
+    let civil = jiff::civil::DateTime::new(
+        i16::try_from(local.year()).ok()?,
+        u8::from(local.month()) as i8,
+        local.day() as i8,
+        local.hour() as i8,
+        local.minute() as i8,
+        local.second() as i8,
+        local.nanosecond() as i32,
+    )
+    .ok()?;
+    let timestamp = zone
+        .to_ambiguous_timestamp(civil)
+        .compatible()
+        .ok()?
+        .as_nanosecond();
+    OffsetDateTime::from_unix_timestamp_nanos(timestamp).ok()
+}
+
+/// `at` formatted as RFC 3339 with the offset of `zone`, or in UTC.
+pub(crate) fn format_local(at: OffsetDateTime, zone: Option<&str>) -> String {
+    let zone = zone.and_then(|name| parse_zone(name).ok());
+    at.to_offset(offset_at(at, zone.as_ref()))
+        .format(&Rfc3339)
+        .unwrap_or_else(|_| at.to_string())
+}
+
+/// Splits `recipients` by the `tz` of their `/user` block, keeping their
+/// order. Recipients without a user or zone share the `None` group.
+pub(crate) fn group_by_zone(
+    recipients: &[String],
+    users: &HashMap<String, User>,
+) -> Vec<(Option<String>, Vec<String>)> {
+    let mut groups: Vec<(Option<String>, Vec<String>)> = Vec::new();
+    for recipient in recipients {
+        let zone = users.get(recipient).and_then(|user| user.tz.clone());
+        match groups.iter_mut().find(|(group, _)| *group == zone) {
+            Some((_, members)) => members.push(recipient.clone()),
+            None => groups.push((zone, vec![recipient.clone()])),
+        }
+    }
+    groups
+}
+
+fn offset_at(at: OffsetDateTime, zone: Option<&jiff::tz::TimeZone>) -> UtcOffset {
+    let Some(zone) = zone else {
+        return UtcOffset::UTC;
+    };
+    let Ok(timestamp) = jiff::Timestamp::from_nanosecond(at.unix_timestamp_nanos()) else {
+        return UtcOffset::UTC;
+    };
+    UtcOffset::from_whole_seconds(zone.to_offset(timestamp).seconds()).unwrap_or(UtcOffset::UTC)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    fn utc(value: &str) -> OffsetDateTime {
+        OffsetDateTime::parse(value, &Rfc3339).expect("parse time")
+    }
+
+    #[test]
+    fn from_local__should_follow_daylight_saving_changes() {
+        // Given
+        let berlin = parse_zone("Europe/Berlin").expect("zone");
+        let summer = to_local(utc("2026-07-01T00:00:00Z"), None).replace_hour(9);
+        let winter = to_local(utc("2026-12-01T00:00:00Z"), None).replace_hour(9);
+        // 02:30 on 2026-03-29 does not exist in Berlin.
+        let gap = to_local(utc("2026-03-29T00:00:00Z"), None)
+            .replace_hour(2)
+            .and_then(|local| local.replace_minute(30));
+
+        // Then
+        assert_eq!(
+            from_local(summer.expect("hour"), Some(&berlin)),
+            Some(utc("2026-07-01T07:00:00Z"))
+        );
+        assert_eq!(
+            from_local(winter.expect("hour"), Some(&berlin)),
+            Some(utc("2026-12-01T08:00:00Z"))
+        );
+        assert_eq!(
+            from_local(gap.expect("time"), Some(&berlin)),
+            Some(utc("2026-03-29T01:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn format_local__should_use_the_zone_offset() {
+        assert_eq!(
+            format_local(utc("2026-10-19T07:00:00Z"), Some("Europe/Berlin")),
+            "2026-10-19T09:00:00+02:00"
+        );
+        assert_eq!(
+            format_local(utc("2026-10-19T07:00:00Z"), None),
+            "2026-10-19T07:00:00Z"
+        );
+        assert!(parse_zone("Mars/Olympus").is_err());
+    }
+}
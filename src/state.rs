@@ -1,5 +1,8 @@
+use crate::adapters::ConfiguredAgent;
+use crate::agent as agent_service;
 use crate::auth;
 use crate::config::AppConfig;
+use crate::documents::SearchIndex;
 use crate::push as push_service;
 use crate::types::directives;
 
@@ -12,5 +15,10 @@ pub struct AppState {
     pub auth: Option<auth::AuthState>,
     pub registries: Arc<Mutex<directives::DirectiveRegistries>>,
     pub push_handles: Arc<Mutex<Vec<push_service::ScheduledNotificationHandle>>>,
+    pub delivery_log: push_service::history::DeliveryLog,
+    /// `None` when no notification sender could be set up.
+    pub push_scheduler: Option<push_service::Scheduler>,
+    pub search_index: Arc<Mutex<SearchIndex>>,
     pub git_dir: Option<PathBuf>,
+    pub agent: Option<agent_service::AgentRunner<ConfiguredAgent>>,
 }
@@ -1,6 +1,7 @@
 use askama::Template;
 use askama_web::WebTemplate;
 
+pub(crate) use crate::documents::Backlink;
 pub(crate) use crate::documents::FileKind;
 
 pub(crate) struct BreadcrumbSegment {
@@ -10,6 +11,7 @@ pub(crate) struct BreadcrumbSegment {
 
 pub(crate) struct DirectoryFileEntry {
     pub(crate) name: String,
+    pub(crate) title: Option<String>,
     pub(crate) kind: FileKind,
     pub(crate) url: String,
 }
@@ -57,6 +59,7 @@ pub(crate) struct DocumentTemplate {
     pub(crate) doc_name: String,
     pub(crate) breadcrumbs: Vec<BreadcrumbSegment>,
     pub(crate) content: String,
+    pub(crate) backlinks: Vec<Backlink>,
     pub(crate) has_mermaid: bool,
     pub(crate) has_abc: bool,
     pub(crate) has_code: bool,
@@ -111,9 +114,19 @@ pub(crate) struct AgentViewTemplate {
     pub(crate) doc_id: String,
     pub(crate) blocks: Vec<AgentBlock>,
     pub(crate) line_count: usize,
+    /// Whether accepting an edit also applies it, see `--apply-magent-edits`.
+    pub(crate) apply_edits: bool,
     pub(crate) git_enabled: bool,
 }
 
+/// The agent view's blocks alone, pushed to open agent views when the
+/// document changes.
+#[derive(Template)]
+#[template(path = "agent_blocks.html")]
+pub(crate) struct AgentBlocksTemplate {
+    pub(crate) blocks: Vec<AgentBlock>,
+}
+
 pub(crate) struct AgentBlock {
     pub(crate) html: String,
     pub(crate) start_line: usize,
@@ -162,11 +175,57 @@ pub(crate) struct SearchTemplate {
     pub(crate) app_name: String,
     pub(crate) query: String,
     pub(crate) results: Vec<SearchResult>,
+    /// Whether the results were cut off at the search limit.
+    pub(crate) truncated: bool,
     pub(crate) git_enabled: bool,
 }
 
 pub(crate) use crate::documents::SearchResult;
 
+#[derive(Template, WebTemplate)]
+#[template(path = "broken_links.html")]
+pub(crate) struct BrokenLinksTemplate {
+    pub(crate) app_name: String,
+    pub(crate) links: Vec<BrokenLink>,
+    pub(crate) git_enabled: bool,
+}
+
+pub(crate) use crate::documents::BrokenLink;
+
+#[derive(Template, WebTemplate)]
+#[template(path = "tags.html")]
+pub(crate) struct TagsTemplate {
+    pub(crate) app_name: String,
+    pub(crate) tags: Vec<TagCount>,
+    pub(crate) git_enabled: bool,
+}
+
+#[derive(Template, WebTemplate)]
+#[template(path = "tag.html")]
+pub(crate) struct TagTemplate {
+    pub(crate) app_name: String,
+    pub(crate) tag: String,
+    pub(crate) documents: Vec<TaggedDocument>,
+    pub(crate) git_enabled: bool,
+}
+
+pub(crate) use crate::documents::{TagCount, TaggedDocument};
+
+#[derive(Template, WebTemplate)]
+#[template(path = "tasks.html")]
+pub(crate) struct TaskBoardTemplate {
+    pub(crate) app_name: String,
+    pub(crate) tasks: Vec<BoardTask>,
+    pub(crate) options: TaskFilterOptions,
+    pub(crate) folder: String,
+    pub(crate) assignee: String,
+    pub(crate) status: &'static str,
+    pub(crate) tag: String,
+    pub(crate) git_enabled: bool,
+}
+
+pub(crate) use crate::documents::{BoardTask, TaskFilterOptions};
+
 #[derive(Template, WebTemplate)]
 #[template(path = "push_subscribe.html")]
 pub(crate) struct PushSubscribeTemplate {
@@ -174,6 +233,19 @@ pub(crate) struct PushSubscribeTemplate {
     pub(crate) git_enabled: bool,
 }
 
+#[derive(Template, WebTemplate)]
+#[template(path = "push_history.html")]
+pub(crate) struct PushHistoryTemplate {
+    pub(crate) app_name: String,
+    /// Newest first.
+    pub(crate) records: Vec<DeliveryRecord>,
+    /// `before` for the next page, when there may be older records.
+    pub(crate) older: Option<String>,
+    pub(crate) git_enabled: bool,
+}
+
+pub(crate) use crate::push::history::DeliveryRecord;
+
 #[derive(Template, WebTemplate)]
 #[template(path = "upload.html")]
 pub(crate) struct UploadTemplate {
@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use crate::documents::SearchIndex;
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 pub(crate) fn create_temp_root(test_name: &str) -> PathBuf {
     let mut root = std::env::temp_dir();
@@ -10,3 +13,130 @@ pub(crate) fn create_temp_root(test_name: &str) -> PathBuf {
     std::fs::create_dir_all(&root).expect("create temp dir");
     root
 }
+
+/// Writes each `(path, contents)` pair under `root`, creating folders as needed.
+pub(crate) fn write_files(root: &Path, files: &[(&str, &str)]) {
+    for (path, contents) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().expect("parent")).expect("mkdir");
+        std::fs::write(path, contents).expect("write");
+    }
+}
+
+/// A temp root holding `files`, and the search index built from it.
+pub(crate) fn build_index(name: &str, files: &[(&str, &str)]) -> (PathBuf, SearchIndex) {
+    let root = create_temp_root(name);
+    write_files(&root, files);
+    let index = SearchIndex::build(&root).expect("build index");
+    (root, index)
+}
+
+/// Builds a minimal PDF with one line of Helvetica text per page.
+pub(crate) fn minimal_pdf(pages: &[&str]) -> Vec<u8> {
+    let page_count = pages.len();
+    let font_id = 3 + 2 * page_count;
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {page_count} >>",
+            (0..page_count)
+                .map(|idx| format!("{} 0 R", 3 + 2 * idx))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+    ];
+    for (idx, text) in pages.iter().enumerate() {
+        let content = format!("BT /F1 12 Tf 72 720 Td ({text}) Tj ET");
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+             /Resources << /Font << /F1 {font_id} 0 R >> >> /Contents {} 0 R >>",
+            4 + 2 * idx
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{content}\nendstream",
+            content.len()
+        ));
+    }
+    objects.push(
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    );
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    for (idx, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{object}\nendobj\n", idx + 1));
+    }
+    let xref_offset = pdf.len();
+    pdf.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    ));
+    for offset in offsets {
+        pdf.push_str(&format!("{offset:010} 00000 n \n"));
+    }
+    pdf.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+        objects.len() + 1
+    ));
+    pdf.into_bytes()
+}
+
+/// A request received by [`spawn_recording_server`].
+#[derive(Debug, Clone)]
+pub(crate) struct RecordedRequest {
+    pub(crate) path: String,
+    pub(crate) content_type: Option<String>,
+    pub(crate) authorization: Option<String>,
+    pub(crate) body: String,
+}
+
+/// Starts an HTTP server on a free local port that answers every request
+/// with `status` and records it. Returns the server's base URL.
+pub(crate) async fn spawn_recording_server(
+    status: axum::http::StatusCode,
+) -> (String, Arc<Mutex<Vec<RecordedRequest>>>) {
+    spawn_responding_server(status, String::new()).await
+}
+
+/// Like [`spawn_recording_server`], answering every request with `body` too.
+pub(crate) async fn spawn_responding_server(
+    status: axum::http::StatusCode,
+    body: String,
+) -> (String, Arc<Mutex<Vec<RecordedRequest>>>) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&requests);
+    let app = axum::Router::new().fallback(
+        move |uri: axum::http::Uri, headers: axum::http::HeaderMap, request_body: String| {
+            let recorded = Arc::clone(&recorded);
+            let body = body.clone();
+            async move {
+                let header = |name| {
+                    headers
+                        .get(name)
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string)
+                };
+                recorded
+                    .lock()
+                    .expect("requests lock")
+                    .push(RecordedRequest {
+                        path: uri.path().to_string(),
+                        content_type: header(axum::http::header::CONTENT_TYPE),
+                        authorization: header(axum::http::header::AUTHORIZATION),
+                        body: request_body,
+                    });
+                (status, body)
+            }
+        },
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("serve");
+    });
+    (format!("http://{addr}"), requests)
+}
@@ -1,2 +1,3 @@
+pub(crate) mod agent;
 pub(crate) mod directives;
 pub(crate) mod push;
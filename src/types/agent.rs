@@ -0,0 +1,24 @@
+/// One `@magent` directive for an agent backend to answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentRequest {
+    pub doc_id: String,
+    /// The directive text after `@magent `.
+    pub directive: String,
+    /// 0-based line index of the directive.
+    pub line: usize,
+    /// The whole document, including earlier responses.
+    pub document: String,
+}
+
+impl AgentRequest {
+    /// The request as one plain-text prompt: the document, then the directive.
+    pub fn prompt(&self) -> String {
+        format!(
+            "Document {}:\n\n{}\n\nDirective on line {}: {}\n",
+            self.doc_id,
+            self.document.trim_end(),
+            self.line + 1,
+            self.directive
+        )
+    }
+}
@@ -1,12 +1,14 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use time::OffsetDateTime;
+use time::{Date, OffsetDateTime, PrimitiveDateTime};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DirectiveRegistries {
     pub users: HashMap<String, User>,
     pub subscriptions: HashMap<String, Vec<Subscription>>,
     pub notifications: Vec<Notification>,
+    #[serde(default)]
+    pub task_reminders: Vec<TaskReminder>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,19 +18,146 @@ pub struct User {
     pub email: String,
     #[serde(skip_serializing, default)]
     pub password_hash: String,
+    /// IANA time zone for local notification times, e.g. `Europe/Berlin`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tz: Option<String>,
+    /// Where notifications for the user go; web push when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<Channel>,
+    /// URL the `webhook` channel posts JSON to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<String>,
+    /// ntfy topic URL the `ntfy` channel posts to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ntfy: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A way of delivering notifications, as named in `channels` of a `/user`
+/// block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    Webpush,
+    Webhook,
+    Ntfy,
+    Email,
+}
+
+impl Channel {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "webpush" => Some(Channel::Webpush),
+            "webhook" => Some(Channel::Webhook),
+            "ntfy" => Some(Channel::Ntfy),
+            "email" => Some(Channel::Email),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Channel::Webpush => "webpush",
+            Channel::Webhook => "webhook",
+            Channel::Ntfy => "ntfy",
+            Channel::Email => "email",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Subscription {
     pub endpoint: String,
     pub p256dh: String,
     pub auth: String,
+    /// The document holding the `/subscription` block; empty for
+    /// subscriptions that did not come from a document.
+    #[serde(default)]
+    pub doc_id: String,
+}
+
+/// Where one notification goes: a browser's web push subscription, or the
+/// URL or address a `/user` block gives for another channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryTarget {
+    WebPush(Subscription),
+    Webhook(String),
+    Ntfy(String),
+    Email(String),
+}
+
+impl DeliveryTarget {
+    pub fn channel(&self) -> Channel {
+        match self {
+            DeliveryTarget::WebPush(_) => Channel::Webpush,
+            DeliveryTarget::Webhook(_) => Channel::Webhook,
+            DeliveryTarget::Ntfy(_) => Channel::Ntfy,
+            DeliveryTarget::Email(_) => Channel::Email,
+        }
+    }
+
+    /// The push service URL, webhook URL, ntfy topic URL or email address.
+    pub fn endpoint(&self) -> &str {
+        match self {
+            DeliveryTarget::WebPush(subscription) => &subscription.endpoint,
+            DeliveryTarget::Webhook(url) | DeliveryTarget::Ntfy(url) => url,
+            DeliveryTarget::Email(address) => address,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Notification {
     pub to: Vec<String>,
+    /// The first time the notification goes out.
     pub at: OffsetDateTime,
     pub message: String,
     pub doc_id: String,
+    /// Line of the block the notification comes from, 1-based; 0 for
+    /// notifications that do not come from a block.
+    #[serde(default)]
+    pub line: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat: Option<Repeat>,
+    /// No occurrences after this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<OffsetDateTime>,
+    /// IANA time zone the notification repeats in; UTC when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tz: Option<String>,
+    /// Times written without an offset in a block without `tz`, until they
+    /// are resolved in each recipient's time zone.
+    #[serde(skip)]
+    pub pending_local: Option<LocalTimes>,
+}
+
+/// The `at` and `until` values that were written as wall clock times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalTimes {
+    pub at: Option<PrimitiveDateTime>,
+    pub until: Option<PrimitiveDateTime>,
+}
+
+/// How a notification repeats after `at`, as written in the `/notify` block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Repeat {
+    /// `hourly`, `daily`, `weekdays`, `weekly`, `monthly` or `yearly`.
+    Every(String),
+    /// A five-field cron expression.
+    Cron(String),
+    /// An RFC 5545 recurrence rule.
+    Rrule(String),
+}
+
+/// An open task with a due date that mentions the users to remind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskReminder {
+    pub to: Vec<String>,
+    pub due: Date,
+    pub message: String,
+    pub doc_id: String,
+    pub task_index: usize,
+    /// Line of the task, 1-based.
+    #[serde(default)]
+    pub line: usize,
 }
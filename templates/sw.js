@@ -17,6 +17,7 @@ const STATIC_ASSETS = [
   '/static/features/abc_render.js',
   '/static/features/palette.js',
   '/static/features/fuzzy.js',
+  '/static/features/pdf_page.js',
   '/static/manifest.json',
   '/sw.js'
 ];
@@ -86,6 +87,11 @@ self.addEventListener('fetch', event => {
     return;
   }
 
+  // Leave live event streams to the browser
+  if (request.headers.get('Accept') === 'text/event-stream') {
+    return;
+  }
+
   const isDocument = request.destination === 'document';
   const networkFirst = (isDocument || url.pathname.startsWith('/d/')) && !AUTH_ENABLED;
 